@@ -0,0 +1,168 @@
+//! Parser throughput benchmarks, run with `cargo bench`.
+//!
+//! The inputs are generated deterministically so results are comparable across runs,
+//! see `benches/README.md` for baseline numbers.
+
+use codebank::{BankStrategy, CodeBank, LanguageParser, RustParser};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+
+/// A flat file with many documented structs, impls and functions
+fn large_file(items: usize) -> String {
+    let mut source =
+        String::from("//! A large generated file\n\nuse std::collections::HashMap;\n\n");
+    for i in 0..items {
+        write!(
+            source,
+            r#"
+/// Record number {i}
+#[derive(Debug, Clone, Default)]
+pub struct Record{i} {{
+    /// The identifier
+    pub id: u64,
+    /// The attributes
+    pub attributes: HashMap<String, String>,
+    cache: Option<Vec<u8>>,
+}}
+
+impl Record{i} {{
+    /// Create a new record
+    pub fn new(id: u64) -> Self {{
+        Self {{ id, ..Default::default() }}
+    }}
+
+    fn checksum(&self) -> u64 {{
+        self.attributes.len() as u64 ^ self.id
+    }}
+}}
+
+/// Process record number {i}
+pub fn process_{i}(record: &Record{i}) -> Option<u64> {{
+    record.cache.as_ref().map(|cache| cache.len() as u64 + record.checksum())
+}}
+"#
+        )
+        .unwrap();
+    }
+    source
+}
+
+/// Modules nested `depth` levels deep, each with a few items
+fn nested_modules(depth: usize) -> String {
+    let mut source = String::new();
+    for level in 0..depth {
+        let indent = "    ".repeat(level);
+        write!(
+            source,
+            "{indent}/// Module at level {level}\n{indent}pub mod level_{level} {{\n\
+             {indent}    /// A function at level {level}\n\
+             {indent}    pub fn run_{level}() -> usize {{ {level} }}\n\
+             {indent}    pub struct Item{level};\n\
+             {indent}    impl Item{level} {{ pub fn get(&self) -> usize {{ {level} }} }}\n"
+        )
+        .unwrap();
+    }
+    for level in (0..depth).rev() {
+        writeln!(source, "{}}}", "    ".repeat(level)).unwrap();
+    }
+    source
+}
+
+/// Traits and impls with many generic parameters, bounds and where clauses
+fn heavy_generics(items: usize) -> String {
+    let mut source = String::from("use std::fmt::Debug;\nuse std::hash::Hash;\n\n");
+    for i in 0..items {
+        write!(
+            source,
+            r#"
+/// A generic store number {i}
+pub trait Store{i}<K, V>: Send + Sync
+where
+    K: Hash + Eq + Clone + Debug,
+    V: Clone + Default,
+{{
+    /// Fetch a value
+    fn fetch<'a, Q>(&'a self, key: &Q) -> Option<&'a V>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Hash + Eq + ?Sized;
+}}
+
+pub struct Cache{i}<K, V, const N: usize = 16> {{
+    entries: Vec<(K, V)>,
+}}
+
+impl<K, V, const N: usize> Store{i}<K, V> for Cache{i}<K, V, N>
+where
+    K: Hash + Eq + Clone + Debug + Send + Sync,
+    V: Clone + Default + Send + Sync,
+{{
+    fn fetch<'a, Q>(&'a self, key: &Q) -> Option<&'a V>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {{
+        self.entries.iter().find(|(k, _)| k.borrow() == key).map(|(_, v)| v)
+    }}
+}}
+"#
+        )
+        .unwrap();
+    }
+    source
+}
+
+fn inputs() -> Vec<(&'static str, String)> {
+    vec![
+        ("large_file", large_file(200)),
+        ("nested_modules", nested_modules(32)),
+        ("heavy_generics", heavy_generics(50)),
+    ]
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_str");
+    let mut parser = RustParser::try_new().unwrap();
+    for (name, source) in inputs() {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &source, |b, source| {
+            b.iter(|| {
+                parser
+                    .parse_str(black_box(source), Path::new("bench.rs"))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    let code_bank = CodeBank::try_new().unwrap();
+    let sources: BTreeMap<PathBuf, String> = inputs()
+        .into_iter()
+        .map(|(name, source)| (PathBuf::from(format!("{name}.rs")), source))
+        .collect();
+    let bytes: usize = sources.values().map(String::len).sum();
+    group.throughput(Throughput::Bytes(bytes as u64));
+    for strategy in [BankStrategy::Default, BankStrategy::Summary] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{strategy:?}")),
+            &strategy,
+            |b, strategy| {
+                b.iter(|| {
+                    code_bank
+                        .generate_from_sources(black_box(&sources), *strategy)
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_generate);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+using System;
+using System.Collections.Generic;
+
+namespace Acme.Billing
+{
+    using System.Linq;
+
+    /// <summary>
+    /// Represents a customer invoice.
+    /// </summary>
+    [Serializable]
+    public class Invoice : IPayable
+    {
+        private readonly List<string> _lines = new();
+
+        /// <summary>The invoice number.</summary>
+        public int Number { get; set; }
+
+        public decimal Total { get; private set; }
+
+        /// <summary>Adds a line.</summary>
+        /// <param name="line">The line text.</param>
+        public void AddLine(string line) { _lines.Add(line); }
+
+        internal static Invoice Empty() => new Invoice();
+
+        public Invoice(int number) { Number = number; }
+    }
+
+    public interface IPayable
+    {
+        /// <summary>Pays the amount.</summary>
+        void Pay(decimal amount);
+    }
+
+    public enum Status { Draft, Sent = 2, Paid }
+
+    public record Payment(decimal Amount, DateTime At);
+
+    internal struct Money { public decimal Value; }
+}
@@ -0,0 +1,67 @@
+<?php
+
+namespace Acme\Billing;
+
+use Acme\Support\Money;
+use function Acme\Support\format_amount;
+
+/**
+ * Represents a customer invoice.
+ */
+#[Entity]
+abstract class Invoice extends Document implements Payable
+{
+    use HasTimestamps;
+
+    /** The invoice number. */
+    public int $number;
+
+    private array $lines = [], $notes = [];
+
+    protected ?Money $total = null;
+
+    const PREFIX = 'INV';
+
+    /**
+     * Adds a line.
+     *
+     * @param string $line The line text.
+     */
+    public function addLine(string $line): void
+    {
+        $this->lines[] = $line;
+    }
+
+    private static function normalize(string $line): string { return trim($line); }
+
+    function legacy() {}
+
+    abstract protected function render(): string;
+}
+
+interface Payable
+{
+    /** Pays the amount. */
+    public function pay(Money $amount): bool;
+}
+
+trait HasTimestamps
+{
+    protected ?int $createdAt = null;
+
+    public function touch(): void {}
+}
+
+enum Status: string
+{
+    case Draft = 'draft';
+    case Paid = 'paid';
+}
+
+/**
+ * Formats an invoice number.
+ */
+function invoice_number(int $number): string
+{
+    return Invoice::PREFIX . $number;
+}
@@ -83,6 +83,7 @@ mod private_module {
 }
 
 /// This is a public function with documentation
+#[must_use = "Return value should be used"]
 pub fn public_function() -> String {
     "Hello, world!".to_string()
 }
@@ -0,0 +1,57 @@
+//! Fixture for associated types and constants of traits and impls
+
+/// A shape with associated items
+pub trait Shape {
+    /// The type of the computed area
+    type Output;
+
+    /// The number of sides
+    const SIDES: u32 = 0;
+
+    /// Compute the area
+    fn area(&self) -> Self::Output;
+}
+
+/// A square
+pub struct Square(pub f64);
+
+impl Shape for Square {
+    /// Areas of squares are floats
+    type Output = f64;
+
+    /// Squares have four sides
+    const SIDES: u32 = 4;
+
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Square {
+    /// The unit square
+    pub const UNIT: Square = Square(1.0);
+
+    const SCALE: f64 = 1.0;
+}
+
+/// An iterator lending items borrowed from itself
+pub trait LendingIterator {
+    /// The items, borrowed for `'a`
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// Advance the iterator
+    fn next<'a>(&'a mut self) -> Option<Self::Item<'a>>;
+}
+
+/// A lending iterator over windows of a buffer
+pub struct Windows(Vec<u8>);
+
+impl LendingIterator for Windows {
+    type Item<'a> = &'a [u8] where Self: 'a;
+
+    fn next<'a>(&'a mut self) -> Option<Self::Item<'a>> {
+        Some(&self.0)
+    }
+}
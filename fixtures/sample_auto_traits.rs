@@ -0,0 +1,24 @@
+//! Fixture for impl blocks with manual auto-trait control
+
+/// A type shared across threads through a raw pointer
+pub struct Foo {
+    ptr: *mut u8,
+}
+
+// SAFETY: access to `ptr` is synchronized by the owner
+unsafe impl Send for Foo {}
+
+/// A type that must never be shared between threads
+pub struct Bar;
+
+impl !Sync for Bar {}
+
+/// A marker trait implemented unsafely
+pub unsafe trait Marker {}
+
+unsafe impl Marker for Bar {}
+
+/// A fixed-size buffer
+pub struct Buffer<const N: usize>([u8; N]);
+
+unsafe impl Sync for Buffer<{ 4 * 1024 }> {}
@@ -0,0 +1,34 @@
+//! Fixture for const functions
+
+/// The largest supported size
+pub const fn max_size() -> usize {
+    1024
+}
+
+/// Not const-evaluable
+pub fn runtime_size() -> usize {
+    std::env::args().count()
+}
+
+/// Reads raw memory at compile time
+pub const unsafe fn read(ptr: *const u8) -> u8 {
+    unsafe { *ptr }
+}
+
+/// A fixed-capacity buffer
+pub struct Buffer {
+    len: usize,
+}
+
+impl Buffer {
+    /// An empty buffer
+    pub const fn new() -> Self {
+        Self { len: 0 }
+    }
+
+    /// Whether the buffer is empty, a `const` item inside does not make it const
+    pub fn is_empty(&self) -> bool {
+        const EMPTY: usize = 0;
+        self.len == EMPTY
+    }
+}
@@ -0,0 +1,21 @@
+//! Constants and statics
+
+/// The maximum number of retries
+pub const MAX_RETRIES: u32 = 3;
+
+/// The default greeting
+#[allow(dead_code)]
+pub static GREETING: &str = "hello";
+
+const BUFFER_SIZE: usize = 4 * 1024;
+
+pub(crate) const TABLE: [u8; 4] = [1, 2, 3, 4];
+
+pub static mut COUNTER: usize = 0;
+
+pub mod limits {
+    /// The largest accepted payload
+    pub const MAX_PAYLOAD: usize = 1 << 20;
+
+    static INTERNAL: u8 = 0;
+}
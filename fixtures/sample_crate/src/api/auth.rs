@@ -0,0 +1,3 @@
+pub(crate) fn token() -> String {
+    String::new()
+}
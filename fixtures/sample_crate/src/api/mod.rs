@@ -0,0 +1,23 @@
+//! Public API
+
+pub mod v1;
+mod auth;
+
+/// An API client
+pub struct Client;
+
+impl Client {
+    /// Create a client authenticated with a fresh token
+    pub fn new() -> Self {
+        let _token = auth::token();
+        Client
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Something that can be sent by a [`Client`]
+pub trait Request {
+    /// The path of the endpoint
+    fn path(&self) -> &str;
+}
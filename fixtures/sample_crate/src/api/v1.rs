@@ -0,0 +1,34 @@
+//! Version 1 of the API
+
+use super::{Client, Request};
+
+/// Fetch the root resource
+pub fn get() {}
+
+/// The request for the status endpoint
+pub struct Status {
+    /// Whether to include details
+    pub verbose: bool,
+    retries: u8,
+}
+
+impl Request for Status {
+    fn path(&self) -> &str {
+        "/v1/status"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_path() {
+        let status = Status {
+            verbose: false,
+            retries: 0,
+        };
+        assert_eq!(status.path(), "/v1/status");
+        let _ = Client::new();
+    }
+}
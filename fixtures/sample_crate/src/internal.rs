@@ -0,0 +1 @@
+pub(crate) struct State;
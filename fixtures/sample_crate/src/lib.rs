@@ -0,0 +1,18 @@
+//! A sample crate with nested modules
+
+pub mod api;
+pub(crate) mod utils;
+mod internal;
+
+/// Inline module
+pub mod prelude {
+    pub use crate::api::Client;
+
+    pub(super) mod hidden {}
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn it_works() {}
+}
@@ -0,0 +1,5 @@
+pub mod strings {
+    pub fn trim(s: &str) -> &str {
+        s.trim()
+    }
+}
@@ -0,0 +1,26 @@
+/// Old entry point
+#[deprecated(since = "0.2.0", note = "use `run` instead")]
+pub fn start() {}
+
+#[deprecated(note = "use `run` and drop the handle instead")]
+pub fn stop() {}
+
+#[deprecated = "use `Config` instead"]
+pub struct Settings {
+    pub verbose: bool,
+}
+
+#[deprecated = "use `Config { verbose = true }` instead"]
+pub struct Options;
+
+#[deprecated]
+pub trait Legacy {}
+
+#[deprecated(since = "0.3.0", note = "use `MAX_JOBS` instead")]
+pub const MAX_THREADS: usize = 8;
+
+#[deprecated]
+pub type Handle = u64;
+
+/// The replacement entry point
+pub fn run() {}
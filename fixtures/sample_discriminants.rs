@@ -0,0 +1,16 @@
+/// The status of an account, as stored in the database
+#[repr(u8)]
+pub enum Status {
+    /// The account can sign in
+    Active = 1,
+    Inactive = 2,
+    Banned = 1 << 4,
+}
+
+/// Frame types of the wire protocol
+#[repr(C)]
+pub enum FrameType {
+    Data = 0x0,
+    Headers = 0x1,
+    Settings,
+}
@@ -0,0 +1,40 @@
+//! Fixture with a mix of documented and undocumented public items
+
+/// Documented public function
+pub fn documented() {}
+
+pub fn undocumented() {}
+
+/// Another documented function
+pub fn also_documented() {}
+
+// Private functions are not counted
+fn private_helper() {}
+
+/// Documented struct
+pub struct Documented;
+
+impl Documented {
+    /// Documented method
+    pub fn start(&self) {}
+
+    pub fn stop(&self) {}
+
+    // Private methods are not counted
+    fn reset(&self) {}
+}
+
+pub struct Undocumented;
+
+pub trait UndocumentedTrait {
+    /// Documented trait method
+    fn describe(&self);
+}
+
+/// Documented module
+pub mod api {
+    /// Documented function in a module
+    pub fn nested() {}
+
+    pub mod inner {}
+}
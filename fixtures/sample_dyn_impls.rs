@@ -0,0 +1,43 @@
+//! Fixture for impl blocks on trait objects and unsized type parameters
+
+use std::fmt;
+
+/// A trait used as a trait object
+pub trait Other {
+    /// The name of the implementor
+    fn name(&self) -> &str;
+}
+
+/// A trait implemented for trait objects and boxes
+pub trait Describe {
+    /// Describe the value
+    fn describe(&self) -> String;
+}
+
+impl Describe for dyn Other + Send {
+    fn describe(&self) -> String {
+        self.name().to_string()
+    }
+}
+
+impl<T: ?Sized> Describe for Box<T>
+where
+    T: Describe,
+{
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+impl fmt::Debug for dyn Other + Send + Sync + 'static {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl dyn Other {
+    /// Whether the name is empty
+    pub fn is_anonymous(&self) -> bool {
+        self.name().is_empty()
+    }
+}
@@ -0,0 +1,9 @@
+// Colors of a traffic light
+enum Color { RED = 1, GREEN = 2, BLUE };
+
+// Flags exchanged over FFI
+enum class Flags : unsigned int {
+    NONE = 0,
+    READ = 1 << 0,
+    WRITE = 1 << 1,
+};
@@ -0,0 +1,48 @@
+//! Fixture for impl blocks with concrete and mixed generic arguments
+
+use std::collections::HashMap;
+
+/// A trait generic over its input
+pub trait GenericTrait<T> {
+    /// Convert the input
+    fn convert(&self, value: T) -> T;
+}
+
+/// A type with one generic parameter
+pub struct Foo<T>(T);
+
+/// A type with two generic parameters
+pub struct Pair<A, B>(A, B);
+
+impl GenericTrait<String> for Foo<u32> {
+    fn convert(&self, value: String) -> String {
+        value
+    }
+}
+
+impl<T: Clone> GenericTrait<Vec<T>> for Pair<T, HashMap<String, Vec<T>>> {
+    fn convert(&self, value: Vec<T>) -> Vec<T> {
+        value
+    }
+}
+
+impl<const N: usize> GenericTrait<[u8; N]> for Foo<[u8; N]> {
+    fn convert(&self, value: [u8; N]) -> [u8; N] {
+        value
+    }
+}
+
+impl Foo<Option<&'static str>> {
+    /// The wrapped value, if any
+    pub fn get(&self) -> Option<&'static str> {
+        self.0
+    }
+}
+
+impl<'a, B> std::ops::Deref for Pair<&'a str, B> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
@@ -0,0 +1,12 @@
+#![deny(warnings)]
+//! Crate docs
+//! spanning two lines.
+#![allow(dead_code)]
+/*!
+ * Block docs after an inner attribute.
+ */
+
+/// Returns the answer.
+pub fn answer() -> u32 {
+    42
+}
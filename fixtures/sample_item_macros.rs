@@ -0,0 +1,24 @@
+use bitflags::bitflags;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+bitflags! {
+    /// Permissions of a file
+    pub struct Permissions: u32 {
+        const READ = 0b001;
+        const WRITE = 0b010;
+        const EXECUTE = 0b100;
+    }
+}
+
+lazy_static! {
+    /// Default configuration values
+    pub static ref DEFAULTS: HashMap<&'static str, u32> = HashMap::new();
+}
+
+// Unknown macros are not item definitions
+println!("not an item");
+
+pub mod flags {
+    bitflags::bitflags!(pub struct Mode: u8 { const FAST = 1; });
+}
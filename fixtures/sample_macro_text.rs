@@ -0,0 +1,21 @@
+//! `macro` lines inside string literals and comments are not macros 2.0 definitions
+
+/// Prints the usage
+pub fn help() -> &'static str {
+    "usage:
+macro name {
+    body
+}"
+}
+
+/*
+macro commented {
+    1
+}
+*/
+pub fn after_comment() {}
+
+/// A real one
+pub macro real($x:expr) {
+    $x
+}
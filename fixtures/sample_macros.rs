@@ -0,0 +1,40 @@
+//! Fixture covering both `macro_rules!` and macros 2.0 definitions.
+
+/// An exported declarative macro
+#[macro_export]
+macro_rules! exported_macro {
+    ($x:expr) => {
+        $x + 1
+    };
+}
+
+/// A crate-local declarative macro
+macro_rules! local_macro {
+    () => {};
+}
+
+/// A public macros 2.0 definition
+pub macro public_macro($x:expr) {
+    $x * 2
+}
+
+/// A private macros 2.0 definition
+macro private_macro($x:expr) {
+    $x
+}
+
+/// A function following the macros
+pub fn after_macros() -> i32 {
+    1
+}
+
+pub mod nested {
+    /// A crate-visible macro inside a module
+    pub(crate) macro crate_macro() {}
+
+    /// Exported from within a module
+    #[macro_export]
+    macro_rules! nested_exported {
+        () => {};
+    }
+}
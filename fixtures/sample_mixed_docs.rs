@@ -0,0 +1,20 @@
+/** block doc */
+/// line doc
+pub fn f() {}
+
+/// line doc first
+/**
+ * then a block doc
+ */
+pub fn g() {}
+
+/** Text on the opening line,
+ *  and on the next one. */
+#[inline]
+/// Line doc after an attribute.
+pub fn h() {}
+
+/* Not a doc comment */
+/***************/
+/// Only this line is a doc.
+pub fn i() {}
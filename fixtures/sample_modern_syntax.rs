@@ -0,0 +1,39 @@
+/// A buffer whose size defaults to a block expression
+pub struct Buffer<const N: usize = { 4 * 1024 }> {
+    data: [u8; N],
+}
+
+/// A tuple struct with an array field
+pub struct Pair(pub u8, pub [u8; 4]);
+
+/// Returns the first word, using `let-else`
+pub fn first_word(text: &str) -> &str {
+    let Some(word) = text.split_whitespace().next() else {
+        return "";
+    };
+    word
+}
+
+/// Sums two options with a let chain
+pub fn both(a: Option<u8>, b: Option<u8>) -> u8 {
+    if let Some(a) = a && let Some(b) = b { a + b } else { 0 }
+}
+
+/// Returns a buffer sized by a const generic expression
+pub fn chunk() -> Buffer<{ 2 * 1024 }> {
+    Buffer { data: [0; 2048] }
+}
+
+/// Counts the urls from nested async blocks
+pub async fn fetch_all(urls: Vec<String>) -> usize {
+    let task = async move {
+        let inner = async { urls.len() };
+        inner.await
+    };
+    task.await
+}
+
+pub trait Codec {
+    /// A declaration whose return type contains a `;`
+    fn header() -> [u8; 4];
+}
@@ -0,0 +1,46 @@
+pub struct Token;
+
+// Impls in an unnamed const, as generated by derive macros
+const _: () = {
+    impl Clone for Token {
+        fn clone(&self) -> Self {
+            Token
+        }
+    }
+};
+
+pub fn register() {
+    struct Local;
+
+    impl Local {
+        fn run(&self) {}
+    }
+
+    impl Default for Token {
+        fn default() -> Self {
+            Token
+        }
+    }
+
+    Local.run();
+}
+
+pub mod api {
+    pub fn debug() {
+        impl std::fmt::Debug for super::Token {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("Token")
+            }
+        }
+    }
+
+    static MARKER: () = {
+        impl Copy for super::Token {}
+    };
+
+    pub struct Handle;
+
+    impl Handle {
+        pub fn close(&self) {}
+    }
+}
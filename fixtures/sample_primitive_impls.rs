@@ -0,0 +1,34 @@
+/// A value with a stable identifier
+pub trait MyTrait {
+    fn id(&self) -> u8;
+}
+
+impl MyTrait for u32 {
+    fn id(&self) -> u8 {
+        1
+    }
+}
+
+impl MyTrait for () {
+    fn id(&self) -> u8 {
+        2
+    }
+}
+
+impl<T> MyTrait for [T] {
+    fn id(&self) -> u8 {
+        3
+    }
+}
+
+impl<A, B> MyTrait for (A, B) {
+    fn id(&self) -> u8 {
+        4
+    }
+}
+
+impl MyTrait for &str {
+    fn id(&self) -> u8 {
+        5
+    }
+}
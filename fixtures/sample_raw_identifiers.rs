@@ -0,0 +1,25 @@
+//! Bindings using keywords as identifiers
+
+/// Run the future to completion
+pub fn r#async(r#in: u32) -> u32 {
+    r#in
+}
+
+/// A type descriptor
+pub struct r#type {
+    pub r#ref: String,
+}
+
+impl r#type {
+    pub fn r#match(&self, r#type: &str) -> bool {
+        self.r#ref == r#type
+    }
+}
+
+pub trait r#trait {
+    fn r#move(&self);
+}
+
+pub mod r#mod {
+    pub fn r#yield() {}
+}
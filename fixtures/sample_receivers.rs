@@ -0,0 +1,43 @@
+use std::pin::Pin;
+
+pub struct Task;
+
+impl Task {
+    pub fn new() -> Self {
+        Task
+    }
+
+    pub fn by_ref(&self) {}
+
+    pub fn by_mut(&mut self) {}
+
+    pub fn by_value(self) {}
+
+    pub fn by_mut_value(mut self) {}
+
+    pub fn with_lifetime<'a>(&'a self) -> &'a Self {
+        self
+    }
+
+    pub fn boxed(self: Box<Self>) {}
+
+    pub fn pinned(self: Pin<&mut Self>) {}
+
+    pub fn mut_boxed(mut self: Box<Self>) {}
+}
+
+pub trait Poll {
+    fn create() -> Self
+    where
+        Self: Sized;
+
+    fn poll(self: Pin<&mut Self>) -> bool;
+
+    fn finish(self)
+    where
+        Self: Sized,
+    {
+    }
+}
+
+pub fn free(task: Task) {}
@@ -0,0 +1,29 @@
+use std::cell::{Cell, RefCell};
+
+/// A regular static
+pub static GREETING: &str = "hello";
+
+/// Global counter
+pub static mut COUNTER: usize = 0;
+
+static mut PRIVATE_COUNTER: u64 = 0;
+
+thread_local! {
+    pub static DEPTH: Cell<u32> = Cell::new(0);
+    static BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+}
+
+pub mod registry {
+    std::thread_local!(pub static NAMES: RefCell<Vec<String>> = RefCell::new(Vec::new()));
+
+    pub fn register(name: &str) {
+        NAMES.with(|names| names.borrow_mut().push(name.to_string()));
+    }
+}
+
+pub fn bump() -> usize {
+    unsafe {
+        COUNTER += 1;
+        COUNTER
+    }
+}
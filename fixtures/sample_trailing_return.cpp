@@ -0,0 +1,32 @@
+#include <cstddef>
+#include <utility>
+#include <vector>
+
+/// Adds two numbers
+auto add(int a, int b) -> int {
+    return a + b;
+}
+
+auto make_pair(int first, int second) -> std::pair<int, int> {
+    return {first, second};
+}
+
+auto empty_values(int count) -> decltype(std::vector<int>{}) {
+    return std::vector<int>(count);
+}
+
+class Buffer {
+public:
+    auto size(int scale) const -> std::size_t {
+        return length * scale;
+    }
+
+private:
+    std::size_t length;
+};
+
+namespace math {
+auto square(int x) -> int {
+    return x * x;
+}
+}
@@ -0,0 +1,27 @@
+//! Type aliases
+
+use std::collections::HashMap;
+
+/// The result of the crate's operations
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A boxed callback
+#[allow(type_alias_bounds)]
+pub type Callback<F>
+where
+    F: Fn(u32) -> bool,
+= Box<F>;
+
+/// Handlers indexed by name
+pub type Registry<K, V> = HashMap<K, V> where K: std::hash::Hash + Eq;
+
+type Cache = HashMap<String, Vec<u8>>;
+
+pub struct Error;
+
+pub mod ids {
+    /// A user id
+    pub type UserId = u64;
+
+    pub(crate) type RawId = [u8; 16];
+}
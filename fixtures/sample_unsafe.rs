@@ -0,0 +1,35 @@
+//! Fixture for unsafe functions and traits
+
+/// Types whose all-zero bit pattern is a valid value
+///
+/// # Safety
+///
+/// Implementors must be valid when zeroed.
+pub unsafe trait Zeroable {}
+
+unsafe impl Zeroable for u32 {}
+
+/// A safe trait
+pub trait Reset {
+    /// Reset the value
+    fn reset(&mut self);
+}
+
+/// Read a value from a raw pointer
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads and aligned.
+pub unsafe fn read<T: Copy>(ptr: *const T) -> T {
+    unsafe { *ptr }
+}
+
+/// An unsafe foreign function usable in constants
+pub const unsafe extern "C" fn zeroed() -> u32 {
+    0
+}
+
+/// A safe function with an unsafe block
+pub fn first(values: &[u32]) -> u32 {
+    unsafe { *values.as_ptr() }
+}
@@ -0,0 +1,27 @@
+package shapes
+
+// Foo is exported
+func Foo() {}
+
+func foo() {}
+
+// Bar is an exported type with an unexported field
+type Bar struct {
+	Bar string
+	bar int
+}
+
+// Baz is an exported method
+func (b *Bar) Baz() {}
+
+func (b *Bar) baz() {}
+
+type qux struct {
+	Quux int
+}
+
+// Shape is implemented within the package only, because of scale
+type Shape interface {
+	Area() float64
+	scale(factor float64)
+}
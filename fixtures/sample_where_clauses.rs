@@ -0,0 +1,38 @@
+//! Fixture for impl blocks with complex where clauses
+
+use std::fmt::Debug;
+
+/// A wrapper over any iterator
+pub struct Wrapper<T>(T);
+
+/// A trait with a lifetime parameter
+pub trait Visit<'a> {
+    /// Visit a borrowed value
+    fn visit(&self, value: &'a str);
+}
+
+/// A trait implemented for iterators of cloneable items
+pub trait Summarize {
+    /// Summarize the value
+    fn summarize(&self) -> String;
+}
+
+impl<T> Summarize for Wrapper<T>
+where
+    T: Iterator + Clone,
+    T::Item: Clone + Debug,
+{
+    fn summarize(&self) -> String {
+        format!("{:?}", self.0.clone().collect::<Vec<_>>())
+    }
+}
+
+impl<T> Wrapper<T>
+where
+    for<'a> T: Visit<'a>,
+{
+    /// Visit every string with the wrapped visitor
+    pub fn visit_all(&self, values: &[&str]) {
+        values.iter().for_each(|value| self.0.visit(value));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 /// Documentation for the struct
 pub struct StructWithFields {
     /// A public field documentation
@@ -25,3 +27,11 @@ impl StructWithFields {
         }
     }
 }
+
+/// Fields whose types contain commas and parentheses
+pub struct GenericFields {
+    pub entries: HashMap<String, Vec<u8>>,
+    pub handler: Box<dyn Fn(u32) -> Result<(), Error> + Send + 'static>,
+    pub pairs: Vec<(u8, u16)>,
+    pub reader: fn(&[u8]) -> impl Iterator<Item = (usize, u8)>,
+}
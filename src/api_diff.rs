@@ -0,0 +1,158 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// An item whose signature differs between two versions of an API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedItem {
+    /// The qualified name of the item, e.g. `mycrate::api::send`
+    pub name: String,
+    /// The signature in the old version
+    pub old_signature: String,
+    /// The signature in the new version
+    pub new_signature: String,
+}
+
+/// The differences between the public signatures of two versions of a crate, see
+/// [`crate::CodeBank::api_diff`].
+///
+/// Items are matched by their qualified name, without generics and parameters.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::ApiDiff;
+///
+/// let old = ["app::run(config: Config)".to_string(), "app::stop()".to_string()];
+/// let new = [
+///     "app::run(config: Config, verbose: bool)".to_string(),
+///     "app::restart()".to_string(),
+/// ];
+///
+/// let diff = ApiDiff::new(&old, &new);
+/// assert_eq!(diff.added, ["app::restart()"]);
+/// assert_eq!(diff.removed, ["app::stop()"]);
+/// assert_eq!(diff.changed[0].name, "app::run");
+/// assert_eq!(diff.changed[0].new_signature, "app::run(config: Config, verbose: bool)");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ApiDiff {
+    /// Signatures only found in the new version
+    pub added: Vec<String>,
+    /// Signatures only found in the old version
+    pub removed: Vec<String>,
+    /// Items found in both versions with different signatures
+    pub changed: Vec<ChangedItem>,
+}
+
+impl ApiDiff {
+    /// Compare the `old` and `new` signatures, as listed by
+    /// [`crate::CodeBank::public_signatures`]
+    pub fn new<S: AsRef<str>>(old: &[S], new: &[S]) -> Self {
+        let mut items: BTreeMap<&str, (Vec<&str>, Vec<&str>)> = BTreeMap::new();
+        for signature in old.iter().map(AsRef::as_ref) {
+            items
+                .entry(item_name(signature))
+                .or_default()
+                .0
+                .push(signature);
+        }
+        for signature in new.iter().map(AsRef::as_ref) {
+            items
+                .entry(item_name(signature))
+                .or_default()
+                .1
+                .push(signature);
+        }
+
+        let mut diff = Self::default();
+        for (name, (mut old, mut new)) in items {
+            let unchanged: Vec<&str> = old.iter().copied().filter(|s| new.contains(s)).collect();
+            old.retain(|signature| !unchanged.contains(signature));
+            new.retain(|signature| !unchanged.contains(signature));
+            // A single signature on each side is the same item, changed
+            if let ([old_signature], [new_signature]) = (old.as_slice(), new.as_slice()) {
+                diff.changed.push(ChangedItem {
+                    name: name.to_string(),
+                    old_signature: old_signature.to_string(),
+                    new_signature: new_signature.to_string(),
+                });
+                continue;
+            }
+            diff.removed.extend(old.iter().map(|s| s.to_string()));
+            diff.added.extend(new.iter().map(|s| s.to_string()));
+        }
+        diff
+    }
+
+    /// Whether both versions have the same signatures
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Render the diff as the `## API Diff` markdown block, showing the old and new
+    /// signatures of each changed item as a `diff` code block
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::from("## API Diff\n\n");
+        if self.is_empty() {
+            output.push_str("No changes\n");
+            return output;
+        }
+        for (title, signatures) in [("Added", &self.added), ("Removed", &self.removed)] {
+            if signatures.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("### {}\n\n", title));
+            for signature in signatures {
+                output.push_str(&format!("- `{}`\n", signature));
+            }
+            output.push('\n');
+        }
+        if !self.changed.is_empty() {
+            output.push_str("### Changed\n\n");
+            for item in &self.changed {
+                output.push_str(&format!(
+                    "#### `{}`\n\n```diff\n- {}\n+ {}\n```\n\n",
+                    item.name, item.old_signature, item.new_signature
+                ));
+            }
+        }
+        output
+    }
+}
+
+// The qualified name of the item of a signature, without generics and parameters, e.g.
+// `app::Parser::parse` for `app::Parser::parse<T: Read>(&self, input: T) -> Unit`
+fn item_name(signature: &str) -> &str {
+    let end = signature.find(['<', '(']).unwrap_or(signature.len());
+    signature[..end].trim_end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_diff_markdown() {
+        let old = [
+            "app::parse<T: Read>(input: T) -> Unit",
+            "app::Client::send(&self, request: Request)",
+            "app::version() -> &'static str",
+        ];
+        let new = [
+            "app::parse<T: Read>(input: T) -> Unit",
+            "app::Client::send(&self, request: Request, timeout: Duration)",
+        ];
+
+        let diff = ApiDiff::new(&old, &new);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, ["app::version() -> &'static str"]);
+        assert_eq!(
+            diff.to_markdown(),
+            "## API Diff\n\n### Removed\n\n- `app::version() -> &'static str`\n\n### Changed\n\n#### `app::Client::send`\n\n```diff\n- app::Client::send(&self, request: Request)\n+ app::Client::send(&self, request: Request, timeout: Duration)\n```\n\n"
+        );
+        assert_eq!(
+            ApiDiff::new(&old, &old).to_markdown(),
+            "## API Diff\n\nNo changes\n"
+        );
+    }
+}
@@ -1,70 +1,182 @@
 use crate::{
-    Bank, BankConfig, Error, Result,
+    ApiDiff, Bank, BankConfig, BankFormat, BankStrategy, DEFAULT_SHORTEN_PREFIXES, DocMode, Error,
+    FrontMatterFields, Granularity, Metrics, Result,
+    call_graph::call_graph,
+    module_tree::{
+        ModuleNode, filter_by_module, flatten_to_public_api, merge_module_files, module_paths,
+        public_signatures,
+    },
     parser::{
-        CppParser, FileUnit, GoParser, LanguageParser, LanguageType, PythonParser, RustParser,
-        TypeScriptParser, formatter::Formatter,
+        ConstUnit, FileUnit, FunctionUnit, ImplUnit, LanguageParser, LanguageType, MacroUnit,
+        ModuleUnit, ParserRegistry, Span, StructUnit, TraitUnit, TypeAliasUnit, Visibility,
+        exclude::{impl_type_name, is_item_pattern},
+        formatter::{FormatOptions, Formatter, FormatterRules, matches_file_pattern},
     },
 };
 use ignore::WalkBuilder;
+use rayon::{ThreadPoolBuilder, prelude::*};
 use regex::Regex;
+use serde::Serialize;
 use std::cell::OnceCell;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::{ffi::OsStr, path::Path};
+use std::panic::{self, AssertUnwindSafe};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The name of the files listing, in gitignore syntax, the paths left out of the code bank
+/// without affecting git, e.g. generated sources
+pub const CODEBANK_IGNORE: &str = ".codebankignore";
+
+/// The label of the files outside the directories of [`BankConfig::section_by_dir`]
+pub const DEFAULT_SECTION: &str = "Sources";
 
 #[allow(clippy::declare_interior_mutable_const)]
 const REGEX: OnceCell<Regex> = OnceCell::new();
 
 /// The code bank generator implementation
 pub struct CodeBank {
-    rust_parser: RustParser,
-    python_parser: PythonParser,
-    typescript_parser: TypeScriptParser,
-    c_parser: CppParser,
-    go_parser: GoParser,
+    registry: ParserRegistry,
+    // Parsers created so far, keyed by file extension
+    parsers: HashMap<String, Box<dyn LanguageParser>>,
+    // Maximum parse time of a single file
+    timeout: Option<Duration>,
 }
 
 impl CodeBank {
     /// Create a new code bank generator
     pub fn try_new() -> Result<Self> {
-        let rust_parser = RustParser::try_new()?;
-        let python_parser = PythonParser::try_new()?;
-        let typescript_parser = TypeScriptParser::try_new()?;
-        let c_parser = CppParser::try_new()?;
-        let go_parser = GoParser::try_new()?;
-
-        Ok(Self {
-            rust_parser,
-            python_parser,
-            typescript_parser,
-            c_parser,
-            go_parser,
-        })
+        Ok(Self::with_registry(ParserRegistry::new()))
+    }
+
+    /// Create a code bank generator using the parsers of `registry`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{CodeBank, FileUnit, LanguageParser, ParserRegistry, Result};
+    /// use std::path::Path;
+    ///
+    /// struct TextParser;
+    ///
+    /// impl LanguageParser for TextParser {
+    ///     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
+    ///         Ok(FileUnit::new(file_path.to_path_buf()))
+    ///     }
+    /// }
+    ///
+    /// let mut registry = ParserRegistry::new();
+    /// registry.register("txt", || Ok(Box::new(TextParser)));
+    /// let code_bank = CodeBank::with_registry(registry);
+    /// ```
+    pub fn with_registry(registry: ParserRegistry) -> Self {
+        Self {
+            registry,
+            parsers: HashMap::new(),
+            timeout: None,
+        }
     }
 
     /// Detect the language type from a file extension
     fn detect_language(&self, path: &Path) -> Option<LanguageType> {
-        match path.extension().and_then(OsStr::to_str) {
-            Some("rs") => Some(LanguageType::Rust),
-            Some("py") => Some(LanguageType::Python),
-            Some("ts") | Some("tsx") | Some("js") | Some("jsx") => Some(LanguageType::TypeScript),
-            Some("c") | Some("h") | Some("cpp") | Some("hpp") => Some(LanguageType::Cpp),
-            Some("go") => Some(LanguageType::Go),
-            _ => Some(LanguageType::Unknown),
-        }
+        Some(self.registry.language(path))
+    }
+
+    /// Get the parser registered for the file extension, creating it on first use
+    fn parser_for(&mut self, file_path: &Path) -> Result<Option<&mut Box<dyn LanguageParser>>> {
+        let (Some(ext), Some(factory)) = (
+            file_path.extension().and_then(OsStr::to_str),
+            self.registry.factory(file_path),
+        ) else {
+            return Ok(None);
+        };
+
+        let parser = match self.parsers.entry(ext.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut parser = factory()?;
+                parser.set_timeout(self.timeout);
+                entry.insert(parser)
+            }
+        };
+        Ok(Some(parser))
     }
 
     /// Parse a single file using the appropriate language parser
     fn parse_file(&mut self, file_path: &Path) -> Result<Option<FileUnit>> {
-        match self.detect_language(file_path) {
-            Some(LanguageType::Rust) => self.rust_parser.parse_file(file_path).map(Some),
-            Some(LanguageType::Python) => self.python_parser.parse_file(file_path).map(Some),
-            Some(LanguageType::TypeScript) => {
-                self.typescript_parser.parse_file(file_path).map(Some)
+        self.parse_guarded(file_path, |parser| parser.parse_file(file_path))
+    }
+
+    /// Parse in-memory source code using the parser matching the file extension
+    fn parse_source(&mut self, file_path: &Path, source: &str) -> Result<Option<FileUnit>> {
+        self.parse_guarded(file_path, |parser| parser.parse_str(source, file_path))
+    }
+
+    /// Run `parse` with the parser for the file, turning a parser panic or timeout into an
+    /// empty file unit that reports it as a parse error
+    fn parse_guarded(
+        &mut self,
+        file_path: &Path,
+        parse: impl FnOnce(&mut dyn LanguageParser) -> Result<FileUnit>,
+    ) -> Result<Option<FileUnit>> {
+        let Some(parser) = self.parser_for(file_path)? else {
+            return Ok(None);
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| parse(parser.as_mut()))) {
+            Ok(Err(Error::ParseTimeout(_))) => {
+                // A halted parse leaves the parser mid-way, drop it so the next file gets a new one
+                self.drop_parser(file_path);
+                let mut file_unit = FileUnit::new(file_path.to_path_buf());
+                file_unit.parse_errors.push((
+                    Span::default(),
+                    format!(
+                        "parsing timed out after {:?}",
+                        self.timeout.unwrap_or_default()
+                    ),
+                ));
+                Ok(Some(file_unit))
+            }
+            Ok(result) => result.map(Some),
+            Err(payload) => {
+                // The parser may be left in a broken state, drop it so the next file gets a new one
+                self.drop_parser(file_path);
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                let mut file_unit = FileUnit::new(file_path.to_path_buf());
+                file_unit
+                    .parse_errors
+                    .push((Span::default(), format!("parser panicked: {}", message)));
+                Ok(Some(file_unit))
             }
-            Some(LanguageType::Cpp) => self.c_parser.parse_file(file_path).map(Some),
-            Some(LanguageType::Go) => self.go_parser.parse_file(file_path).map(Some),
-            Some(LanguageType::Unknown) => Ok(None),
-            None => Ok(None),
+        }
+    }
+
+    fn drop_parser(&mut self, file_path: &Path) {
+        if let Some(ext) = file_path.extension().and_then(OsStr::to_str) {
+            self.parsers.remove(ext);
+        }
+    }
+
+    /// Check whether a file only contains tests, based on its name and the configured
+    /// (or per-language default) test file patterns
+    fn is_test_file(&self, file_path: &Path, config: &BankConfig) -> bool {
+        let Some(file_name) = file_path.file_name().and_then(OsStr::to_str) else {
+            return false;
+        };
+        match &config.test_file_patterns {
+            Some(patterns) => patterns
+                .iter()
+                .any(|pattern| matches_file_pattern(pattern, file_name)),
+            None => self
+                .detect_language(file_path)
+                .is_some_and(|lang| FormatterRules::for_language(lang).is_test_file(file_name)),
         }
     }
 
@@ -107,53 +219,627 @@ impl CodeBank {
 
 impl Bank for CodeBank {
     fn generate(&self, config: &BankConfig) -> Result<String> {
-        let root_dir = &config.root_dir;
+        self.generate_filtered(config, BankFormat::Markdown, |_| true)
+    }
+}
 
-        // Make sure the root directory exists
-        if !root_dir.exists() {
-            return Err(Error::DirectoryNotFound(root_dir.to_path_buf()));
+impl CodeBank {
+    /// Generate the code bank in the format selected by `config.format`.
+    ///
+    /// See [`BankFormat`] for how the strategy applies to each format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankFormat, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let mut config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+    /// config.format = BankFormat::Html;
+    ///
+    /// let content = code_bank.render(&config)?;
+    /// assert!(content.starts_with("<!DOCTYPE html>"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn render(&self, config: &BankConfig) -> Result<String> {
+        self.generate_filtered(config, config.format, |_| true)
+    }
+
+    /// Compute the documentation coverage metrics of the files selected by `config`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+    /// let metrics = code_bank.metrics(&config)?;
+    /// assert!(metrics.files > 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn metrics(&self, config: &BankConfig) -> Result<Metrics> {
+        let file_units = self.collect_file_units(config, |_| true)?;
+        Ok(Metrics::from_file_units(&file_units))
+    }
+
+    /// Render the module hierarchy of the Rust crate in `config.root_dir` as an indented
+    /// tree, with the visibility of every module.
+    ///
+    /// Modules come from `mod` declarations resolved to their files and from inline
+    /// `mod {}` blocks. Test modules are only shown by the `Default` strategy. The crate
+    /// root is `lib.rs` or `main.rs`, either in the root directory or in its `src`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let config = BankConfig::new("src", BankStrategy::NoTests, vec![]);
+    /// let tree = code_bank.module_tree(&config)?;
+    /// assert!(tree.starts_with("crate\n"));
+    /// assert!(tree.contains("── mod bank\n"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn module_tree(&self, config: &BankConfig) -> Result<String> {
+        let is_rust = |path: &Path| path.extension().is_some_and(|ext| ext == "rs");
+        let file_units = self.collect_file_units(config, is_rust)?;
+        let rules = FormatterRules::for_language(LanguageType::Rust);
+        let includes_tests = FormatOptions::from(config).includes_tests(&config.strategy);
+        let tree = ModuleNode::build(&config.root_dir, &file_units, |module| {
+            includes_tests || !rules.is_test_module(&module.name, &module.attributes)
+        })?;
+        Ok(tree.render())
+    }
+
+    /// List the signatures of the `pub` functions and methods of the Rust crate at
+    /// `config.root_dir`, one per entry and qualified by their module path, for grepping
+    /// the callable surface of a crate. Docs and bodies are left out whatever the strategy.
+    ///
+    /// Paths start with `config.crate_name`, by default the package name of the crate.
+    /// Only the files of `pub` modules are listed, items re-exported from private modules
+    /// are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let config = BankConfig::new("fixtures/sample_crate", BankStrategy::Summary, vec![]);
+    /// let signatures = code_bank.public_signatures(&config)?;
+    /// assert!(signatures.contains(&"sample_crate::api::Client::new() -> Self".to_string()));
+    /// // `utils` is `pub(crate)`
+    /// assert!(!signatures.iter().any(|signature| signature.contains("trim")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn public_signatures(&self, config: &BankConfig) -> Result<Vec<String>> {
+        let is_rust = |path: &Path| path.extension().is_some_and(|ext| ext == "rs");
+        let file_units = self.collect_file_units(config, is_rust)?;
+        public_signatures(&config.root_dir, config.crate_name.as_deref(), &file_units)
+    }
+
+    /// List the functions and methods of the Rust crate at `config.root_dir`, each with the
+    /// functions of the crate it calls, as an adjacency list of qualified names for
+    /// dependency analysis. Calls are resolved by name on a best-effort basis, unresolved
+    /// and ambiguous calls are left out. Tests are left out unless the strategy or
+    /// `config.include_tests` includes them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+    /// let graph = code_bank.call_graph(&config)?;
+    /// let (_, callees) = graph
+    ///     .iter()
+    ///     .find(|(name, _)| name == "codebank::bank::CodeBank::api_diff")
+    ///     .unwrap();
+    /// assert!(callees.contains(&"codebank::bank::CodeBank::public_signatures".to_string()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_graph(&self, config: &BankConfig) -> Result<Vec<(String, Vec<String>)>> {
+        let is_rust = |path: &Path| path.extension().is_some_and(|ext| ext == "rs");
+        let file_units = self.collect_file_units(config, is_rust)?;
+        let includes_tests = FormatOptions::from(config).includes_tests(&config.strategy);
+        call_graph(
+            &config.root_dir,
+            config.crate_name.as_deref(),
+            &file_units,
+            includes_tests,
+        )
+    }
+
+    /// Compare the public signatures of the Rust crates at `old.root_dir` and
+    /// `new.root_dir`, e.g. two checkouts of a crate, see [`CodeBank::public_signatures`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+    /// let diff = code_bank.api_diff(&config, &config)?;
+    /// assert!(diff.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn api_diff(&self, old: &BankConfig, new: &BankConfig) -> Result<ApiDiff> {
+        Ok(ApiDiff::new(
+            &self.public_signatures(old)?,
+            &self.public_signatures(new)?,
+        ))
+    }
+
+    /// Check `config` up front, before walking and parsing any file, and report its first
+    /// problem:
+    ///
+    /// - [`Error::DirectoryNotFound`] when `root_dir` doesn't exist
+    /// - [`Error::InvalidConfig`] when `root_dir` is not a directory, a limit is zero, a
+    ///   name or path is malformed, or an option has no effect with the others, e.g.
+    ///   `flatten_to_public_api` without the `Summary` strategy
+    ///
+    /// The generate methods don't call it, options without effect are ignored there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Error, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let mut config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+    /// code_bank.validate_config(&config)?;
+    ///
+    /// config.max_files = Some(0);
+    /// assert!(matches!(
+    ///     code_bank.validate_config(&config),
+    ///     Err(Error::InvalidConfig(_))
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate_config(&self, config: &BankConfig) -> Result<()> {
+        check_root_dir(&config.root_dir)?;
+        let invalid = |message: String| Err(Error::InvalidConfig(message));
+
+        if config.max_files == Some(0) {
+            return invalid("max_files must be at least 1".to_string());
+        }
+        if config.truncate_to_max_files && config.max_files.is_none() {
+            return invalid("truncate_to_max_files requires max_files".to_string());
+        }
+        if config.max_file_bytes == Some(0) {
+            return invalid("max_file_bytes must be at least 1".to_string());
+        }
+        if config
+            .per_file_timeout
+            .is_some_and(|timeout| timeout.is_zero())
+        {
+            return invalid("per_file_timeout must be longer than zero".to_string());
+        }
+        if config.flatten_to_public_api && config.strategy != BankStrategy::Summary {
+            return invalid("flatten_to_public_api requires the Summary strategy".to_string());
+        }
+        if config.merge_module_files && config.strategy == BankStrategy::Default {
+            return invalid(
+                "merge_module_files has no effect with the Default strategy".to_string(),
+            );
+        }
+
+        // Names matched against a single path component
+        let is_name = |name: &str| !name.is_empty() && !name.contains(['/', '\\']);
+        if let Some(name) = config.ignore_files.iter().find(|name| !is_name(name)) {
+            return invalid(format!("ignore_files takes file names, got `{}`", name));
+        }
+        for (i, (dir, label)) in config.section_by_dir.iter().enumerate() {
+            if !is_name(dir) || label.trim().is_empty() {
+                return invalid(format!(
+                    "section_by_dir takes directory names and labels, got `{}` = `{}`",
+                    dir, label
+                ));
+            }
+            if config.section_by_dir[..i]
+                .iter()
+                .any(|(other, _)| other == dir)
+            {
+                return invalid(format!("section_by_dir maps `{}` twice", dir));
+            }
+        }
+
+        if let Some(pattern) = config
+            .exclude_items
+            .iter()
+            .find(|pattern| !is_item_pattern(pattern))
+        {
+            return invalid(format!(
+                "exclude_items takes item names with `*` wildcards, e.g. `api::Client::*`, got `{}`",
+                pattern
+            ));
+        }
+
+        if let Some(filter) = &config.module_filter {
+            let is_identifier = |segment: &str| {
+                let segment = segment.strip_prefix("r#").unwrap_or(segment);
+                segment.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+            };
+            if !filter.split("::").map(str::trim).all(is_identifier) {
+                return invalid(format!(
+                    "module_filter must be a module path, e.g. `crate::parser`, got `{}`",
+                    filter
+                ));
+            }
+        }
+        if config
+            .crate_name
+            .as_deref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return invalid("crate_name must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Generate the code bank as a single JSON document, ignoring `config.format`
+    pub fn generate_json(&self, config: &BankConfig) -> Result<String> {
+        self.generate_filtered(config, BankFormat::Json, |_| true)
+    }
+
+    /// Generate the code bank as JSON lines, one file per line, ignoring `config.format`
+    pub fn generate_json_lines(&self, config: &BankConfig) -> Result<String> {
+        self.generate_filtered(config, BankFormat::JsonLines, |_| true)
+    }
+
+    /// Generate the code bank as an HTML page, ignoring `config.format`
+    pub fn generate_html(&self, config: &BankConfig) -> Result<String> {
+        self.generate_filtered(config, BankFormat::Html, |_| true)
+    }
+
+    /// Generate the code bank from an in-memory map of file path to source code, without
+    /// touching the filesystem. Each file's language is detected from its extension and
+    /// files in unsupported languages are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankStrategy, CodeBank, Result};
+    /// use std::collections::BTreeMap;
+    /// use std::path::PathBuf;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let mut sources = BTreeMap::new();
+    /// sources.insert(PathBuf::from("lib.rs"), "pub fn hello() {}".to_string());
+    ///
+    /// let content = code_bank.generate_from_sources(&sources, BankStrategy::Summary)?;
+    /// assert!(content.contains("## lib.rs"));
+    /// assert!(content.contains("pub fn hello()"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_from_sources(
+        &self,
+        sources: &BTreeMap<PathBuf, String>,
+        strategy: BankStrategy,
+    ) -> Result<String> {
+        let mut code_bank = self.try_clone()?;
+        let mut file_units = Vec::new();
+        for (path, source) in sources {
+            if let Some(file_unit) = code_bank.parse_source(path, source)? {
+                file_units.push(file_unit);
+            }
+        }
+
+        let config = BankConfig::new(PathBuf::new(), strategy, vec![]);
+        self.render_file_units(file_units, &config, "# Code Bank\n\n".to_string())
+    }
+
+    /// Render a single snippet of source code in `language` as a markdown code block,
+    /// formatted with `strategy`, without touching the filesystem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankStrategy, CodeBank, LanguageType, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let content = code_bank.render_source(
+    ///     "pub fn hello() {\n    println!(\"hi\");\n}\n",
+    ///     LanguageType::Rust,
+    ///     BankStrategy::Summary,
+    /// )?;
+    /// assert_eq!(content, "```rust\npub fn hello() { ... }\n```\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn render_source(
+        &self,
+        source: &str,
+        language: LanguageType,
+        strategy: BankStrategy,
+    ) -> Result<String> {
+        let unsupported = || Error::UnsupportedLanguage(language.as_str().to_string());
+        let extension = language.extension().ok_or_else(unsupported)?;
+        let path = PathBuf::from(format!("source.{}", extension));
+        let file_unit = self
+            .try_clone()?
+            .parse_source(&path, source)?
+            .ok_or_else(unsupported)?;
+
+        let content =
+            file_unit.format_with_options(&strategy, language, &FormatOptions::default())?;
+        Ok(format!(
+            "```{}\n{}\n```\n",
+            language.as_str(),
+            content.trim_matches('\n')
+        ))
+    }
+
+    /// Write one markdown file per source file to `out_dir`, mirroring the layout of
+    /// `config.root_dir`, e.g. `src/lib.rs` becomes `<out_dir>/src/lib.rs.md`. Returns
+    /// the paths of the written files.
+    ///
+    /// With [`Granularity::Module`] as `config.output_granularity`, the files of each
+    /// top-level module of a Rust crate are written together instead, e.g. `src/api.rs`
+    /// and `src/api/client.rs` to `<out_dir>/api.md`, under a heading per file.
+    ///
+    /// With `config.front_matter` each file starts with a YAML front matter block holding
+    /// the fields selected by `config.front_matter_fields`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let out_dir = tempfile::tempdir()?;
+    /// let mut config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+    /// config.front_matter = true;
+    ///
+    /// let written = code_bank.generate_to_dir(&config, out_dir.path())?;
+    /// let page = std::fs::read_to_string(out_dir.path().join("lib.rs.md"))?;
+    /// assert!(written.len() > 1);
+    /// assert!(page.starts_with("---\ntitle: \"lib.rs\"\nlanguage: rust\n"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_to_dir(
+        &self,
+        config: &BankConfig,
+        out_dir: impl AsRef<Path>,
+    ) -> Result<Vec<PathBuf>> {
+        let out_dir = out_dir.as_ref();
+        let file_units = self.collect_file_units(config, |_| true)?;
+        let regex = REGEX;
+        let regex = regex.get_or_init(|| Regex::new(r"\n*\s*\n+").unwrap());
+
+        let sections = self.format_sections(&file_units, config)?;
+        let module_paths = match config.output_granularity {
+            Granularity::File => HashMap::new(),
+            Granularity::Module => {
+                module_paths(&config.root_dir, config.crate_name.as_deref(), &file_units)
+            }
+        };
+        // Pages in the order of their first section, with whether they hold a module. The
+        // page of a module is named after its top-level module, or the crate for the root
+        let mut pages: Vec<(String, bool, Vec<&Section>)> = Vec::new();
+        for section in &sections {
+            let module_path = module_paths.get(&section.file_unit.path);
+            let name = match module_path {
+                Some(path) => path.get(1).unwrap_or(&path[0]),
+                None => &section.path,
+            };
+            match pages.iter_mut().find(|(page, _, _)| page == name) {
+                Some((_, _, sections)) => sections.push(section),
+                None => pages.push((name.clone(), module_path.is_some(), vec![section])),
+            }
+        }
+        // Parent modules before their submodules
+        for (_, _, sections) in &mut pages {
+            sections.sort_by_key(|section| module_paths.get(&section.file_unit.path));
+        }
+
+        let mut written = Vec::new();
+        for (name, is_module, sections) in pages {
+            let mut output = String::new();
+            if config.front_matter {
+                output.push_str(&front_matter(&name, &sections, &config.front_matter_fields));
+            }
+            let mut page = format!("# {}\n\n", name);
+            for section in &sections {
+                // Module pages hold a heading per file
+                if is_module {
+                    page.push_str(&format!("## {}\n\n", section.path));
+                }
+                page.push_str(&format!(
+                    "```{}\n{}```\n\n",
+                    section.fence(),
+                    section.content
+                ));
+            }
+            output.push_str(&regex.replace_all(&page, "\n"));
+
+            let path = out_dir.join(format!("{}.md", name));
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, output)?;
+            written.push(path);
         }
+        Ok(written)
+    }
 
+    /// Generate the code bank for the files changed since `base_ref`, as reported by
+    /// `git diff --name-only <base_ref>` run inside `config.root_dir`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use codebank::{BankConfig, BankStrategy, CodeBank, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let code_bank = CodeBank::try_new()?;
+    /// let config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+    /// let content = code_bank.generate_changed(&config, "origin/main")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "git")]
+    pub fn generate_changed(&self, config: &BankConfig, base_ref: &str) -> Result<String> {
+        let root_dir = &config.root_dir;
         if !root_dir.is_dir() {
-            return Err(Error::InvalidConfig(format!(
-                "{} is not a directory",
-                root_dir.display()
-            )));
+            return Err(Error::DirectoryNotFound(root_dir.to_path_buf()));
         }
 
-        // Initialize output
-        let mut output = String::new();
-        output.push_str("# Code Bank\n\n");
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(root_dir)
+            .args(["diff", "--name-only", "--relative", base_ref, "--"])
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::Git(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        // `--relative` reports paths relative to root_dir, matching the walker's paths once joined
+        let changed: std::collections::HashSet<std::path::PathBuf> =
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| root_dir.join(line))
+                .collect();
 
-        // Add package file content if found
-        match self.find_and_read_package_file(root_dir) {
-            Ok(Some(content)) => {
-                output.push_str("## Package File\n\n");
-                // Determine code block language based on filename (basic heuristic)
-                // This part might need refinement if the actual found filename is needed
-                // For now, using a generic block
-                output.push_str("```toml\n"); // Assuming TOML for Cargo.toml, adjust if needed
-                output.push_str(&content);
-                output.push_str("\n```\n\n");
+        self.generate_filtered(config, config.format, |path| changed.contains(path))
+    }
+
+    // Generate the code bank for the files under root_dir accepted by `include`
+    fn generate_filtered(
+        &self,
+        config: &BankConfig,
+        format: BankFormat,
+        include: impl Fn(&Path) -> bool,
+    ) -> Result<String> {
+        let root_dir = &config.root_dir;
+        let file_units = self.collect_file_units(config, include)?;
+
+        match format {
+            BankFormat::Markdown => {
+                // Initialize output
+                let mut output = String::new();
+                output.push_str("# Code Bank\n\n");
+                if config.include_provenance {
+                    output.push_str(&format!(
+                        "<!-- generated by codebank v{}; strategy={:?}; root={}; files={} -->\n\n",
+                        env!("CARGO_PKG_VERSION"),
+                        config.strategy,
+                        root_dir.display(),
+                        file_units.len()
+                    ));
+                }
+
+                // Add package file content if found
+                match self.find_and_read_package_file(root_dir) {
+                    Ok(Some(content)) => {
+                        output.push_str("## Package File\n\n");
+                        // Determine code block language based on filename (basic heuristic)
+                        // This part might need refinement if the actual found filename is needed
+                        // For now, using a generic block
+                        output.push_str("```toml\n"); // Assuming TOML for Cargo.toml, adjust if needed
+                        output.push_str(&content);
+                        output.push_str("\n```\n\n");
+                    }
+                    Ok(None) => { /* No package file found, do nothing */ }
+                    Err(e) => {
+                        // Log or handle the error appropriately, for now just continuing
+                        eprintln!("Warning: Failed to read package file: {}", e);
+                    }
+                }
+
+                let statistics = config
+                    .statistics
+                    .then(|| Metrics::from_file_units(&file_units));
+                let mut output = self.render_file_units(file_units, config, output)?;
+                if let Some(metrics) = statistics {
+                    output.push('\n');
+                    output.push_str(&metrics.to_markdown());
+                }
+                Ok(output)
+            }
+            BankFormat::Html => self.render_html(&file_units, config),
+            BankFormat::Reference => self.render_reference(&file_units, config, false),
+            BankFormat::Details => self.render_reference(&file_units, config, true),
+            BankFormat::Json => {
+                let bank = JsonBank {
+                    strategy: config.strategy,
+                    files: self.json_files(&file_units, config),
+                };
+                Ok(serde_json::to_string_pretty(&bank)?)
             }
-            Ok(None) => { /* No package file found, do nothing */ }
-            Err(e) => {
-                // Log or handle the error appropriately, for now just continuing
-                eprintln!("Warning: Failed to read package file: {}", e);
+            BankFormat::JsonWithRendered => {
+                let options = FormatOptions::from(config);
+                let files = self
+                    .json_files(&file_units, config)
+                    .into_iter()
+                    .map(|file| self.rendered_json_file(file, &options))
+                    .collect::<Result<Vec<_>>>()?;
+                let bank = JsonBank {
+                    strategy: config.strategy,
+                    files,
+                };
+                Ok(serde_json::to_string_pretty(&bank)?)
+            }
+            BankFormat::JsonLines => {
+                let mut output = String::new();
+                for file in self.json_files(&file_units, config) {
+                    output.push_str(&serde_json::to_string(&file)?);
+                    output.push('\n');
+                }
+                Ok(output)
             }
         }
+    }
 
-        // Clone self to make it mutable (needed for parsers)
-        let mut code_bank = self.try_clone()?;
+    // Walk root_dir and parse the files accepted by `include`, sorted by path
+    fn collect_file_units(
+        &self,
+        config: &BankConfig,
+        include: impl Fn(&Path) -> bool,
+    ) -> Result<Vec<FileUnit>> {
+        let root_dir = &config.root_dir;
+        check_root_dir(root_dir)?;
 
-        // Use a vector to collect all file units so we can sort them
-        let mut file_units = Vec::new();
+        // Collect the files to parse first, they are parsed in parallel below
+        let mut paths = Vec::new();
 
-        // Build the directory walker, respecting ignored directories
-        let walker = WalkBuilder::new(root_dir);
-        // walker.hidden(false); // Optionally include hidden files/dirs
-        // walker.git_ignore(true); // Use .gitignore
-        // walker.ignore(true); // Use .ignore files
+        let includes_tests = FormatOptions::from(config).includes_tests(&config.strategy);
+
+        // Build the directory walker, respecting `.gitignore`, `.ignore` and
+        // `.codebankignore` files, see `BankConfig::ignore_dirs`
+        let mut walker = WalkBuilder::new(root_dir);
+        walker.add_custom_ignore_filename(CODEBANK_IGNORE);
+        // Walk in path order, so that a truncated walk keeps the same files every time
+        if config.max_files.is_some() {
+            walker.sort_by_file_path(|a, b| a.cmp(b));
+        }
 
         // Walk through all files in the directory
         for entry in walker.build().filter_map(|e| e.ok()) {
@@ -168,158 +854,1087 @@ impl Bank for CodeBank {
                 })
             });
 
-            if should_ignore {
+            let is_ignored_file = path
+                .file_name()
+                .is_some_and(|name| config.ignore_files.iter().any(|file| name == file.as_str()));
+
+            if should_ignore || is_ignored_file || !include(path) {
+                continue;
+            }
+
+            // Test files are only included by the Default strategy, unless overridden
+            if !includes_tests && self.is_test_file(path, config) {
                 continue;
             }
 
-            if path.is_file() {
-                // Try to parse the file with the appropriate parser
-                if let Ok(Some(file_unit)) = code_bank.parse_file(path) {
-                    file_units.push(file_unit);
+            // Skip oversized files based on their metadata, without reading them
+            let oversized = config.max_file_bytes.is_some_and(|max_bytes| {
+                fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.len() > max_bytes)
+            });
+            if oversized {
+                continue;
+            }
+
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(limit) = config.max_files.filter(|limit| paths.len() >= *limit) {
+                if !config.truncate_to_max_files {
+                    return Err(Error::TooManyFiles {
+                        root: root_dir.to_path_buf(),
+                        limit,
+                    });
                 }
+                eprintln!(
+                    "Warning: more than {} files under {}, the remaining files are skipped",
+                    limit,
+                    root_dir.display()
+                );
+                break;
             }
+            paths.push(path.to_path_buf());
         }
 
+        // Each worker thread gets its own parsers, as parsers are stateful
+        let registry = &self.registry;
+        let parse = || -> Vec<FileUnit> {
+            paths
+                .par_iter()
+                .map_init(
+                    || CodeBank {
+                        timeout: config.per_file_timeout,
+                        ..CodeBank::with_registry(registry.clone())
+                    },
+                    |code_bank, path| code_bank.parse_file(path).ok().flatten(),
+                )
+                .flatten()
+                .collect()
+        };
+        let mut file_units = match config.parse_threads {
+            Some(threads) => ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(|e| Error::InvalidConfig(format!("failed to build thread pool: {}", e)))?
+                .install(parse),
+            None => parse(),
+        };
+
         // Sort file units by path for consistent output
         file_units.sort_by(|a, b| a.path.cmp(&b.path));
 
-        // Format each file unit as markdown using the Formatter trait
-        for file_unit in &file_units {
-            // Get the relative path of the file
-            let relative_path = file_unit
-                .path
-                .strip_prefix(root_dir)
-                .map(|p| p.display().to_string())
-                .unwrap_or_else(|_| file_unit.path.display().to_string());
+        if config.strict_signatures {
+            for file_unit in &mut file_units {
+                file_unit.check_signatures();
+            }
+        }
 
-            // Format the file unit using the Formatter trait
-            let lang = code_bank
-                .detect_language(&file_unit.path)
-                .unwrap_or(LanguageType::Unknown);
-            let formatted_content = file_unit.format(&config.strategy, lang)?;
+        if let Some(filter) = &config.module_filter {
+            file_units = filter_by_module(root_dir, file_units, filter)?;
+        }
 
-            if !formatted_content.is_empty() {
-                // Add the file header
-                output.push_str(&format!("## {}\n", relative_path));
+        if config.flatten_to_public_api && config.strategy == BankStrategy::Summary {
+            file_units = flatten_to_public_api(root_dir, file_units);
+        }
 
-                // Add the code block with appropriate language
-                output.push_str(&format!("```{}\n", lang.as_str()));
+        if config.merge_module_files && config.strategy != BankStrategy::Default {
+            file_units = merge_module_files(root_dir, file_units);
+        }
 
-                output.push_str(&formatted_content);
+        for file_unit in &mut file_units {
+            file_unit.exclude_items(&config.exclude_items);
+        }
 
-                output.push_str("```\n\n");
+        if let Some(min) = &config.min_visibility {
+            for file_unit in &mut file_units {
+                file_unit.retain_visibility(min);
             }
         }
 
-        // remove all empty lines
-        let regex = REGEX;
-        let regex = regex.get_or_init(|| Regex::new(r"\n*\s*\n+").unwrap());
-        output = regex.replace_all(&output, "\n").to_string();
+        if config.shorten_paths {
+            for file_unit in &mut file_units {
+                match &config.shorten_path_prefixes {
+                    Some(prefixes) => file_unit.shorten_paths(prefixes),
+                    None => file_unit.shorten_paths(DEFAULT_SHORTEN_PREFIXES),
+                }
+            }
+        }
 
-        Ok(output)
-    }
-}
+        if config.sort_items {
+            for file_unit in &mut file_units {
+                file_unit.sort_items();
+            }
+        }
 
-impl CodeBank {
-    // Helper method to clone the CodeBank for mutability
-    fn try_clone(&self) -> Result<Self> {
-        CodeBank::try_new()
+        // Only summaries are capped, the other strategies render files in full
+        let max_items = config
+            .max_items_per_file
+            .filter(|_| config.strategy == BankStrategy::Summary);
+        if let Some(max) = max_items {
+            for file_unit in &mut file_units {
+                file_unit.truncate_items(max);
+            }
+        }
+
+        Ok(file_units)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
+    // Format each file unit with the strategy, skipping files with nothing to show
+    fn format_sections<'a>(
+        &self,
+        file_units: &'a [FileUnit],
+        config: &BankConfig,
+    ) -> Result<Vec<Section<'a>>> {
+        let format_options = FormatOptions::from(config);
+        let mut sections = Vec::new();
+        for file_unit in file_units {
+            let language = self
+                .detect_language(&file_unit.path)
+                .unwrap_or(LanguageType::Unknown);
+            let content =
+                file_unit.format_with_options(&config.strategy, language, &format_options)?;
 
-    #[test]
-    fn test_detect_language() {
-        let code_bank = CodeBank::try_new().unwrap();
+            // Skip files with nothing to show (empty or whitespace-only output)
+            if config.include_empty_files || !content.trim().is_empty() {
+                sections.push(Section {
+                    file_unit,
+                    path: relative_path(file_unit, &config.root_dir),
+                    language,
+                    content,
+                });
+            }
+        }
+        Ok(sections)
+    }
 
-        // Test Rust files
-        let rust_path = PathBuf::from("test.rs");
-        assert_eq!(
-            code_bank.detect_language(&rust_path),
-            Some(LanguageType::Rust)
+    // Render the parsed file units as a standalone HTML page
+    fn render_html(&self, file_units: &[FileUnit], config: &BankConfig) -> Result<String> {
+        let mut output = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Code Bank</title>\n</head>\n<body>\n<h1>Code Bank</h1>\n",
         );
+        for section in self.format_sections(file_units, config)? {
+            output.push_str(&format!(
+                "<h2 id=\"{}\">{}</h2>\n<pre><code class=\"language-{}\">{}</code></pre>\n",
+                section_anchor(&section.path),
+                escape_html(&section.path),
+                section.fence(),
+                escape_html(&section.content)
+            ));
+        }
+        output.push_str("</body>\n</html>\n");
+        Ok(output)
+    }
 
-        // Test Python files
-        let python_path = PathBuf::from("test.py");
-        assert_eq!(
-            code_bank.detect_language(&python_path),
-            Some(LanguageType::Python)
-        );
+    // Render the parsed file units as reference documentation, one entry per item
+    // With `details`, each entry shows the Summary of the item and its full code below in a
+    // collapsible `<details>` block, whatever the strategy
+    fn render_reference(
+        &self,
+        file_units: &[FileUnit],
+        config: &BankConfig,
+        details: bool,
+    ) -> Result<String> {
+        let mut output = String::from("# Code Bank\n\n");
+        let config = &BankConfig {
+            strategy: if details {
+                BankStrategy::Summary
+            } else {
+                config.strategy
+            },
+            ..config.clone()
+        };
+        for section in self.format_sections(file_units, config)? {
+            output.push_str(&format!("## {}\n\n", section.path));
+            if let Some(doc) = &section.file_unit.doc {
+                output.push_str(&format!("{}\n\n", escape_markdown_prose(doc.trim())));
+            }
+            let mut reference = Reference {
+                strategy: config.strategy,
+                language: section.language,
+                options: FormatOptions::from(config),
+                fence: section.fence(),
+                details,
+                output: &mut output,
+            };
+            let file_unit = section.file_unit;
+            reference.items(
+                "",
+                (
+                    &file_unit.functions,
+                    &file_unit.structs,
+                    &file_unit.traits,
+                    &file_unit.impls,
+                    &file_unit.macros,
+                    &file_unit.consts,
+                    &file_unit.type_aliases,
+                ),
+            )?;
+            reference.modules(&file_unit.modules, "")?;
+        }
+        Ok(output)
+    }
 
-        // Test TypeScript files
-        let ts_path = PathBuf::from("test.ts");
-        assert_eq!(
-            code_bank.detect_language(&ts_path),
-            Some(LanguageType::TypeScript)
-        );
+    // The parsed file units with their relative path and language, for JSON output
+    fn json_files<'a>(&self, file_units: &'a [FileUnit], config: &BankConfig) -> Vec<JsonFile<'a>> {
+        file_units
+            .iter()
+            .map(|file_unit| JsonFile {
+                path: relative_path(file_unit, &config.root_dir),
+                language: self
+                    .detect_language(&file_unit.path)
+                    .unwrap_or(LanguageType::Unknown)
+                    .as_str(),
+                unit: file_unit,
+            })
+            .collect()
+    }
 
-        let tsx_path = PathBuf::from("test.tsx");
-        assert_eq!(
-            code_bank.detect_language(&tsx_path),
-            Some(LanguageType::TypeScript)
-        );
+    // The JSON of a file whose items carry their Summary as a markdown code block
+    fn rendered_json_file(
+        &self,
+        file: JsonFile,
+        options: &FormatOptions,
+    ) -> Result<serde_json::Value> {
+        let language = self
+            .detect_language(&file.unit.path)
+            .unwrap_or(LanguageType::Unknown);
+        let fence = match language {
+            LanguageType::Unknown => file.unit.path.extension().and_then(OsStr::to_str),
+            _ => Some(language.as_str()),
+        };
+        let items = RenderedItems {
+            language,
+            fence: fence.unwrap_or_default(),
+            options,
+        };
+        let mut value = serde_json::to_value(&file)?;
+        let unit = file.unit;
+        if let Some(unit_value) = value.get_mut("unit") {
+            items.members(
+                unit_value,
+                (
+                    &unit.functions,
+                    &unit.structs,
+                    &unit.traits,
+                    &unit.impls,
+                    &unit.macros,
+                    &unit.consts,
+                    &unit.type_aliases,
+                ),
+            )?;
+            items.items(unit_value, "modules", &unit.modules, |value, module| {
+                items.module(value, module)
+            })?;
+        }
+        Ok(value)
+    }
 
-        let js_path = PathBuf::from("test.js");
-        assert_eq!(
-            code_bank.detect_language(&js_path),
-            Some(LanguageType::TypeScript)
-        );
+    // Render the parsed file units as markdown sections appended to `output`
+    fn render_file_units(
+        &self,
+        file_units: Vec<FileUnit>,
+        config: &BankConfig,
+        mut output: String,
+    ) -> Result<String> {
+        let mut sections = self.format_sections(&file_units, config)?;
+        // The label of the directory section of each file, if files are grouped
+        let group = |section: &Section| {
+            let path = Path::new(&section.path);
+            let dir = path
+                .parent()
+                .and_then(|parent| parent.components().next())
+                .map(|dir| dir.as_os_str());
+            config
+                .section_by_dir
+                .iter()
+                .position(|(name, _)| dir.is_some_and(|dir| dir == name.as_str()))
+        };
+        sections.sort_by_key(|section| group(section));
+        let mut current_group = None;
 
-        let jsx_path = PathBuf::from("test.jsx");
-        assert_eq!(
-            code_bank.detect_language(&jsx_path),
-            Some(LanguageType::TypeScript)
+        let trait_links = TraitLinks::new(
+            sections
+                .iter()
+                .map(|section| (section.file_unit, section.path.as_str(), section.language)),
+            config.strategy,
         );
 
-        // Test C files
-        let c_path = PathBuf::from("test.c");
-        assert_eq!(code_bank.detect_language(&c_path), Some(LanguageType::Cpp));
+        for (i, section) in sections.iter().enumerate() {
+            if let Some(separator) = config.file_separator.as_deref().filter(|_| i > 0) {
+                output.push_str(&separator.replace("{path}", &section.path));
+            }
 
-        let h_path = PathBuf::from("test.h");
-        assert_eq!(code_bank.detect_language(&h_path), Some(LanguageType::Cpp));
+            if !config.section_by_dir.is_empty() && (i == 0 || group(section) != current_group) {
+                current_group = group(section);
+                let label = current_group.map_or(DEFAULT_SECTION, |group| {
+                    config.section_by_dir[group].1.as_str()
+                });
+                output.push_str(&format!("# {}\n", label));
+            }
 
-        // Test Go files
-        let go_path = PathBuf::from("test.go");
-        assert_eq!(code_bank.detect_language(&go_path), Some(LanguageType::Go));
+            // Add the file header
+            output.push_str(&format!("## {}\n", section.path));
+            // Below the heading, so that the anchor of the section stays the same
+            if let Some(date) = config
+                .include_file_mtime
+                .then(|| last_modified(&section.file_unit.path))
+                .flatten()
+            {
+                output.push_str(&format!("_modified {}_\n", date));
+            }
 
-        // Test unsupported files
-        let unsupported_path = PathBuf::from("test.txt");
-        assert_eq!(
-            code_bank.detect_language(&unsupported_path),
-            Some(LanguageType::Unknown)
-        );
-    }
+            // Add the code block with appropriate language
+            output.push_str(&format!("```{}\n", section.fence()));
 
-    #[test]
-    fn test_get_language_name() {
-        let code_bank = CodeBank::try_new().unwrap();
+            output.push_str(&section.content);
 
-        // Test Rust files
-        let rust_path = PathBuf::from("test.rs");
-        let lang = code_bank.detect_language(&rust_path).unwrap();
-        assert_eq!(lang.as_str(), "rust");
+            output.push_str("```\n\n");
 
-        // Test Python files
-        let python_path = PathBuf::from("test.py");
-        let lang = code_bank.detect_language(&python_path).unwrap();
-        assert_eq!(lang.as_str(), "python");
+            // Link traits to their implementors and trait impls back to their trait
+            output.push_str(&trait_links.render(
+                section.file_unit,
+                section.language,
+                config.strategy,
+            ));
+        }
 
-        // Test TypeScript files
-        let ts_path = PathBuf::from("test.ts");
-        let lang = code_bank.detect_language(&ts_path).unwrap();
-        assert_eq!(lang.as_str(), "ts");
+        // remove all empty lines
+        let regex = REGEX;
+        let regex = regex.get_or_init(|| Regex::new(r"\n*\s*\n+").unwrap());
+        output = regex.replace_all(&output, "\n").to_string();
 
-        // Test C files
-        let c_path = PathBuf::from("test.c");
-        let lang = code_bank.detect_language(&c_path).unwrap();
-        assert_eq!(lang.as_str(), "cpp");
+        Ok(output)
+    }
+}
 
-        // Test Go files
-        let go_path = PathBuf::from("test.go");
+// Writes reference entries: a heading with the qualified name of the item, its doc as
+// prose and its code, without the doc, as filtered by the strategy
+struct Reference<'a> {
+    strategy: BankStrategy,
+    language: LanguageType,
+    options: FormatOptions,
+    fence: &'a str,
+    // Add the full code of each item in a collapsible block below its code
+    details: bool,
+    output: &'a mut String,
+}
+
+impl Reference<'_> {
+    fn items(&mut self, scope: &str, members: Members) -> Result<()> {
+        let (functions, structs, traits, impls, macros, consts, type_aliases) = members;
+        for const_unit in consts {
+            let code = ConstUnit {
+                doc: None,
+                ..const_unit.clone()
+            };
+            self.entry(
+                &qualify(scope, &const_unit.name),
+                const_unit.doc.as_deref(),
+                &code,
+                &code,
+            )?;
+        }
+        for type_alias in type_aliases {
+            let code = TypeAliasUnit {
+                doc: None,
+                ..type_alias.clone()
+            };
+            self.entry(
+                &qualify(scope, &type_alias.name),
+                type_alias.doc.as_deref(),
+                &code,
+                &code,
+            )?;
+        }
+        for function in functions {
+            let code = FunctionUnit {
+                doc: None,
+                ..function.clone()
+            };
+            self.entry(
+                &qualify(scope, &function.name),
+                function.doc.as_deref(),
+                &code,
+                &code,
+            )?;
+        }
+        for struct_unit in structs {
+            let code = StructUnit {
+                doc: None,
+                ..struct_unit.clone()
+            };
+            self.entry(
+                &qualify(scope, &struct_unit.name),
+                struct_unit.doc.as_deref(),
+                &code,
+                &code,
+            )?;
+        }
+        for trait_unit in traits {
+            let code = TraitUnit {
+                doc: None,
+                ..trait_unit.clone()
+            };
+            self.entry(
+                &qualify(scope, &trait_unit.name),
+                trait_unit.doc.as_deref(),
+                &code,
+                &code,
+            )?;
+        }
+        // Methods are listed under their type, each in an impl block of its own so that
+        // the impl keeps filtering them
+        for impl_unit in impls {
+            let type_scope = qualify(scope, impl_type_name(&impl_unit.head));
+            for method in &impl_unit.methods {
+                let full = FunctionUnit {
+                    doc: None,
+                    ..method.clone()
+                };
+                let code = ImplUnit {
+                    doc: None,
+                    methods: vec![full.clone()],
+                    associated_items: Vec::new(),
+                    source: None,
+                    ..impl_unit.clone()
+                };
+                // The impl keeps no source, its method has the full code
+                self.entry(
+                    &qualify(&type_scope, &method.name),
+                    method.doc.as_deref(),
+                    &code,
+                    &full,
+                )?;
+            }
+        }
+        for macro_unit in macros {
+            let code = MacroUnit {
+                doc: None,
+                ..macro_unit.clone()
+            };
+            self.entry(
+                &qualify(scope, &macro_unit.name),
+                macro_unit.doc.as_deref(),
+                &code,
+                &code,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn modules(&mut self, modules: &[ModuleUnit], scope: &str) -> Result<()> {
+        for module in modules {
+            let content =
+                module.format_with_options(&self.strategy, self.language, &self.options)?;
+            if content.trim().is_empty() {
+                continue;
+            }
+            let scope = qualify(scope, &module.name);
+            if let Some(doc) = &module.doc {
+                self.output.push_str(&format!(
+                    "#### {}\n\n{}\n\n",
+                    escape_markdown_prose(&scope),
+                    escape_markdown_prose(doc.trim())
+                ));
+            }
+            self.items(
+                &scope,
+                (
+                    &module.functions,
+                    &module.structs,
+                    &module.traits,
+                    &module.impls,
+                    &module.macros,
+                    &module.consts,
+                    &module.type_aliases,
+                ),
+            )?;
+            self.modules(&module.submodules, &scope)?;
+        }
+        Ok(())
+    }
+
+    // Write the entry of an item, unless the strategy leaves its code out. `full` is the
+    // item whose code goes in the details
+    fn entry(
+        &mut self,
+        name: &str,
+        doc: Option<&str>,
+        code: &dyn Formatter,
+        full: &dyn Formatter,
+    ) -> Result<()> {
+        let code = code.format_with_options(&self.strategy, self.language, &self.options)?;
+        if code.trim().is_empty() {
+            return Ok(());
+        }
+        self.output
+            .push_str(&format!("#### {}\n\n", escape_markdown_prose(name)));
+        if let Some(doc) = doc.filter(|_| self.options.doc_mode == DocMode::Full) {
+            self.output
+                .push_str(&format!("{}\n\n", escape_markdown_prose(doc.trim())));
+        }
+        self.output.push_str(&format!(
+            "```{}\n{}\n```\n\n",
+            self.fence,
+            code.trim_matches('\n')
+        ));
+        if self.details {
+            let full =
+                full.format_with_options(&BankStrategy::Default, self.language, &self.options)?;
+            self.output.push_str(&format!(
+                "<details>\n<summary>Implementation</summary>\n\n```{}\n{}\n```\n\n</details>\n\n",
+                self.fence,
+                dedent_source(full.trim_matches('\n'))
+            ));
+        }
+        Ok(())
+    }
+}
+
+// The source of a nested item, e.g. a method, starts at its first token but its other
+// lines keep the indentation of the item: remove the indentation they have in common
+fn dedent_source(source: &str) -> String {
+    let indent = source
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line,
+            _ => line.get(indent..).unwrap_or(line.trim_start()),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", scope, name)
+    }
+}
+
+// A file unit formatted with the configured strategy
+struct Section<'a> {
+    file_unit: &'a FileUnit,
+    // Path relative to the root directory
+    path: String,
+    language: LanguageType,
+    content: String,
+}
+
+impl Section<'_> {
+    // The code block language, externally registered languages fall back to the file extension
+    fn fence(&self) -> &str {
+        match self.language {
+            LanguageType::Unknown => self
+                .file_unit
+                .path
+                .extension()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default(),
+            _ => self.language.as_str(),
+        }
+    }
+}
+
+// The YAML front matter block of a page holding `sections`, `---` lines included
+fn front_matter(title: &str, sections: &[&Section], fields: &FrontMatterFields) -> String {
+    let mut output = String::from("---\n");
+    if fields.title {
+        output.push_str(&format!("title: {}\n", yaml_string(title)));
+    }
+    if let Some(section) = sections.first().filter(|_| fields.language) {
+        output.push_str(&format!("language: {}\n", section.fence()));
+    }
+    if fields.item_counts {
+        let metrics: Vec<Metrics> = sections
+            .iter()
+            .map(|section| Metrics::from_file_units(std::slice::from_ref(section.file_unit)))
+            .collect();
+        let total = |count: fn(&Metrics) -> usize| metrics.iter().map(count).sum::<usize>();
+        output.push_str(&format!("functions: {}\n", total(|m| m.functions.total)));
+        output.push_str(&format!("structs: {}\n", total(|m| m.structs.total)));
+        output.push_str(&format!("traits: {}\n", total(|m| m.traits.total)));
+        output.push_str(&format!("modules: {}\n", total(|m| m.modules.total)));
+    }
+    output.push_str("---\n\n");
+    output
+}
+
+// A double-quoted YAML string
+fn yaml_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Serialize)]
+struct JsonBank<F> {
+    strategy: BankStrategy,
+    files: Vec<F>,
+}
+
+#[derive(Serialize)]
+struct JsonFile<'a> {
+    path: String,
+    language: &'static str,
+    unit: &'a FileUnit,
+}
+
+// The items of a module or file unit
+type Members<'a> = (
+    &'a [FunctionUnit],
+    &'a [StructUnit],
+    &'a [TraitUnit],
+    &'a [ImplUnit],
+    &'a [MacroUnit],
+    &'a [ConstUnit],
+    &'a [TypeAliasUnit],
+);
+
+// Sets the `rendered` field of the items of a serialized file unit to their Summary
+struct RenderedItems<'a> {
+    language: LanguageType,
+    fence: &'a str,
+    options: &'a FormatOptions,
+}
+
+impl RenderedItems<'_> {
+    // Render the units serialized in the `key` array of `value`, then their children
+    fn items<T: Formatter>(
+        &self,
+        value: &mut serde_json::Value,
+        key: &str,
+        units: &[T],
+        children: impl Fn(&mut serde_json::Value, &T) -> Result<()>,
+    ) -> Result<()> {
+        let Some(values) = value.get_mut(key).and_then(|v| v.as_array_mut()) else {
+            return Ok(());
+        };
+        for (value, unit) in values.iter_mut().zip(units) {
+            let summary =
+                unit.format_with_options(&BankStrategy::Summary, self.language, self.options)?;
+            // Items hidden by the Summary, e.g. private ones, render as an empty string
+            let rendered = match summary.trim() {
+                "" => String::new(),
+                summary => format!("```{}\n{}\n```\n", self.fence, summary),
+            };
+            if let Some(object) = value.as_object_mut() {
+                object.insert("rendered".to_string(), rendered.into());
+            }
+            children(value, unit)?;
+        }
+        Ok(())
+    }
+
+    fn members(&self, value: &mut serde_json::Value, members: Members) -> Result<()> {
+        let (functions, structs, traits, impls, macros, consts, type_aliases) = members;
+        self.items(value, "consts", consts, no_children)?;
+        self.items(value, "type_aliases", type_aliases, no_children)?;
+        self.items(value, "functions", functions, no_children)?;
+        self.items(value, "structs", structs, |value, unit: &StructUnit| {
+            self.items(value, "methods", &unit.methods, no_children)
+        })?;
+        self.items(value, "traits", traits, |value, unit: &TraitUnit| {
+            self.items(value, "methods", &unit.methods, no_children)
+        })?;
+        self.items(value, "impls", impls, |value, unit: &ImplUnit| {
+            self.items(value, "methods", &unit.methods, no_children)
+        })?;
+        self.items(value, "macros", macros, no_children)
+    }
+
+    fn module(&self, value: &mut serde_json::Value, module: &ModuleUnit) -> Result<()> {
+        self.members(
+            value,
+            (
+                &module.functions,
+                &module.structs,
+                &module.traits,
+                &module.impls,
+                &module.macros,
+                &module.consts,
+                &module.type_aliases,
+            ),
+        )?;
+        self.items(value, "submodules", &module.submodules, |value, module| {
+            self.module(value, module)
+        })
+    }
+}
+
+// The children of a rendered item without children of their own
+fn no_children<T>(_: &mut serde_json::Value, _: &T) -> Result<()> {
+    Ok(())
+}
+
+// Make sure the root directory exists and is a directory
+fn check_root_dir(root_dir: &Path) -> Result<()> {
+    if !root_dir.exists() {
+        return Err(Error::DirectoryNotFound(root_dir.to_path_buf()));
+    }
+    if !root_dir.is_dir() {
+        return Err(Error::InvalidConfig(format!(
+            "{} is not a directory",
+            root_dir.display()
+        )));
+    }
+    Ok(())
+}
+
+// The path of a file unit relative to the root directory
+fn relative_path(file_unit: &FileUnit, root_dir: &Path) -> String {
+    file_unit
+        .path
+        .strip_prefix(root_dir)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| file_unit.path.display().to_string())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Escape the HTML special characters of markdown prose, e.g. docs rendered as text, so that
+// `Vec<T>` doesn't render as a `<T>` tag. Code spans and fenced code blocks are left as
+// written, markdown renders them literally.
+fn escape_markdown_prose(text: &str) -> String {
+    let mut output = String::new();
+    let mut in_fence = false;
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            output.push_str(line);
+            continue;
+        }
+        if in_fence {
+            output.push_str(line);
+            continue;
+        }
+        // Odd segments between backticks are code spans
+        for (j, segment) in line.split('`').enumerate() {
+            if j > 0 {
+                output.push('`');
+            }
+            if j % 2 == 1 {
+                output.push_str(segment);
+            } else {
+                output.push_str(
+                    &segment
+                        .replace('&', "&amp;")
+                        .replace('<', "&lt;")
+                        .replace('>', "&gt;"),
+                );
+            }
+        }
+    }
+    output
+}
+
+// Cross references between trait definitions and trait impls across the rendered files.
+// Only explicit impl blocks are linked, derived impls are shown by the type's `#[derive]`
+// attribute, so a trait is never reported twice for the same type.
+struct TraitLinks<'a> {
+    // Trait name -> sections defining a trait with that name
+    definitions: HashMap<&'a str, Vec<&'a str>>,
+    // Trait name -> (impl head, section) of its implementations
+    implementors: HashMap<&'a str, Vec<(&'a str, &'a str)>>,
+}
+
+impl<'a> TraitLinks<'a> {
+    fn new(
+        sections: impl Iterator<Item = (&'a FileUnit, &'a str, LanguageType)>,
+        strategy: BankStrategy,
+    ) -> Self {
+        let mut definitions: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut implementors: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+        for (file_unit, section, language) in sections {
+            let (traits, impls) = collect_trait_units(file_unit, language, strategy);
+            for trait_unit in traits {
+                definitions
+                    .entry(&trait_unit.name)
+                    .or_default()
+                    .push(section);
+            }
+            for (trait_name, impl_unit) in impls {
+                implementors
+                    .entry(trait_name)
+                    .or_default()
+                    .push((&impl_unit.head, section));
+            }
+        }
+
+        Self {
+            definitions,
+            implementors,
+        }
+    }
+
+    // Render the links for the traits and trait impls of a file, empty if there are none
+    fn render(
+        &self,
+        file_unit: &FileUnit,
+        language: LanguageType,
+        strategy: BankStrategy,
+    ) -> String {
+        let mut output = String::new();
+        let (traits, impls) = collect_trait_units(file_unit, language, strategy);
+
+        for trait_unit in traits {
+            let Some(implementors) = self.implementors.get(trait_unit.name.as_str()) else {
+                continue;
+            };
+            output.push_str(&format!("Implementors of `{}`:\n", trait_unit.name));
+            for (head, section) in implementors {
+                output.push_str(&format!("- [`{}`](#{})\n", head, section_anchor(section)));
+            }
+        }
+
+        let mut implemented = Vec::new();
+        for (trait_name, impl_unit) in impls {
+            let Some(sections) = self.definitions.get(trait_name) else {
+                continue;
+            };
+            for section in sections {
+                implemented.push(format!(
+                    "- `{}` implements [`{}`](#{})\n",
+                    impl_unit.head,
+                    trait_name,
+                    section_anchor(section)
+                ));
+            }
+        }
+        if !implemented.is_empty() {
+            output.push_str("Trait implementations:\n");
+            output.push_str(&implemented.concat());
+        }
+        output
+    }
+}
+
+// Collect the traits and trait impls of a file, including nested modules. Units that are
+// not rendered by the strategy (private traits in Summary, test modules) are left out.
+fn collect_trait_units(
+    file_unit: &FileUnit,
+    language: LanguageType,
+    strategy: BankStrategy,
+) -> (Vec<&TraitUnit>, Vec<(&str, &ImplUnit)>) {
+    fn visit<'a>(
+        traits: &'a [TraitUnit],
+        impls: &'a [ImplUnit],
+        modules: &'a [ModuleUnit],
+        rules: &FormatterRules,
+        strategy: BankStrategy,
+        found: &mut (Vec<&'a TraitUnit>, Vec<(&'a str, &'a ImplUnit)>),
+    ) {
+        found.0.extend(traits.iter().filter(|trait_unit| {
+            strategy != BankStrategy::Summary || trait_unit.visibility == Visibility::Public
+        }));
+        found.1.extend(
+            impls
+                .iter()
+                .filter_map(|impl_unit| Some((impl_unit.trait_name()?, impl_unit))),
+        );
+        for module in modules {
+            if strategy != BankStrategy::Default
+                && rules.is_test_module(&module.name, &module.attributes)
+            {
+                continue;
+            }
+            visit(
+                &module.traits,
+                &module.impls,
+                &module.submodules,
+                rules,
+                strategy,
+                found,
+            );
+        }
+    }
+
+    let mut found = (Vec::new(), Vec::new());
+    visit(
+        &file_unit.traits,
+        &file_unit.impls,
+        &file_unit.modules,
+        &FormatterRules::for_language(language),
+        strategy,
+        &mut found,
+    );
+    found
+}
+
+// The date a file was last modified as `YYYY-MM-DD`, from its last commit with the `git`
+// feature and from its modification time otherwise
+fn last_modified(path: &Path) -> Option<String> {
+    #[cfg(feature = "git")]
+    if let Some(date) = last_commit_date(path) {
+        return Some(date);
+    }
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(format_date(modified))
+}
+
+#[cfg(feature = "git")]
+fn last_commit_date(path: &Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(path.parent()?)
+        .args(["log", "-1", "--format=%cs", "--"])
+        .arg(path.file_name()?)
+        .output()
+        .ok()?;
+    let date = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !date.is_empty()).then_some(date)
+}
+
+// Format a time as a UTC `YYYY-MM-DD` date, converting days since the epoch to a civil
+// date as in http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn format_date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// The anchor of a `## path` section as generated by GitHub-flavored markdown
+fn section_anchor(section: &str) -> String {
+    section
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+impl CodeBank {
+    // Helper method to clone the CodeBank for mutability
+    fn try_clone(&self) -> Result<Self> {
+        Ok(CodeBank::with_registry(self.registry.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FunctionUnit;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_detect_language() {
+        let code_bank = CodeBank::try_new().unwrap();
+
+        // Test Rust files
+        let rust_path = PathBuf::from("test.rs");
+        assert_eq!(
+            code_bank.detect_language(&rust_path),
+            Some(LanguageType::Rust)
+        );
+
+        // Test Python files
+        let python_path = PathBuf::from("test.py");
+        assert_eq!(
+            code_bank.detect_language(&python_path),
+            Some(LanguageType::Python)
+        );
+
+        // Test TypeScript files
+        let ts_path = PathBuf::from("test.ts");
+        assert_eq!(
+            code_bank.detect_language(&ts_path),
+            Some(LanguageType::TypeScript)
+        );
+
+        let tsx_path = PathBuf::from("test.tsx");
+        assert_eq!(
+            code_bank.detect_language(&tsx_path),
+            Some(LanguageType::TypeScript)
+        );
+
+        let js_path = PathBuf::from("test.js");
+        assert_eq!(
+            code_bank.detect_language(&js_path),
+            Some(LanguageType::TypeScript)
+        );
+
+        let jsx_path = PathBuf::from("test.jsx");
+        assert_eq!(
+            code_bank.detect_language(&jsx_path),
+            Some(LanguageType::TypeScript)
+        );
+
+        // Test C files
+        let c_path = PathBuf::from("test.c");
+        assert_eq!(code_bank.detect_language(&c_path), Some(LanguageType::Cpp));
+
+        let h_path = PathBuf::from("test.h");
+        assert_eq!(code_bank.detect_language(&h_path), Some(LanguageType::Cpp));
+
+        // Test Go files
+        let go_path = PathBuf::from("test.go");
+        assert_eq!(code_bank.detect_language(&go_path), Some(LanguageType::Go));
+
+        // Test unsupported files
+        let unsupported_path = PathBuf::from("test.txt");
+        assert_eq!(
+            code_bank.detect_language(&unsupported_path),
+            Some(LanguageType::Unknown)
+        );
+    }
+
+    #[test]
+    fn test_get_language_name() {
+        let code_bank = CodeBank::try_new().unwrap();
+
+        // Test Rust files
+        let rust_path = PathBuf::from("test.rs");
+        let lang = code_bank.detect_language(&rust_path).unwrap();
+        assert_eq!(lang.as_str(), "rust");
+
+        // Test Python files
+        let python_path = PathBuf::from("test.py");
+        let lang = code_bank.detect_language(&python_path).unwrap();
+        assert_eq!(lang.as_str(), "python");
+
+        // Test TypeScript files
+        let ts_path = PathBuf::from("test.ts");
+        let lang = code_bank.detect_language(&ts_path).unwrap();
+        assert_eq!(lang.as_str(), "ts");
+
+        // Test C files
+        let c_path = PathBuf::from("test.c");
+        let lang = code_bank.detect_language(&c_path).unwrap();
+        assert_eq!(lang.as_str(), "cpp");
+
+        // Test Go files
+        let go_path = PathBuf::from("test.go");
         let lang = code_bank.detect_language(&go_path).unwrap();
         assert_eq!(lang.as_str(), "go");
 
@@ -328,4 +1943,1786 @@ mod tests {
         let lang = code_bank.detect_language(&unsupported_path).unwrap();
         assert_eq!(lang.as_str(), "unknown");
     }
+
+    #[test]
+    fn test_generate_skips_empty_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        fs::copy(fixtures.join("empty.rs"), temp_dir.path().join("empty.rs")).unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "pub fn answer() -> i32 {\n    42\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("## lib.rs"));
+        assert!(!content.contains("## empty.rs"));
+
+        config.include_empty_files = true;
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("## lib.rs"));
+        assert!(content.contains("## empty.rs"));
+    }
+
+    #[test]
+    fn test_no_tests_skips_test_files_and_macros() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "button.ts",
+                "export function render(): string {\n  return 'ok';\n}\n",
+            ),
+            (
+                "button.test.ts",
+                "describe('button', () => {\n  it('renders', () => {});\n});\n",
+            ),
+            ("models.py", "def load():\n    return 1\n"),
+            (
+                "test_models.py",
+                "def test_load():\n    assert load() == 1\n",
+            ),
+            (
+                "math.cpp",
+                "int add(int a, int b) { return a + b; }\n\nTEST(MathTest, Adds) {\n  EXPECT_EQ(add(1, 2), 3);\n}\n",
+            ),
+        ];
+        for (name, content) in files {
+            fs::write(temp_dir.path().join(name), content).unwrap();
+        }
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::NoTests, vec![]);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("## button.ts"));
+        assert!(!content.contains("## button.test.ts"));
+        assert!(content.contains("## models.py"));
+        assert!(!content.contains("## test_models.py"));
+        assert!(content.contains("int add(int a, int b)"));
+        assert!(!content.contains("TEST(MathTest, Adds)"));
+
+        // Custom patterns replace the per-language defaults
+        config.test_file_patterns = Some(vec!["*_test.py".to_string(), "button.*".to_string()]);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(!content.contains("## button.ts"));
+        assert!(content.contains("## test_models.py"));
+
+        // Default strategy keeps everything
+        config.strategy = BankStrategy::Default;
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("## button.test.ts"));
+        assert!(content.contains("TEST(MathTest, Adds)"));
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn test_generate_changed_only_includes_changed_files() {
+        use std::process::Command;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(temp_dir.path())
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q"]);
+        fs::write(temp_dir.path().join("a.rs"), "pub fn a() {}\n").unwrap();
+        fs::write(temp_dir.path().join("b.rs"), "pub fn b() {}\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "initial"]);
+
+        fs::write(temp_dir.path().join("b.rs"), "pub fn b_changed() {}\n").unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        let content = code_bank.generate_changed(&config, "HEAD").unwrap();
+        assert!(content.contains("## b.rs"));
+        assert!(content.contains("b_changed"));
+        assert!(!content.contains("## a.rs"));
+
+        assert!(matches!(
+            code_bank.generate_changed(&config, "no-such-ref"),
+            Err(Error::Git(_))
+        ));
+    }
+
+    #[test]
+    fn test_generate_skips_files_over_max_bytes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let small = "pub fn small() {}\n";
+        fs::write(temp_dir.path().join("small.rs"), small).unwrap();
+        let large = format!("pub fn large() {{}}\n{}", " ".repeat(small.len()));
+        fs::write(temp_dir.path().join("large.rs"), &large).unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        config.max_file_bytes = Some(small.len() as u64);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("## small.rs"));
+        assert!(!content.contains("## large.rs"));
+        assert!(!content.contains("fn large"));
+
+        // One byte over the limit is enough to skip the file
+        config.max_file_bytes = Some(large.len() as u64 - 1);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(!content.contains("## large.rs"));
+
+        config.max_file_bytes = Some(large.len() as u64);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("## large.rs"));
+    }
+
+    #[test]
+    fn test_generate_from_sources() {
+        let mut sources = BTreeMap::new();
+        sources.insert(
+            PathBuf::from("src/zeta.rs"),
+            "pub fn zeta() -> u8 {\n    1\n}\n".to_string(),
+        );
+        sources.insert(
+            PathBuf::from("src/alpha.rs"),
+            "pub fn alpha() -> u8 {\n    2\n}\n".to_string(),
+        );
+        sources.insert(PathBuf::from("notes.txt"), "not code".to_string());
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let content = code_bank
+            .generate_from_sources(&sources, BankStrategy::Summary)
+            .unwrap();
+
+        let alpha = content.find("## src/alpha.rs").unwrap();
+        let zeta = content.find("## src/zeta.rs").unwrap();
+        assert!(alpha < zeta);
+        assert!(content.contains("pub fn alpha() -> u8 { ... }"));
+        assert!(!content.contains("notes.txt"));
+    }
+
+    #[test]
+    fn test_render_source() {
+        let code_bank = CodeBank::try_new().unwrap();
+        let snippets = [
+            (
+                LanguageType::Rust,
+                "pub fn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n",
+            ),
+            (LanguageType::Python, "def add(a, b):\n    return a + b\n"),
+            (
+                LanguageType::TypeScript,
+                "export function add(a: number, b: number): number {\n  return a + b;\n}\n",
+            ),
+            (
+                LanguageType::Cpp,
+                "int add(int a, int b) {\n    return a + b;\n}\n",
+            ),
+            (
+                LanguageType::Go,
+                "package calc\n\nfunc Add(a, b int) int {\n\treturn a + b\n}\n",
+            ),
+            (
+                LanguageType::CSharp,
+                "public class Calc {\n    public int Add(int a, int b) {\n        return a + b;\n    }\n}\n",
+            ),
+            (
+                LanguageType::Php,
+                "<?php\nfunction add($a, $b) {\n    return $a + $b;\n}\n",
+            ),
+        ];
+        for (language, source) in snippets {
+            let content = code_bank
+                .render_source(source, language, BankStrategy::Summary)
+                .unwrap();
+            assert!(
+                content.starts_with(&format!("```{}\n", language.as_str())),
+                "{content}"
+            );
+            assert!(content.to_lowercase().contains("add"), "{content}");
+            assert!(!content.contains("return"), "{content}");
+            assert!(content.ends_with("\n```\n"), "{content}");
+        }
+
+        assert!(matches!(
+            code_bank.render_source("", LanguageType::Unknown, BankStrategy::Summary),
+            Err(Error::UnsupportedLanguage(_))
+        ));
+    }
+
+    // A toy Swift parser that only reads `func` lines, enough to test the registration of
+    // an extra language. It is not a model for real parsers, which build on a tree-sitter
+    // grammar like the built-in ones, e.g. `GoParser`
+    struct ToySwiftParser;
+
+    impl LanguageParser for ToySwiftParser {
+        fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
+            let source_code = fs::read_to_string(file_path)?;
+            self.parse_str(&source_code, file_path)
+        }
+
+        fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
+            let mut file_unit = FileUnit::new(file_path.to_path_buf());
+            file_unit.source = Some(source_code.to_string());
+            for line in source_code.lines().map(str::trim) {
+                let Some(rest) = line
+                    .strip_prefix("public func ")
+                    .or_else(|| line.strip_prefix("func "))
+                else {
+                    continue;
+                };
+                let name = rest.split('(').next().unwrap_or_default().to_string();
+                let visibility = if line.starts_with("public ") {
+                    crate::Visibility::Public
+                } else {
+                    crate::Visibility::Private
+                };
+                file_unit.functions.push(crate::FunctionUnit {
+                    name,
+                    visibility,
+                    signature: line.split('{').next().map(|s| s.trim().to_string()),
+                    source: Some(line.to_string()),
+                    ..Default::default()
+                });
+            }
+            Ok(file_unit)
+        }
+    }
+
+    #[test]
+    fn test_register_external_parser() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("greeter.swift"),
+            "public func greet(name: String) -> String { \"Hi \\(name)\" }\nfunc helper() {}\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "pub fn hello() {}\n").unwrap();
+
+        // Without a registration Swift files are skipped
+        let config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        let content = CodeBank::try_new().unwrap().generate(&config).unwrap();
+        assert!(!content.contains("greeter.swift"));
+
+        let mut registry = ParserRegistry::new();
+        registry.register("swift", || Ok(Box::new(ToySwiftParser)));
+        assert_eq!(
+            registry.language(Path::new("greeter.swift")),
+            LanguageType::Unknown
+        );
+        let code_bank = CodeBank::with_registry(registry);
+
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("## greeter.swift\n```swift\n"));
+        assert!(content.contains("public func greet(name: String) -> String"));
+        assert!(content.contains("func helper() {}"));
+        // Built-in languages are still parsed
+        assert!(content.contains("## lib.rs\n```rust\n"));
+
+        let config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("public func greet(name: String) -> String"));
+        assert!(!content.contains("helper"));
+
+        let mut sources = BTreeMap::new();
+        sources.insert(
+            PathBuf::from("greeter.swift"),
+            "public func greet() {}".to_string(),
+        );
+        let content = code_bank
+            .generate_from_sources(&sources, BankStrategy::Default)
+            .unwrap();
+        assert!(content.contains("public func greet() {}"));
+    }
+
+    #[test]
+    fn test_generate_to_dir_per_module() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let out_dir = tempfile::tempdir().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("api")).unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"my-app\"\n",
+        )
+        .unwrap();
+        fs::write(src.join("lib.rs"), "pub mod api;\n\npub fn version() {}\n").unwrap();
+        fs::write(src.join("api.rs"), "pub mod client;\n\npub fn route() {}\n").unwrap();
+        fs::write(src.join("api").join("client.rs"), "pub fn connect() {}\n").unwrap();
+
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.output_granularity = Granularity::Module;
+        config.front_matter = true;
+        let mut written = CodeBank::try_new()
+            .unwrap()
+            .generate_to_dir(&config, out_dir.path())
+            .unwrap();
+        written.sort();
+        assert_eq!(
+            written,
+            [
+                out_dir.path().join("api.md"),
+                out_dir.path().join("my_app.md")
+            ]
+        );
+
+        let page = fs::read_to_string(out_dir.path().join("api.md")).unwrap();
+        assert!(page.starts_with("---\ntitle: \"api\"\nlanguage: rust\nfunctions: 2\n"));
+        let body = page.split_once("\n---\n\n").unwrap().1;
+        assert_eq!(
+            body,
+            "# api\n## src/api.rs\n```rust\npub fn route() { ... }\n```\n## src/api/client.rs\n```rust\npub fn connect() { ... }\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_to_dir_with_front_matter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let out_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("api")).unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "/// Say hello\npub fn hello() {}\npub struct Greeter;\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("api/client.py"),
+            "def fetch():\n    pass\n",
+        )
+        .unwrap();
+
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let written = CodeBank::try_new()
+            .unwrap()
+            .generate_to_dir(&config, out_dir.path())
+            .unwrap();
+        assert_eq!(written.len(), 2);
+        let page = fs::read_to_string(out_dir.path().join("lib.rs.md")).unwrap();
+        assert!(page.starts_with("# lib.rs\n```rust\n"));
+
+        config.front_matter = true;
+        let written = CodeBank::try_new()
+            .unwrap()
+            .generate_to_dir(&config, out_dir.path())
+            .unwrap();
+        for path in &written {
+            let page = fs::read_to_string(path).unwrap();
+            let block = page
+                .strip_prefix("---\n")
+                .and_then(|rest| rest.split_once("\n---\n"))
+                .map(|(block, _)| block)
+                .unwrap();
+            let keys: Vec<_> = block
+                .lines()
+                .map(|line| line.split_once(": ").unwrap().0)
+                .collect();
+            assert_eq!(
+                keys,
+                [
+                    "title",
+                    "language",
+                    "functions",
+                    "structs",
+                    "traits",
+                    "modules"
+                ]
+            );
+        }
+
+        let page = fs::read_to_string(out_dir.path().join("lib.rs.md")).unwrap();
+        assert!(page.starts_with(
+            "---\ntitle: \"lib.rs\"\nlanguage: rust\nfunctions: 1\nstructs: 1\ntraits: 0\nmodules: 0\n---\n\n# lib.rs\n"
+        ));
+        let page = fs::read_to_string(out_dir.path().join("api/client.py.md")).unwrap();
+        assert!(page.starts_with("---\ntitle: \"api/client.py\"\nlanguage: python\n"));
+
+        config.front_matter_fields = FrontMatterFields {
+            item_counts: false,
+            ..Default::default()
+        };
+        CodeBank::try_new()
+            .unwrap()
+            .generate_to_dir(&config, out_dir.path())
+            .unwrap();
+        let page = fs::read_to_string(out_dir.path().join("lib.rs.md")).unwrap();
+        assert!(page.starts_with("---\ntitle: \"lib.rs\"\nlanguage: rust\n---\n"));
+    }
+
+    // A parser with a bug that panics on every file
+    struct PanickingParser;
+
+    impl LanguageParser for PanickingParser {
+        fn parse_file(&mut self, _file_path: &Path) -> Result<FileUnit> {
+            panic!("unexpected node shape")
+        }
+    }
+
+    #[test]
+    fn test_parser_panic_skips_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("broken.txt"), "anything").unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "pub fn hello() {}\n").unwrap();
+
+        let mut registry = ParserRegistry::new();
+        registry.register("txt", || Ok(Box::new(PanickingParser)));
+        let mut code_bank = CodeBank::with_registry(registry);
+
+        let file_unit = code_bank
+            .parse_file(&temp_dir.path().join("broken.txt"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(file_unit.parse_errors.len(), 1);
+        assert_eq!(
+            file_unit.parse_errors[0].1,
+            "parser panicked: unexpected node shape"
+        );
+        assert!(file_unit.functions.is_empty());
+
+        let config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("pub fn hello() {}"));
+        assert!(!content.contains("## broken.txt"));
+    }
+
+    #[test]
+    fn test_per_file_timeout_skips_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let huge: String = (0..200_000)
+            .map(|i| format!("pub fn f{}(a: u32) -> u32 {{ a + {} }}\n", i, i))
+            .collect();
+        fs::write(temp_dir.path().join("huge.rs"), huge).unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "pub fn hello() {}\n").unwrap();
+
+        let mut code_bank = CodeBank {
+            timeout: Some(Duration::from_micros(1)),
+            ..CodeBank::try_new().unwrap()
+        };
+        let file_unit = code_bank
+            .parse_file(&temp_dir.path().join("huge.rs"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(file_unit.parse_errors[0].1, "parsing timed out after 1µs");
+        assert!(file_unit.functions.is_empty());
+        // The halted parser is dropped, the next file gets a new one
+        assert!(code_bank.parsers.is_empty());
+
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.per_file_timeout = Some(Duration::from_millis(20));
+        let content = code_bank.generate(&config).unwrap();
+        assert!(content.contains("pub fn hello()"));
+        assert!(!content.contains("pub fn f1("));
+    }
+
+    // A parser that fails slowly, for another reason than its timeout
+    struct SlowFailingParser;
+
+    impl LanguageParser for SlowFailingParser {
+        fn parse_file(&mut self, _file_path: &Path) -> Result<FileUnit> {
+            std::thread::sleep(Duration::from_millis(5));
+            Err(Error::Parse("unsupported encoding".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_parse_failure_after_timeout_is_not_a_timeout() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("slow.txt"), "anything").unwrap();
+
+        let mut registry = ParserRegistry::new();
+        registry.register("txt", || Ok(Box::new(SlowFailingParser)));
+        let mut code_bank = CodeBank {
+            timeout: Some(Duration::from_micros(1)),
+            ..CodeBank::with_registry(registry)
+        };
+        let error = code_bank
+            .parse_file(&temp_dir.path().join("slow.txt"))
+            .unwrap_err();
+        assert!(matches!(error, Error::Parse(message) if message == "unsupported encoding"));
+    }
+
+    // A parser with a bug that cuts signatures at the first `:`
+    struct TruncatingParser;
+
+    impl LanguageParser for TruncatingParser {
+        fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
+            let source = fs::read_to_string(file_path)?;
+            let (head, body) = source.split_once('{').unwrap();
+            let mut file_unit = FileUnit::new(file_path.to_path_buf());
+            file_unit.functions.push(FunctionUnit {
+                name: "add".to_string(),
+                visibility: Visibility::Public,
+                signature: head.split(':').next().map(str::to_string),
+                body: Some(format!("{{{}", body.trim_end())),
+                source: Some(source),
+                ..Default::default()
+            });
+            Ok(file_unit)
+        }
+    }
+
+    #[test]
+    fn test_strict_signatures() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("add.txt"),
+            "fn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "pub fn hello(name: &str) {}\n",
+        )
+        .unwrap();
+
+        let mut registry = ParserRegistry::new();
+        registry.register("txt", || Ok(Box::new(TruncatingParser)));
+        let code_bank = CodeBank::with_registry(registry);
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.statistics = true;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(!output.contains("Parse errors"));
+
+        config.strict_signatures = true;
+        let file_units = code_bank.collect_file_units(&config, |_| true).unwrap();
+        assert_eq!(file_units[0].parse_errors.len(), 1);
+        assert_eq!(
+            file_units[0].parse_errors[0].1,
+            "signature of `add` differs from its source: `fn add(a` instead of `fn add(a: u32, b: u32) -> u32`"
+        );
+        assert!(file_units[1].parse_errors.is_empty());
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("Parse errors: 1"));
+    }
+
+    #[test]
+    fn test_malformed_input_does_not_panic() {
+        let inputs = [
+            "",
+            "\0\u{feff}}}}{{{",
+            "\"\"\"",
+            "impl<T: for<'a> Fn(&'a",
+            "pub(in ) struct é { é: é }",
+            "#[deprecated = \"",
+            "/**",
+            "typedef unsigned int café;",
+            "class A:\n  def __init__(self):\n    self.=\n",
+            "namespace ; class { public é é { get",
+            "func (",
+            "export default class<",
+            "<?php namespace { class é { public function",
+        ];
+        let mut sources = BTreeMap::new();
+        for ext in ["rs", "py", "ts", "cpp", "h", "go", "cs", "php"] {
+            for (i, input) in inputs.iter().enumerate() {
+                sources.insert(PathBuf::from(format!("f{}.{}", i, ext)), input.to_string());
+            }
+        }
+
+        let code_bank = CodeBank::try_new().unwrap();
+        for strategy in [
+            BankStrategy::Default,
+            BankStrategy::NoTests,
+            BankStrategy::Summary,
+        ] {
+            assert!(code_bank.generate_from_sources(&sources, strategy).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_trait_implementor_links() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(
+            src.join("shape.rs"),
+            "pub trait Shape {\n    fn area(&self) -> f64;\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("circle.rs"),
+            "pub struct Circle(f64);\n\nimpl crate::shape::Shape for Circle {\n    fn area(&self) -> f64 {\n        self.0 * self.0 * 3.14\n    }\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("square.rs"),
+            "pub struct Square(f64);\n\nimpl Shape for Square {\n    fn area(&self) -> f64 {\n        self.0 * self.0\n    }\n}\n\nimpl Square {\n    pub fn new(side: f64) -> Self {\n        Self(side)\n    }\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        let content = code_bank.generate(&config).unwrap();
+
+        assert!(content.contains(
+            "Implementors of `Shape`:\n\
+             - [`impl crate::shape::Shape for Circle`](#srccirclers)\n\
+             - [`impl Shape for Square`](#srcsquarers)\n"
+        ));
+        assert!(content.contains(
+            "Trait implementations:\n- `impl Shape for Square` implements [`Shape`](#srcshapers)\n"
+        ));
+        assert!(content.contains(
+            "- `impl crate::shape::Shape for Circle` implements [`Shape`](#srcshapers)\n"
+        ));
+        // Inherent impls have no trait to link to
+        assert!(!content.contains("`impl Square` implements"));
+    }
+
+    #[test]
+    fn test_derived_and_explicit_impls_are_not_duplicated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "#[derive(Debug, Clone, PartialEq)]\npub struct Foo {\n    pub id: u32,\n}\n\nimpl Clone for Foo {\n    fn clone(&self) -> Self {\n        Self { id: self.id }\n    }\n}\n\n#[derive(Clone)]\npub struct Bar {\n    pub id: u32,\n}\n\nimpl Clone for Bar {\n    fn clone(&self) -> Self {\n        Self { id: self.id }\n    }\n}\n\n#[derive(Clone)]\npub struct Baz {\n    pub id: u32,\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let content = code_bank.generate(&config).unwrap();
+
+        // The explicit impl is shown, the trait is left out of the derives
+        assert!(content.contains("#[derive(Debug, PartialEq)]\npub struct Foo"));
+        assert!(content.contains("impl Clone for Foo {\n    fn clone(&self) -> Self { ... }\n}"));
+        // A derive of only explicitly implemented traits is dropped
+        assert!(content.contains("}\npub struct Bar"));
+        assert!(content.contains("impl Clone for Bar {"));
+        // Derives without an explicit impl are kept
+        assert!(content.contains("#[derive(Clone)]\npub struct Baz"));
+    }
+
+    #[test]
+    fn test_parse_threads_output_matches_default() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        let code_bank = CodeBank::try_new().unwrap();
+
+        let mut config = BankConfig::new(&fixtures, BankStrategy::Summary, vec![]);
+        let expected = code_bank.generate(&config).unwrap();
+
+        config.parse_threads = Some(1);
+        assert_eq!(code_bank.generate(&config).unwrap(), expected);
+
+        config.parse_threads = Some(4);
+        assert_eq!(code_bank.generate(&config).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_shorten_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "pub fn index(words: &[std::string::String]) -> std::collections::HashMap<String, usize> {\n    Default::default()\n}\n\npub fn entry(map: &mut std::collections::hash_map::HashMap<u8, u8>) {}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("-> std::collections::HashMap<String, usize>"));
+
+        config.shorten_paths = true;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("pub fn index(words: &[String]) -> HashMap<String, usize>"));
+        // Only the prefix of the final segment is stripped
+        assert!(output.contains("map: &mut std::collections::hash_map::HashMap<u8, u8>"));
+
+        config.shorten_path_prefixes = Some(vec!["std::string::".to_string()]);
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("(words: &[String]) -> std::collections::HashMap<String, usize>"));
+    }
+
+    #[test]
+    fn test_module_tree() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join("sample_crate");
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(&crate_dir, BankStrategy::NoTests, vec![]);
+
+        let tree = code_bank.module_tree(&config).unwrap();
+        assert_eq!(
+            tree,
+            [
+                "crate",
+                "├── pub mod api",
+                "│   ├── pub mod v1",
+                "│   └── mod auth",
+                "├── pub(crate) mod utils",
+                "│   └── pub mod strings",
+                "├── mod internal",
+                "└── pub mod prelude",
+                "    └── pub(super) mod hidden",
+                "",
+            ]
+            .join("\n")
+        );
+
+        config.strategy = BankStrategy::Default;
+        let tree = code_bank.module_tree(&config).unwrap();
+        assert!(tree.ends_with("│   └── pub(super) mod hidden\n└── mod tests\n"));
+
+        config.root_dir = crate_dir.join("src").join("api");
+        assert!(matches!(
+            code_bank.module_tree(&config),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_public_signatures() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(
+            src_dir.join("lib.rs"),
+            "pub mod parser;\nmod internal;\n\npub fn version() -> &'static str {\n    \"1.0\"\n}\n\nfn helper() {}\n\n#[cfg(test)]\nmod tests {\n    pub fn fixture() {}\n}\n",
+        )
+        .unwrap();
+        // Files of private modules and binaries are not part of the API
+        fs::write(src_dir.join("internal.rs"), "pub fn cache() {}\n").unwrap();
+        fs::create_dir_all(src_dir.join("bin")).unwrap();
+        fs::write(src_dir.join("bin").join("cli.rs"), "pub fn run() {}\n").unwrap();
+        fs::write(
+            src_dir.join("parser.rs"),
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn parse_file(\n        &mut self,\n        path: &Path,\n    ) -> Result<Unit> {\n        todo!()\n    }\n\n    fn reset(&mut self) {}\n}\n\npub trait Parse {\n    fn parse<T: Read>(&self, input: T) -> Unit;\n}\n",
+        )
+        .unwrap();
+
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"my-parser\"\nversion = \"0.1.0\"\n\n[lib]\nname = \"other\"\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let signatures = code_bank.public_signatures(&config).unwrap();
+        assert_eq!(
+            signatures,
+            [
+                "my_parser::version() -> &'static str",
+                "my_parser::parser::Parse::parse<T: Read>(&self, input: T) -> Unit",
+                "my_parser::parser::Parser::parse_file(&mut self, path: &Path) -> Result<Unit>",
+            ]
+        );
+
+        config.crate_name = Some("mycrate".to_string());
+        let signatures = code_bank.public_signatures(&config).unwrap();
+        assert_eq!(signatures[0], "mycrate::version() -> &'static str");
+
+        // Without a manifest, the crate is named after its directory
+        fs::remove_file(temp_dir.path().join("Cargo.toml")).unwrap();
+        config.crate_name = None;
+        let crate_dir = temp_dir.path().join("my-tool");
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::rename(&src_dir, crate_dir.join("src")).unwrap();
+        config.root_dir = crate_dir;
+        let signatures = code_bank.public_signatures(&config).unwrap();
+        assert_eq!(signatures[0], "my_tool::version() -> &'static str");
+    }
+
+    #[test]
+    fn test_api_diff() {
+        let old_dir = tempfile::tempdir().unwrap();
+        let new_dir = tempfile::tempdir().unwrap();
+        for (dir, source) in [
+            (
+                &old_dir,
+                "pub fn connect(host: &str) {}\n\npub fn close() {}\n",
+            ),
+            (
+                &new_dir,
+                "pub fn connect(host: &str, port: u16) {}\n\npub fn close() {}\n",
+            ),
+        ] {
+            fs::write(dir.path().join("lib.rs"), source).unwrap();
+        }
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut old = BankConfig::new(old_dir.path(), BankStrategy::Summary, vec![]);
+        old.crate_name = Some("net".to_string());
+        let new = BankConfig {
+            root_dir: new_dir.path().to_path_buf(),
+            ..old.clone()
+        };
+        let diff = code_bank.api_diff(&old, &new).unwrap();
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        let report = diff.to_markdown();
+        assert!(report.contains(
+            "#### `net::connect`\n\n```diff\n- net::connect(host: &str)\n+ net::connect(host: &str, port: u16)\n```\n"
+        ));
+        assert!(!report.contains("close"));
+    }
+
+    #[test]
+    fn test_module_filter() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join("sample_crate");
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(&crate_dir, BankStrategy::Summary, vec![]);
+
+        config.module_filter = Some("crate::api".to_string());
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("## src/api/mod.rs"));
+        assert!(output.contains("## src/api/v1.rs"));
+        assert!(!output.contains("## src/lib.rs"));
+        assert!(!output.contains("## src/utils.rs"));
+
+        // Inline modules are pruned out of the file declaring them
+        config.module_filter = Some("utils::strings".to_string());
+        config.strategy = BankStrategy::Default;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("## src/utils.rs"));
+        assert!(output.contains("pub fn trim(s: &str) -> &str"));
+        assert!(!output.contains("## src/api"));
+
+        // Segments are compared whole
+        config.module_filter = Some("crate::ap".to_string());
+        let output = code_bank.generate(&config).unwrap();
+        assert!(!output.contains("## src/"));
+
+        let src_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut config = BankConfig::new(&src_dir, BankStrategy::Summary, vec![]);
+        config.module_filter = Some("crate::parser".to_string());
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("## parser/mod.rs"));
+        assert!(!output.contains("## bank.rs"));
+        assert!(!output.contains("pub struct CodeBank"));
+    }
+
+    #[test]
+    fn test_flatten_to_public_api() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(src_dir.join("a")).unwrap();
+        fs::write(
+            src_dir.join("lib.rs"),
+            "mod a;\n\npub use a::b::{helper as run, Deep};\n",
+        )
+        .unwrap();
+        fs::write(src_dir.join("a.rs"), "pub mod b;\n").unwrap();
+        fs::write(
+            src_dir.join("a").join("b.rs"),
+            "pub struct Deep;\n\nimpl Deep {\n    pub fn new() -> Self {\n        Deep\n    }\n}\n\npub fn helper() {}\n\npub struct Stays;\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.flatten_to_public_api = true;
+        let output = code_bank.generate(&config).unwrap();
+        let (nested, root) = output.split_once("## src/lib.rs").unwrap();
+        assert!(nested.contains("## src/a/b.rs"));
+        assert!(root.contains("pub struct Deep;"));
+        assert!(root.contains("pub fn new() -> Self"));
+        assert!(root.contains("pub fn helper()"));
+        assert!(nested.contains("pub struct Stays;"));
+        assert!(!nested.contains("struct Deep"));
+        assert!(!nested.contains("impl Deep"));
+        assert!(!nested.contains("fn helper"));
+
+        // Source layout otherwise
+        config.flatten_to_public_api = false;
+        let output = code_bank.generate(&config).unwrap();
+        let (nested, _) = output.split_once("## src/lib.rs").unwrap();
+        assert!(nested.contains("pub struct Deep;"));
+    }
+
+    #[test]
+    fn test_summary_of_empty_impls() {
+        let code_bank = CodeBank::try_new().unwrap();
+        let source = "pub struct Foo;\n\nunsafe impl Send for Foo {}\n\nimpl Copy for Foo {}\n\nimpl Foo {\n    fn private() {}\n}\n";
+        let content = code_bank
+            .render_source(source, LanguageType::Rust, BankStrategy::Summary)
+            .unwrap();
+
+        // Marker trait impls document the conformance of the type, all-private inherent
+        // impls have nothing to show
+        assert!(content.contains("unsafe impl Send for Foo {\n}"));
+        assert!(content.contains("impl Copy for Foo {\n}"));
+        assert!(!content.contains("impl Foo"));
+        assert!(!content.contains("private"));
+    }
+
+    #[test]
+    fn test_generate_go_project() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        fs::copy(fixtures.join("sample.go"), temp_dir.path().join("main.go")).unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let output = code_bank.generate(&config).unwrap();
+
+        // Parsed by the Go parser, not rendered as is
+        assert!(output.contains("## main.go\n```go\n"));
+        assert!(output.contains(
+            "// Package example is a sample Go file for testing the parser.\npackage example\n"
+        ));
+        assert!(output.contains("type Person struct{\n    // Name is the person's name\n"));
+        assert!(output.contains("type Greeter interface"));
+        assert!(output.contains("func NewPerson(name string, age int) *Person { ... }"));
+        assert!(!output.contains("return &Person"));
+    }
+
+    #[test]
+    fn test_codebankignore() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        // `.gitignore` files only apply inside a git repository
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::create_dir(root.join("api")).unwrap();
+        fs::create_dir(root.join("target")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\nlegacy.rs\nvendored.rs\n").unwrap();
+        fs::write(root.join(CODEBANK_IGNORE), "generated.rs\n!legacy.rs\n").unwrap();
+        fs::write(root.join("api").join(CODEBANK_IGNORE), "scratch.rs\n").unwrap();
+        fs::write(root.join("lib.rs"), "pub fn hello() {}\n").unwrap();
+        fs::write(root.join("generated.rs"), "pub fn generated() {}\n").unwrap();
+        fs::write(root.join("legacy.rs"), "pub fn legacy() {}\n").unwrap();
+        fs::write(root.join("vendored.rs"), "pub fn vendored() {}\n").unwrap();
+        fs::write(root.join("target").join("build.rs"), "pub fn built() {}\n").unwrap();
+        fs::write(root.join("api").join("client.rs"), "pub fn connect() {}\n").unwrap();
+        fs::write(root.join("api").join("scratch.rs"), "pub fn scratch() {}\n").unwrap();
+        fs::write(
+            root.join("api").join("generated.rs"),
+            "pub fn nested() {}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let config = BankConfig::new(root, BankStrategy::Default, vec![]);
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("pub fn hello() {}"));
+        assert!(output.contains("pub fn connect() {}"));
+        // Ignored by `.gitignore`
+        assert!(!output.contains("built()"));
+        assert!(!output.contains("vendored()"));
+        // Ignored by `.codebankignore`, including nested ones
+        assert!(!output.contains("generated()"));
+        assert!(!output.contains("nested()"));
+        assert!(!output.contains("scratch()"));
+        assert!(!output.contains(CODEBANK_IGNORE));
+        // `.codebankignore` takes precedence over `.gitignore`
+        assert!(output.contains("pub fn legacy() {}"));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("lib.rs"), "pub fn hello() {}\n").unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let config = BankConfig::new(root, BankStrategy::Summary, vec![]);
+        code_bank.validate_config(&config).unwrap();
+
+        type Update = fn(&mut BankConfig);
+        let validate = |update: Update| {
+            let mut config = config.clone();
+            update(&mut config);
+            code_bank.validate_config(&config).unwrap_err().to_string()
+        };
+        let missing = BankConfig::new(root.join("missing"), BankStrategy::Summary, vec![]);
+        assert!(matches!(
+            code_bank.validate_config(&missing),
+            Err(Error::DirectoryNotFound(_))
+        ));
+        let file = BankConfig::new(root.join("lib.rs"), BankStrategy::Summary, vec![]);
+        assert!(
+            code_bank
+                .validate_config(&file)
+                .unwrap_err()
+                .to_string()
+                .ends_with("lib.rs is not a directory")
+        );
+
+        let cases: [(Update, &str); 12] = [
+            (|c| c.max_files = Some(0), "max_files must be at least 1"),
+            (
+                |c| c.truncate_to_max_files = true,
+                "truncate_to_max_files requires max_files",
+            ),
+            (
+                |c| c.max_file_bytes = Some(0),
+                "max_file_bytes must be at least 1",
+            ),
+            (
+                |c| c.per_file_timeout = Some(Duration::ZERO),
+                "per_file_timeout must be longer than zero",
+            ),
+            (
+                |c| {
+                    c.strategy = BankStrategy::Default;
+                    c.flatten_to_public_api = true;
+                },
+                "flatten_to_public_api requires the Summary strategy",
+            ),
+            (
+                |c| {
+                    c.strategy = BankStrategy::Default;
+                    c.merge_module_files = true;
+                },
+                "merge_module_files has no effect with the Default strategy",
+            ),
+            (
+                |c| c.ignore_files = vec!["src/build.rs".to_string()],
+                "ignore_files takes file names, got `src/build.rs`",
+            ),
+            (
+                |c| {
+                    c.section_by_dir = vec![
+                        ("tests".to_string(), "Tests".to_string()),
+                        ("tests".to_string(), "More Tests".to_string()),
+                    ]
+                },
+                "section_by_dir maps `tests` twice",
+            ),
+            (
+                |c| c.exclude_items = vec!["__internal_*".to_string(), "api:Client".to_string()],
+                "exclude_items takes item names with `*` wildcards, e.g. `api::Client::*`, got `api:Client`",
+            ),
+            (
+                |c| c.exclude_items = vec!["test_[a-z]*".to_string()],
+                "exclude_items takes item names with `*` wildcards, e.g. `api::Client::*`, got `test_[a-z]*`",
+            ),
+            (
+                |c| c.module_filter = Some("crate::parser::".to_string()),
+                "module_filter must be a module path, e.g. `crate::parser`, got `crate::parser::`",
+            ),
+            (
+                |c| c.crate_name = Some(String::new()),
+                "crate_name must not be empty",
+            ),
+        ];
+        for (update, message) in cases {
+            assert_eq!(
+                validate(update),
+                format!("Invalid configuration: {}", message)
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for name in ["a", "b", "c", "d"] {
+            fs::write(
+                temp_dir.path().join(format!("{}.rs", name)),
+                format!("pub fn {}() {{}}\n", name),
+            )
+            .unwrap();
+        }
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        config.max_files = Some(4);
+        assert!(code_bank.generate(&config).is_ok());
+
+        config.max_files = Some(3);
+        let error = code_bank.generate(&config).unwrap_err();
+        assert!(matches!(error, Error::TooManyFiles { limit: 3, .. }));
+        assert!(error.to_string().contains("more than 3 files"));
+
+        config.truncate_to_max_files = true;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("pub fn a() {}"));
+        assert!(output.contains("pub fn c() {}"));
+        assert!(!output.contains("pub fn d() {}"));
+    }
+
+    #[test]
+    fn test_section_by_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for dir in ["src", "examples", "tests"] {
+            fs::create_dir(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("src").join("lib.rs"), "pub fn hello() {}\n").unwrap();
+        fs::write(root.join("examples").join("demo.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("tests").join("it.rs"), "fn it_works() {}\n").unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(root, BankStrategy::Default, vec![]);
+        config.section_by_dir = vec![
+            ("examples".to_string(), "Examples".to_string()),
+            ("tests".to_string(), "Integration Tests".to_string()),
+        ];
+        let output = code_bank.generate(&config).unwrap();
+        let position = |text: &str| output.find(text).unwrap();
+        assert!(position("# Sources\n") < position("## src/lib.rs"));
+        assert!(position("## src/lib.rs") < position("# Examples\n"));
+        assert!(position("# Examples\n") < position("## examples/demo.rs"));
+        assert!(position("## examples/demo.rs") < position("# Integration Tests\n"));
+        assert!(position("# Integration Tests\n") < position("## tests/it.rs"));
+
+        config.section_by_dir.clear();
+        let output = code_bank.generate(&config).unwrap();
+        assert!(!output.contains("# Sources"));
+        assert!(!output.contains("# Examples"));
+    }
+
+    #[test]
+    fn test_ignore_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("ffi")).unwrap();
+        fs::write(root.join("build.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("lib.rs"), "pub fn hello() {}\n").unwrap();
+        fs::write(
+            root.join("ffi").join("bindings.rs"),
+            "pub fn ffi_call() {}\n",
+        )
+        .unwrap();
+        fs::write(root.join("ffi").join("mod.rs"), "pub mod bindings;\n").unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(root, BankStrategy::Default, vec![]);
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("## build.rs"));
+        assert!(output.contains("## ffi/bindings.rs"));
+
+        config.ignore_files = vec!["build.rs".to_string(), "bindings.rs".to_string()];
+        let output = code_bank.generate(&config).unwrap();
+        assert!(!output.contains("## build.rs"));
+        assert!(!output.contains("## ffi/bindings.rs"));
+        assert!(output.contains("## lib.rs"));
+        assert!(output.contains("## ffi/mod.rs"));
+    }
+
+    #[test]
+    fn test_call_graph() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "pub mod util;\n\nfn a() {\n    b();\n}\n\nfn b() {}\n\npub struct Client;\n\nimpl Client {\n    pub fn new() -> Self {\n        util::log(\"new\");\n        Self\n    }\n\n    pub fn send(&self) {\n        self.encode();\n        a();\n        std::mem::drop(Self::new());\n    }\n\n    fn encode(&self) {}\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("util.rs"),
+            "pub fn log(message: &str) {\n    println!(\"{}\", message);\n    crate::b();\n}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn test_log() {\n        super::log(\"x\");\n    }\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.crate_name = Some("app".to_string());
+        let graph: HashMap<String, Vec<String>> =
+            code_bank.call_graph(&config).unwrap().into_iter().collect();
+
+        assert_eq!(graph["app::a"], ["app::b"]);
+        assert!(graph["app::b"].is_empty());
+        assert_eq!(graph["app::Client::new"], ["app::util::log"]);
+        assert_eq!(
+            graph["app::Client::send"],
+            ["app::Client::encode", "app::a", "app::Client::new"]
+        );
+        assert_eq!(graph["app::util::log"], ["app::b"]);
+        assert!(!graph.contains_key("app::util::tests::test_log"));
+
+        config.include_tests = Some(true);
+        let graph: HashMap<String, Vec<String>> =
+            code_bank.call_graph(&config).unwrap().into_iter().collect();
+        assert_eq!(graph["app::util::tests::test_log"], ["app::util::log"]);
+    }
+
+    #[test]
+    fn test_collapse_accessors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("user.rs"),
+            r#"pub struct User {
+    name: String,
+    email: String,
+    age: u32,
+}
+
+impl User {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn email(&self) -> String { self.email.clone() }
+
+    pub fn age(&self) -> u32 {
+        self.age
+    }
+
+    pub fn greeting(&self) -> String {
+        format!("Hello, {}", self.name)
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.collapse_accessors = true;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("    // accessors: name, email, age\n"));
+        assert!(output.contains("pub fn greeting(&self) -> String { ... }"));
+        assert!(!output.contains("pub fn age(&self)"));
+
+        config.collapse_accessors = false;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("pub fn age(&self) -> u32 { ... }"));
+        assert!(!output.contains("accessors:"));
+    }
+
+    #[test]
+    fn test_flatten_glob_reexports() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "mod prelude;\n\npub mod inline {\n    pub fn inline_fn() {}\n\n    fn hidden() {}\n}\n\npub use prelude::*;\npub use inline::*;\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("prelude.rs"),
+            "pub struct Config;\n\nimpl Config {\n    pub fn new() -> Self {\n        Config\n    }\n}\n\npub fn init() {}\n\nfn private_helper() {}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.flatten_to_public_api = true;
+        let file_units = code_bank.collect_file_units(&config, |_| true).unwrap();
+        let root = file_units
+            .iter()
+            .find(|unit| unit.path.ends_with("lib.rs"))
+            .unwrap();
+        let names: Vec<_> = root.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["init", "inline_fn"]);
+        assert_eq!(root.structs[0].name, "Config");
+        assert_eq!(root.impls[0].methods[0].name, "new");
+        assert_eq!(root.modules[1].functions[0].name, "hidden");
+
+        let prelude = file_units
+            .iter()
+            .find(|unit| unit.path.ends_with("prelude.rs"))
+            .unwrap();
+        assert_eq!(prelude.functions[0].name, "private_helper");
+        assert!(prelude.structs.is_empty() && prelude.impls.is_empty());
+
+        let output = code_bank.generate(&config).unwrap();
+        let root = output.split_once("## lib.rs").unwrap().1;
+        assert!(root.contains("pub fn init()"));
+        assert!(root.contains("pub fn new() -> Self"));
+    }
+
+    #[test]
+    fn test_min_visibility_with_no_tests() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "pub fn visible() -> u32 {\n    helper() + 1\n}\n\nfn helper() -> u32 {\n    41\n}\n\npub(crate) struct Internal;\n\npub struct Api;\n\nimpl Api {\n    pub fn call(&self) {}\n\n    fn secret(&self) {}\n}\n\nimpl Internal {\n    fn hidden(&self) {}\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::NoTests, vec![]);
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("fn helper() -> u32"));
+        assert!(output.contains("pub(crate) struct Internal"));
+
+        config.min_visibility = Some(Visibility::Public);
+        let output = code_bank.generate(&config).unwrap();
+        // Bodies of public functions are kept by NoTests
+        assert!(output.contains("pub fn visible() -> u32"));
+        assert!(output.contains("    helper() + 1\n}"));
+        assert!(output.contains("pub fn call(&self)"));
+        assert!(!output.contains("fn helper()"));
+        assert!(!output.contains("struct Internal"));
+        assert!(!output.contains("fn secret"));
+        assert!(!output.contains("impl Internal"));
+
+        config.min_visibility = Some(Visibility::Crate);
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("pub(crate) struct Internal"));
+        assert!(!output.contains("fn helper()"));
+    }
+
+    #[test]
+    fn test_exclude_items() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "pub fn run() {}\n\npub fn __internal_setup() {}\n\n#[codebank(skip)]\npub struct Scratch;\n\npub struct Client {\n    pub url: String,\n    #[codebank( skip )]\n    pub token: String,\n}\n\nimpl Client {\n    pub fn send(&self) {}\n\n    pub fn __internal_retry(&self) {}\n}\n\npub mod api {\n    pub fn connect() {}\n\n    pub fn debug_dump() {}\n}\n\n#[codebank(skip)]\nimpl Drop for Client {\n    fn drop(&mut self) {}\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let output = code_bank.generate(&config).unwrap();
+        // Items marked with `#[codebank(skip)]` are dropped without any configuration
+        assert!(output.contains("pub fn __internal_setup()"));
+        assert!(output.contains("pub url: String"));
+        assert!(!output.contains("Scratch"));
+        assert!(!output.contains("token"));
+        assert!(!output.contains("impl Drop for Client"));
+
+        config.exclude_items = vec!["__internal_*".to_string(), "api::debug_*".to_string()];
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("pub fn run()"));
+        assert!(output.contains("pub fn send(&self)"));
+        assert!(output.contains("pub fn connect()"));
+        assert!(!output.contains("__internal"));
+        assert!(!output.contains("debug_dump"));
+
+        config.exclude_items = vec!["Client::*".to_string()];
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("pub struct Client"));
+        assert!(!output.contains("pub fn send"));
+        assert!(!output.contains("url"));
+        assert!(!output.contains("impl Client"));
+    }
+
+    #[test]
+    fn test_provenance_comment() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "pub fn one() {}\n").unwrap();
+        fs::write(temp_dir.path().join("util.rs"), "pub fn two() {}\n").unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let output = code_bank.generate(&config).unwrap();
+        let provenance = format!(
+            "# Code Bank\n<!-- generated by codebank v{}; strategy=Summary; root={}; files=2 -->\n",
+            env!("CARGO_PKG_VERSION"),
+            temp_dir.path().display()
+        );
+        assert!(output.starts_with(&provenance));
+
+        config.include_provenance = false;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.starts_with("# Code Bank\n## "));
+        assert!(!output.contains("<!--"));
+    }
+
+    #[test]
+    fn test_include_file_mtime() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(&path, "pub fn one() {}\n").unwrap();
+        // 2024-05-01T12:00:00Z
+        let modified = UNIX_EPOCH + std::time::Duration::from_secs(1_714_564_800);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let output = code_bank.generate(&config).unwrap();
+        assert!(!output.contains("_modified"));
+
+        config.include_file_mtime = true;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.contains("## lib.rs\n_modified 2024-05-01_\n```rust\n"));
+    }
+
+    #[test]
+    fn test_format_date() {
+        let date = |secs| format_date(UNIX_EPOCH + std::time::Duration::from_secs(secs));
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(951_782_400), "2000-02-29");
+        assert_eq!(date(1_735_689_599), "2024-12-31");
+    }
+
+    #[test]
+    fn test_sort_items() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "use std::fmt;\n\npub struct Point;\n\nimpl fmt::Display for Point {\n    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {\n        Ok(())\n    }\n}\n\nimpl Point {\n    pub fn origin() -> Self {\n        Point\n    }\n}\n\nimpl Clone for Point {\n    fn clone(&self) -> Self {\n        Point\n    }\n}\n\npub fn zero() {}\n\npub fn one() {}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        let position = |output: &str, needle: &str| output.find(needle).unwrap();
+
+        let output = code_bank.generate(&config).unwrap();
+        assert!(position(&output, "impl fmt::Display") < position(&output, "impl Point"));
+        assert!(position(&output, "fn zero") < position(&output, "fn one"));
+
+        config.sort_items = true;
+        let output = code_bank.generate(&config).unwrap();
+        assert!(position(&output, "impl Point") < position(&output, "impl Clone for Point"));
+        assert!(
+            position(&output, "impl Clone for Point")
+                < position(&output, "impl fmt::Display for Point")
+        );
+        assert!(position(&output, "fn one") < position(&output, "fn zero"));
+    }
+
+    #[test]
+    fn test_merge_module_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("foo")).unwrap();
+        fs::write(
+            src.join("lib.rs"),
+            "pub mod foo;\n\n/// The root function.\npub fn root() {}\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("foo.rs"),
+            "//! The foo module.\n\npub mod bar;\n\n/// A foo function.\npub fn inner() {}\n",
+        )
+        .unwrap();
+        fs::write(src.join("foo/bar.rs"), "pub struct Deep;\n").unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::NoTests, vec![]);
+        config.merge_module_files = true;
+        let output = code_bank.generate(&config).unwrap();
+
+        assert_eq!(output.matches("\n## ").count(), 1);
+        assert!(output.contains("## src/lib.rs"));
+        assert!(!output.contains("pub mod foo;"));
+        assert!(output.contains(
+            "/// The foo module.\npub mod foo {\n    /// A foo function.\n    pub fn inner(){}\n    \
+             pub mod bar {\n        pub struct Deep;\n    }\n}\n"
+        ));
+
+        // Without merging each module keeps its own section
+        config.merge_module_files = false;
+        let output = code_bank.generate(&config).unwrap();
+        assert_eq!(output.matches("\n## ").count(), 3);
+    }
+
+    #[test]
+    fn test_include_tests_override() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            r#"
+/// Adds two numbers.
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(add(1, 2), 3);
+    }
+}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("test_math.py"),
+            "def test_sum():\n    assert sum([1, 2]) == 3\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        for strategy in [
+            BankStrategy::Default,
+            BankStrategy::NoTests,
+            BankStrategy::Summary,
+        ] {
+            for include_tests in [None, Some(true), Some(false)] {
+                let mut config = BankConfig::new(temp_dir.path(), strategy, vec![]);
+                config.include_tests = include_tests;
+                let output = code_bank.generate(&config).unwrap();
+
+                let expected = include_tests.unwrap_or(strategy == BankStrategy::Default);
+                let context = format!("{:?} with include_tests {:?}", strategy, include_tests);
+                assert_eq!(output.contains("fn test_add()"), expected, "{}", context);
+                assert_eq!(output.contains("mod tests"), expected, "{}", context);
+                assert_eq!(output.contains("## test_math.py"), expected, "{}", context);
+                assert!(
+                    output.contains("pub fn add(a: i32, b: i32) -> i32"),
+                    "{}",
+                    context
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_items_per_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source: String = (0..50)
+            .map(|i| format!("pub fn item_{:02}() {{}}\n\n", i))
+            .chain(std::iter::once("fn private_helper() {}\n".to_string()))
+            .collect();
+        fs::write(temp_dir.path().join("generated.rs"), source).unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.max_items_per_file = Some(10);
+        let output = code_bank.generate(&config).unwrap();
+        assert_eq!(output.matches("pub fn item_").count(), 10);
+        assert!(output.contains("pub fn item_00() { ... }\n"));
+        assert!(output.contains("pub fn item_09() { ... }\n// ... 41 more items\n```"));
+        assert!(!output.contains("item_10"));
+
+        config.max_items_per_file = None;
+        let output = code_bank.generate(&config).unwrap();
+        assert_eq!(output.matches("pub fn item_").count(), 50);
+        assert!(!output.contains("more items"));
+    }
+
+    #[test]
+    fn test_file_separator() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "pub fn one() {}\n").unwrap();
+        fs::write(temp_dir.path().join("util.rs"), "pub fn two() {}\n").unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.include_provenance = false;
+        config.file_separator = Some("\n---\n<!-- file: {path} -->\n".to_string());
+        let output = code_bank.generate(&config).unwrap();
+        assert!(output.starts_with("# Code Bank\n## lib.rs\n"));
+        assert!(output.contains("```\n---\n<!-- file: util.rs -->\n## util.rs\n"));
+        assert_eq!(output.matches("---").count(), 1);
+    }
+
+    #[test]
+    fn test_render_dispatches_on_format() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "/// Wraps a value\npub struct Wrapper<T>(T);\n\npub fn wrap<T>(value: T) -> Wrapper<T> {\n    Wrapper(value)\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+
+        let markdown = code_bank.render(&config).unwrap();
+        assert_eq!(markdown, code_bank.generate(&config).unwrap());
+        assert!(markdown.contains("## lib.rs\n```rust\n"));
+
+        config.format = BankFormat::Html;
+        let html = code_bank.render(&config).unwrap();
+        assert_eq!(html, code_bank.generate_html(&config).unwrap());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2 id=\"librs\">lib.rs</h2>"));
+        assert!(html.contains("pub fn wrap&lt;T&gt;(value: T) -&gt; Wrapper&lt;T&gt; { ... }"));
+
+        config.format = BankFormat::Json;
+        let json = code_bank.render(&config).unwrap();
+        assert_eq!(json, code_bank.generate_json(&config).unwrap());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["strategy"], "Summary");
+        assert_eq!(value["files"][0]["path"], "lib.rs");
+        assert_eq!(value["files"][0]["language"], "rust");
+        assert_eq!(value["files"][0]["unit"]["functions"][0]["name"], "wrap");
+        assert_eq!(
+            value["files"][0]["unit"]["structs"][0]["doc"],
+            "Wraps a value"
+        );
+
+        config.format = BankFormat::JsonLines;
+        let lines = code_bank.render(&config).unwrap();
+        assert_eq!(lines, code_bank.generate_json_lines(&config).unwrap());
+        assert_eq!(lines.lines().count(), 1);
+        let value: serde_json::Value = serde_json::from_str(lines.trim_end()).unwrap();
+        assert_eq!(value["path"], "lib.rs");
+        assert_eq!(value["unit"]["structs"][0]["name"], "Wrapper");
+    }
+    #[test]
+    fn test_json_with_rendered() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "/// Adds two numbers\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\nfn helper() {}\n\npub mod shapes {\n    pub struct Circle;\n\n    impl Circle {\n        pub fn area(&self) -> f64 {\n            0.0\n        }\n    }\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        config.format = BankFormat::JsonWithRendered;
+        let json = code_bank.render(&config).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["strategy"], "Default");
+
+        let unit = &value["files"][0]["unit"];
+        let add = &unit["functions"][0];
+        assert_eq!(add["name"], "add");
+        assert_eq!(add["doc"], "Adds two numbers");
+        assert_eq!(
+            add["rendered"],
+            "```rust\n/// Adds two numbers\npub fn add(a: i32, b: i32) -> i32 { ... }\n```\n"
+        );
+        // Private items are left out of the Summary
+        assert_eq!(unit["functions"][1]["name"], "helper");
+        assert_eq!(unit["functions"][1]["rendered"], "");
+
+        let shapes = &unit["modules"][0];
+        assert!(
+            shapes["rendered"]
+                .as_str()
+                .unwrap()
+                .contains("pub mod shapes {")
+        );
+        let area = &shapes["impls"][0]["methods"][0];
+        assert_eq!(
+            area["rendered"],
+            "```rust\npub fn area(&self) -> f64 { ... }\n```\n"
+        );
+
+        // Plain JSON has no rendered items
+        config.format = BankFormat::Json;
+        let value: serde_json::Value =
+            serde_json::from_str(&code_bank.render(&config).unwrap()).unwrap();
+        assert!(value["files"][0]["unit"]["functions"][0]["rendered"].is_null());
+    }
+
+    #[test]
+    fn test_details_format() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "/// Adds two numbers\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\nfn helper() {}\n\npub struct Counter {\n    count: u32,\n}\n\nimpl Counter {\n    /// Increments the counter\n    pub fn increment(&mut self) {\n        self.count += 1;\n    }\n}\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Default, vec![]);
+        config.format = BankFormat::Details;
+        let output = code_bank.render(&config).unwrap();
+
+        assert!(output.contains(
+            "#### add\n\nAdds two numbers\n\n```rust\npub fn add(a: i32, b: i32) -> i32 { ... }\n```\n\n<details>\n<summary>Implementation</summary>\n\n```rust\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```\n\n</details>\n\n"
+        ));
+        // Methods are shown in their impl, their full code on its own
+        assert!(output.contains(
+            "#### Counter::increment\n\nIncrements the counter\n\n```rust\nimpl Counter {\n    pub fn increment(&mut self) { ... }\n}\n```\n\n<details>\n<summary>Implementation</summary>\n\n```rust\npub fn increment(&mut self) {\n    self.count += 1;\n}\n```\n\n</details>\n\n"
+        ));
+        // Items left out of the Summary have no entry
+        assert!(!output.contains("helper"));
+        assert_eq!(
+            output.matches("<details>").count(),
+            output.matches("</details>").count()
+        );
+    }
+
+    #[test]
+    fn test_reference_format() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            r#"//! Client library
+
+/// Sends requests
+///
+/// Retries on failure.
+pub fn send(request: &str) -> bool {
+    true
+}
+
+fn helper() {}
+
+pub mod api {
+    /// An API client
+    pub struct Client {
+        pub url: String,
+    }
+
+    impl Client {
+        /// Connect the client
+        pub fn connect(&self) {}
+
+        fn reset(&self) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_send() {}
+}
+"#,
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.format = BankFormat::Reference;
+        let output = code_bank.render(&config).unwrap();
+        assert!(output.starts_with("# Code Bank\n\n## lib.rs\n\nClient library\n\n"));
+        assert!(output.contains(
+            "#### send\n\nSends requests\n\nRetries on failure.\n\n```rust\npub fn send(request: &str) -> bool { ... }\n```\n"
+        ));
+        assert!(output.contains("#### api::Client\n\nAn API client\n\n```rust\npub struct Client"));
+        assert!(output.contains("#### api::Client::connect\n\nConnect the client\n\n```rust\n"));
+        assert!(!output.contains("/// "));
+        assert!(!output.contains("helper"));
+        assert!(!output.contains("reset"));
+        assert!(!output.contains("test_send"));
+    }
+
+    #[test]
+    fn test_reference_deprecated_items() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            "#[deprecated(note = \"use `MAX_JOBS` instead\")]\npub const MAX_THREADS: usize = 8;\n\n#[deprecated(since = \"0.3.0\")]\npub type Handle = u64;\n",
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.format = BankFormat::Details;
+        let output = code_bank.render(&config).unwrap();
+        assert!(output.contains(
+            "#### MAX_THREADS\n\n```rust\n/// ⚠️ DEPRECATED: use `MAX_JOBS` instead\n#[deprecated(note = \"use `MAX_JOBS` instead\")]\npub const MAX_THREADS: usize;\n```\n"
+        ));
+        assert!(output.contains(
+            "<summary>Implementation</summary>\n\n```rust\n/// ⚠️ DEPRECATED: use `MAX_JOBS` instead\n"
+        ));
+        assert!(output.contains(
+            "#### Handle\n\n```rust\n/// ⚠️ DEPRECATED since 0.3.0\n#[deprecated(since = \"0.3.0\")]\npub type Handle = u64;\n```\n"
+        ));
+    }
+
+    #[test]
+    fn test_reference_escapes_prose() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.rs"),
+            r#"/// Wraps a <T> & returns it, see `Wrapper<T>`
+///
+/// ```
+/// let w = wrap::<u8>(1) && true;
+/// ```
+pub fn wrap<T>(value: T) -> Wrapper<T> {
+    Wrapper(value)
+}
+"#,
+        )
+        .unwrap();
+
+        let code_bank = CodeBank::try_new().unwrap();
+        let mut config = BankConfig::new(temp_dir.path(), BankStrategy::Summary, vec![]);
+        config.format = BankFormat::Reference;
+        let output = code_bank.render(&config).unwrap();
+        // Prose is escaped, code spans, fenced examples and code blocks are left raw
+        assert!(output.contains(
+            "#### wrap\n\nWraps a &lt;T&gt; &amp; returns it, see `Wrapper<T>`\n\n```\nlet w = wrap::<u8>(1) && true;\n```\n\n"
+        ));
+        assert!(output.contains("```rust\npub fn wrap<T>(value: T) -> Wrapper<T> { ... }\n```\n"));
+
+        assert_eq!(escape_markdown_prose("a < b && c"), "a &lt; b &amp;&amp; c");
+    }
 }
@@ -1,8 +1,9 @@
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
-use codebank::{Bank, BankConfig, BankStrategy, CodeBank};
+use codebank::{BankConfig, BankFormat, BankStrategy, CodeBank, DocMode, Visibility};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[clap(
@@ -20,6 +21,115 @@ struct Cli {
     /// Strategy to use for generating the code bank
     #[clap(short, long, value_enum, default_value_t = OutputStrategy::Default)]
     strategy: OutputStrategy,
+
+    /// Output format of the code bank
+    #[clap(short, long, value_enum, default_value_t = OutputFormat::Markdown)]
+    format: OutputFormat,
+
+    /// Collapse runs of `use` declarations into a single summary line
+    #[clap(long)]
+    collapse_imports: bool,
+
+    /// In summary mode, replace the fields of structs with more than N fields by a count
+    #[clap(long, value_name = "N")]
+    elide_large_structs: Option<usize>,
+
+    /// Number of threads used to parse files (defaults to the number of CPUs)
+    #[clap(long, value_name = "N")]
+    parse_threads: Option<usize>,
+
+    /// Replace doc comments with a `[documented]` marker on documented items
+    #[clap(long)]
+    doc_indicator: bool,
+
+    /// In summary mode, mark `const fn`s with a `// [const]` comment
+    #[clap(long)]
+    tag_const_fns: bool,
+
+    /// In summary mode, list trivial getters and setters on a single `// accessors:` line
+    #[clap(long)]
+    collapse_accessors: bool,
+
+    /// In summary mode, document items re-exported at the crate root at the crate root
+    #[clap(long)]
+    flatten_public_api: bool,
+
+    /// Sort the items of each file by name instead of keeping the source order
+    #[clap(long)]
+    sort_items: bool,
+
+    /// Include (`true`) or leave out (`false`) tests whatever the strategy
+    #[clap(long)]
+    include_tests: Option<bool>,
+
+    /// Render the files of `mod name;` modules inside the file declaring them
+    #[clap(long)]
+    merge_module_files: bool,
+
+    /// Skip the files taking longer than this many milliseconds to parse
+    #[clap(long)]
+    per_file_timeout_ms: Option<u64>,
+
+    /// Fail when there are more than this many files to parse
+    #[clap(long, value_name = "N")]
+    max_files: Option<usize>,
+
+    /// With `--max-files`, parse the first files up to the limit instead of failing
+    #[clap(long)]
+    truncate_to_max_files: bool,
+
+    /// Append documentation coverage statistics to the markdown output
+    #[clap(long)]
+    statistics: bool,
+
+    /// Shorten well-known std paths in signatures, e.g. `std::collections::HashMap` to `HashMap`
+    #[clap(long)]
+    shorten_paths: bool,
+
+    /// Print the module tree of the Rust crate instead of the code bank
+    #[clap(long)]
+    module_tree: bool,
+
+    /// Print the signatures of the public functions of the Rust crate, one per line
+    #[clap(long)]
+    public_signatures: bool,
+
+    /// Print the functions of the Rust crate with the functions each one calls, one per line
+    #[clap(long)]
+    call_graph: bool,
+
+    /// Print the changes of the public signatures from the Rust crate at this path
+    #[clap(long)]
+    api_diff: Option<PathBuf>,
+
+    /// The crate name starting the qualified names, instead of the package name
+    #[clap(long)]
+    crate_name: Option<String>,
+
+    /// Only document the Rust modules under this path, e.g. `crate::parser`
+    #[clap(long)]
+    module: Option<String>,
+
+    /// Exclude non-public items whatever the strategy
+    #[clap(long)]
+    exclude_private: bool,
+
+    /// Exclude items whose name matches this pattern, e.g. `__internal_*` (repeatable)
+    #[clap(long, value_name = "PATTERN")]
+    exclude_item: Vec<String>,
+
+    /// Group the files of a directory under a labeled section, e.g. `examples=Examples`
+    /// (repeatable)
+    #[clap(long, value_name = "DIR=LABEL", value_parser = parse_section)]
+    section: Vec<(String, String)>,
+
+    /// Skip the files with this name wherever they are, e.g. `build.rs` (repeatable)
+    #[clap(long, value_name = "NAME")]
+    ignore_file: Vec<String>,
+
+    /// Leave out the `<!-- generated by codebank ... -->` provenance comment
+    #[clap(long)]
+    no_provenance: bool,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -32,6 +142,32 @@ enum OutputStrategy {
     Summary,
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum OutputFormat {
+    /// Markdown with a code block per file
+    Markdown,
+    /// A single JSON document with the parsed units
+    Json,
+    /// One JSON object per file and line
+    JsonLines,
+    /// A single JSON document, each item with its rendered summary
+    JsonWithRendered,
+    /// A standalone HTML page
+    Html,
+    /// Markdown reference documentation with a section per item
+    Reference,
+    /// Reference documentation with each item's full code in a collapsible block
+    Details,
+}
+
+// Parse a `--section` mapping, e.g. `tests=Integration Tests`
+fn parse_section(value: &str) -> std::result::Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(dir, label)| (dir.to_string(), label.to_string()))
+        .ok_or_else(|| format!("expected DIR=LABEL, got `{}`", value))
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -45,10 +181,75 @@ fn main() -> Result<()> {
         OutputStrategy::Summary => BankStrategy::Summary,
     };
 
-    let config = BankConfig::new(cli.input, strategy, vec![]);
+    let mut config = BankConfig::new(cli.input, strategy, vec![]);
+    config.collapse_imports = cli.collapse_imports;
+    config.elide_large_structs = cli.elide_large_structs;
+    config.parse_threads = cli.parse_threads;
+    config.statistics = cli.statistics;
+    config.shorten_paths = cli.shorten_paths;
+    config.module_filter = cli.module;
+    config.exclude_items = cli.exclude_item;
+    config.ignore_files = cli.ignore_file;
+    config.tag_const_fns = cli.tag_const_fns;
+    config.collapse_accessors = cli.collapse_accessors;
+    config.flatten_to_public_api = cli.flatten_public_api;
+    config.sort_items = cli.sort_items;
+    config.include_tests = cli.include_tests;
+    config.merge_module_files = cli.merge_module_files;
+    config.per_file_timeout = cli.per_file_timeout_ms.map(Duration::from_millis);
+    config.max_files = cli.max_files;
+    config.truncate_to_max_files = cli.truncate_to_max_files;
+    config.crate_name = cli.crate_name;
+    config.include_provenance = !cli.no_provenance;
+    config.section_by_dir = cli.section;
+    if cli.exclude_private {
+        config.min_visibility = Some(Visibility::Public);
+    }
+    if cli.doc_indicator {
+        config.doc_mode = DocMode::Indicator;
+    }
+    config.format = match cli.format {
+        OutputFormat::Markdown => BankFormat::Markdown,
+        OutputFormat::Json => BankFormat::Json,
+        OutputFormat::JsonLines => BankFormat::JsonLines,
+        OutputFormat::JsonWithRendered => BankFormat::JsonWithRendered,
+        OutputFormat::Html => BankFormat::Html,
+        OutputFormat::Reference => BankFormat::Reference,
+        OutputFormat::Details => BankFormat::Details,
+    };
 
-    // Generate the code bank
-    let content = code_bank.generate(&config)?;
+    // Report configuration errors before parsing anything
+    code_bank.validate_config(&config)?;
+    let missing_dir = cli
+        .output
+        .as_deref()
+        .and_then(Path::parent)
+        .filter(|dir| !dir.as_os_str().is_empty() && !dir.is_dir());
+    if let Some(dir) = missing_dir {
+        anyhow::bail!("Output directory not found: {}", dir.display());
+    }
+
+    // Generate the code bank, or only the module tree, public signatures or API diff
+    let content = if cli.module_tree {
+        code_bank.module_tree(&config)?
+    } else if cli.public_signatures {
+        code_bank.public_signatures(&config)?.join("\n")
+    } else if cli.call_graph {
+        code_bank
+            .call_graph(&config)?
+            .into_iter()
+            .map(|(caller, callees)| format!("{} -> {}", caller, callees.join(", ")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else if let Some(old_dir) = cli.api_diff {
+        let old = BankConfig {
+            root_dir: old_dir,
+            ..config.clone()
+        };
+        code_bank.api_diff(&old, &config)?.to_markdown()
+    } else {
+        code_bank.render(&config)?
+    };
 
     // Output to file or stdout
     if let Some(output_file) = cli.output {
@@ -0,0 +1,278 @@
+use crate::module_tree::{crate_name, crate_root, module_path};
+use crate::parser::{exclude::impl_type_name, formatter::FormatterRules};
+use crate::{Error, FileUnit, FunctionUnit, ImplUnit, LanguageType, ModuleUnit, Result, TraitUnit};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+// A function of the crate and what is needed to resolve the calls in its body
+struct Caller<'a> {
+    // Qualified name, e.g. `mycrate::api::Client::send`
+    name: String,
+    // The module defining the function, e.g. `mycrate::api`
+    scope: String,
+    // The qualified type or trait of a method, e.g. `mycrate::api::Client`
+    owner: Option<String>,
+    body: Option<&'a str>,
+}
+
+// A callee as written in a call expression
+enum Callee {
+    // A plain or qualified path, e.g. `helper` or `Client::new`, without generic arguments
+    Path(Vec<String>),
+    // A method call, e.g. `self.send(..)`, with whether the receiver is `self`
+    Method { name: String, on_self: bool },
+}
+
+/// The functions and methods of the Rust crate at `root_dir`, each with the functions it
+/// calls, all qualified by the crate name and module path as in
+/// [`crate::CodeBank::public_signatures`].
+///
+/// Calls are resolved by name against the parsed functions on a best-effort basis: paths
+/// are looked up relative to the caller's module and then anywhere in the crate, method
+/// calls on `self` in the caller's type and other method calls by a unique method name.
+/// Ambiguous calls and calls outside the crate are left out. Test modules and functions
+/// are skipped unless `includes_tests` is set.
+pub(crate) fn call_graph(
+    root_dir: &Path,
+    crate_name: Option<&str>,
+    file_units: &[FileUnit],
+    includes_tests: bool,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let root_file = crate_root(root_dir, file_units)?;
+    let base_dir = root_file.parent().unwrap_or(root_dir);
+    let crate_name = crate_name.map_or_else(|| self::crate_name(&root_file), str::to_string);
+    let mut collector = Collector {
+        rules: FormatterRules::for_language(LanguageType::Rust),
+        includes_tests,
+        callers: Vec::new(),
+    };
+    for file_unit in file_units {
+        let Some(path) = module_path(base_dir, &root_file, &file_unit.path) else {
+            continue;
+        };
+        let scope = [crate_name.clone()]
+            .into_iter()
+            .chain(path)
+            .collect::<Vec<_>>()
+            .join("::");
+        collector.push(
+            &scope,
+            &file_unit.functions,
+            &file_unit.traits,
+            &file_unit.impls,
+        );
+        collector.push_modules(&scope, &file_unit.modules);
+    }
+    let callers = collector.callers;
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_rust::LANGUAGE.into())
+        .map_err(|e| Error::TreeSitter(e.to_string()))?;
+
+    let mut graph = Vec::new();
+    for caller in &callers {
+        let calls = caller
+            .body
+            .map(|body| parse_calls(&mut parser, body))
+            .unwrap_or_default();
+        let mut callees: Vec<String> = Vec::new();
+        for callee in calls {
+            if let Some(name) = resolve(&callee, caller, &callers, &crate_name)
+                .filter(|name| !callees.contains(name))
+            {
+                callees.push(name);
+            }
+        }
+        graph.push((caller.name.clone(), callees));
+    }
+    Ok(graph)
+}
+
+// Collects the functions of the crate, with their qualified names
+struct Collector<'a> {
+    rules: FormatterRules,
+    includes_tests: bool,
+    callers: Vec<Caller<'a>>,
+}
+
+impl<'a> Collector<'a> {
+    fn push(
+        &mut self,
+        scope: &str,
+        functions: &'a [FunctionUnit],
+        traits: &'a [TraitUnit],
+        impls: &'a [ImplUnit],
+    ) {
+        self.push_functions(scope, None, functions);
+        for trait_unit in traits {
+            let owner = format!("{}::{}", scope, trait_unit.name);
+            self.push_functions(scope, Some(owner), &trait_unit.methods);
+        }
+        for impl_unit in impls {
+            let owner = format!("{}::{}", scope, impl_type_name(&impl_unit.head));
+            self.push_functions(scope, Some(owner), &impl_unit.methods);
+        }
+    }
+
+    fn push_functions(
+        &mut self,
+        scope: &str,
+        owner: Option<String>,
+        functions: &'a [FunctionUnit],
+    ) {
+        for function in functions {
+            if !self.includes_tests && self.rules.is_test_function(&function.attributes) {
+                continue;
+            }
+            self.callers.push(Caller {
+                name: format!("{}::{}", owner.as_deref().unwrap_or(scope), function.name),
+                scope: scope.to_string(),
+                owner: owner.clone(),
+                body: function.body.as_deref(),
+            });
+        }
+    }
+
+    fn push_modules(&mut self, scope: &str, modules: &'a [ModuleUnit]) {
+        for module in modules {
+            if !self.includes_tests && self.rules.is_test_module(&module.name, &module.attributes) {
+                continue;
+            }
+            let scope = format!("{}::{}", scope, module.name);
+            self.push(&scope, &module.functions, &module.traits, &module.impls);
+            self.push_modules(&scope, &module.submodules);
+        }
+    }
+}
+
+// The callees of the call expressions in a function body, e.g. `{ helper(); }`
+fn parse_calls(parser: &mut Parser, body: &str) -> Vec<Callee> {
+    let mut calls = Vec::new();
+    if let Some(tree) = parser.parse(body, None) {
+        collect_calls(tree.root_node(), body.as_bytes(), &mut calls);
+    }
+    calls
+}
+
+// Collect the callees of the call expressions below `node`
+fn collect_calls(node: Node, source: &[u8], calls: &mut Vec<Callee>) {
+    if let Some(callee) = node
+        .child_by_field_name("function")
+        .filter(|_| node.kind() == "call_expression")
+        .and_then(|function| callee(function, source))
+    {
+        calls.push(callee);
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_calls(child, source, calls);
+    }
+}
+
+fn callee(function: Node, source: &[u8]) -> Option<Callee> {
+    match function.kind() {
+        "identifier" | "scoped_identifier" => {
+            let path = function.utf8_text(source).ok()?;
+            Some(Callee::Path(path_segments(path)))
+        }
+        // `parse::<T>(..)`
+        "generic_function" => callee(function.child_by_field_name("function")?, source),
+        "field_expression" => {
+            let value = function.child_by_field_name("value")?;
+            let field = function.child_by_field_name("field")?;
+            Some(Callee::Method {
+                name: field.utf8_text(source).ok()?.to_string(),
+                on_self: value.kind() == "self",
+            })
+        }
+        _ => None,
+    }
+}
+
+// The segments of a path without generic arguments, e.g. `Vec`, `new` for `Vec::<u8>::new`
+fn path_segments(path: &str) -> Vec<String> {
+    let mut plain = String::new();
+    let mut depth = 0usize;
+    for c in path.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && !c.is_whitespace() => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+        .split("::")
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Resolve a callee to the qualified name of a parsed function, if it is unambiguous
+fn resolve(
+    callee: &Callee,
+    caller: &Caller,
+    callers: &[Caller],
+    crate_name: &str,
+) -> Option<String> {
+    let exists = |name: &str| callers.iter().any(|function| function.name == name);
+    match callee {
+        Callee::Path(segments) => {
+            let (first, rest) = segments.split_first()?;
+            // Paths anchored to a known module or type
+            let anchor = match first.as_str() {
+                "crate" => Some(crate_name.to_string()),
+                "self" => Some(caller.scope.clone()),
+                "super" => caller
+                    .scope
+                    .rsplit_once("::")
+                    .map(|(parent, _)| parent.to_string()),
+                "Self" => caller.owner.clone(),
+                _ => None,
+            };
+            if let Some(anchor) = anchor {
+                let name = [anchor].into_iter().chain(rest.iter().cloned());
+                let name = name.collect::<Vec<_>>().join("::");
+                return exists(&name).then_some(name);
+            }
+
+            let path = segments.join("::");
+            let local = format!("{}::{}", caller.scope, path);
+            if exists(&local) {
+                return Some(local);
+            }
+            let suffix = format!("::{}", path);
+            unique(
+                callers
+                    .iter()
+                    .map(|function| &function.name)
+                    .filter(|name| name.ends_with(&suffix)),
+            )
+        }
+        Callee::Method { name, on_self } => {
+            if *on_self {
+                let method = caller
+                    .owner
+                    .as_ref()
+                    .map(|owner| format!("{}::{}", owner, name));
+                if let Some(method) = method.filter(|method| exists(method)) {
+                    return Some(method);
+                }
+            }
+            unique(
+                callers
+                    .iter()
+                    .filter(|function| function.owner.is_some())
+                    .map(|function| &function.name)
+                    .filter(|method| method.rsplit("::").next() == Some(name.as_str())),
+            )
+        }
+    }
+}
+
+// The single name yielded by `names`, `None` when there are none or several
+fn unique<'a>(mut names: impl Iterator<Item = &'a String>) -> Option<String> {
+    let name = names.next()?;
+    names.next().is_none().then(|| name.clone())
+}
@@ -1,5 +1,6 @@
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Error types for the CodeBank library.
@@ -39,6 +40,10 @@ pub enum Error {
     #[error("Tree-sitter error: {0}")]
     TreeSitter(String),
 
+    /// A parse stopped by the timeout set with `LanguageParser::set_timeout`
+    #[error("Parse timed out after {0:?}")]
+    ParseTimeout(Duration),
+
     /// File not found error
     #[error("File not found: {0}")]
     FileNotFound(PathBuf),
@@ -51,9 +56,27 @@ pub enum Error {
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
 
+    /// More candidate files than `BankConfig::max_files` under the root directory
+    #[error("Too many files: more than {limit} files to parse under {}", root.display())]
+    TooManyFiles {
+        /// The root directory of the walk
+        root: PathBuf,
+        /// The configured limit
+        limit: usize,
+    },
+
     /// Unsupported language error
     #[error("Unsupported language: {0}")]
     UnsupportedLanguage(String),
+
+    /// JSON serialization error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Git command failure
+    #[cfg(feature = "git")]
+    #[error("Git error: {0}")]
+    Git(String),
 }
 
 /// Result type alias for CodeBank operations.
@@ -120,8 +120,12 @@
 //! # }
 //! ```
 
+mod api_diff;
 mod bank;
+mod call_graph;
 mod error;
+mod metrics;
+mod module_tree;
 mod parser;
 
 #[cfg(feature = "mcp")]
@@ -129,23 +133,175 @@ mod mcp;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
 
-pub use bank::CodeBank;
+pub use api_diff::{ApiDiff, ChangedItem};
+pub use bank::{CODEBANK_IGNORE, CodeBank, DEFAULT_SECTION};
 pub use error::{Error, Result};
+pub use metrics::{DocCoverage, Metrics};
 pub use parser::*;
 
 #[cfg(feature = "mcp")]
 pub use mcp::CodeBankMcp;
 
 /// Configuration for generating code bank documentation.
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BankConfig {
     /// Root directory to generate code bank for.
     pub root_dir: PathBuf,
     /// Strategy for generating code bank documentation.
     pub strategy: BankStrategy,
     /// Directories to ignore.
+    ///
+    /// Files are also skipped when matched by a `.gitignore` (in a git repository),
+    /// `.ignore` or [`CODEBANK_IGNORE`] (`.codebankignore`) file in their directory or a
+    /// parent one up to `root_dir`. The latter only affects the code bank, not git.
+    /// `.codebankignore` patterns take precedence over `.ignore` ones, which take
+    /// precedence over `.gitignore` ones, e.g. `!path` in `.codebankignore` brings back a
+    /// git-ignored file, and among files of the same kind the closest to a path wins.
+    /// `ignore_dirs` is applied on top of all of them.
     pub ignore_dirs: Vec<String>,
+    /// File names to ignore wherever they are, e.g. `build.rs` or `bindings.rs`. Only the
+    /// base name of a file is matched, without wildcards.
+    #[serde(default)]
+    pub ignore_files: Vec<String>,
+    /// Collapse runs of `use` declarations into a single `// N imports (...)` line. The
+    /// imported crates are listed for Rust only, e.g. `// 2 imports` for C# `using`s.
+    #[serde(default)]
+    pub collapse_imports: bool,
+    /// Emit a section for files whose formatted output is empty (e.g. stub files).
+    #[serde(default)]
+    pub include_empty_files: bool,
+    /// File name patterns (`*` wildcards) of test files skipped by the `NoTests` and
+    /// `Summary` strategies. `None` uses the per-language defaults, e.g. `*.test.ts`
+    /// or `test_*.py`.
+    #[serde(default)]
+    pub test_file_patterns: Option<Vec<String>>,
+    /// Skip files larger than this many bytes. The size is checked before the file is read.
+    #[serde(default)]
+    pub max_file_bytes: Option<u64>,
+    /// Abort with [`Error::TooManyFiles`] once the walk finds more candidate files than this
+    /// limit, as a safety rail against running on a huge tree by mistake, e.g. `/`.
+    #[serde(default)]
+    pub max_files: Option<usize>,
+    /// With [`Self::max_files`], parse the first files by path up to the limit and warn
+    /// instead of failing.
+    #[serde(default)]
+    pub truncate_to_max_files: bool,
+    /// In `Summary`, render structs with more fields than this threshold as
+    /// `pub struct Config { /* 14 fields */ }` instead of listing every field.
+    #[serde(default)]
+    pub elide_large_structs: Option<usize>,
+    /// Number of threads used to parse files. `None` uses rayon's global pool, which
+    /// has one thread per CPU.
+    #[serde(default)]
+    pub parse_threads: Option<usize>,
+    /// Output format produced by [`CodeBank::render`].
+    #[serde(default)]
+    pub format: BankFormat,
+    /// How item documentation is rendered.
+    #[serde(default)]
+    pub doc_mode: DocMode,
+    /// Append a `## Statistics` block with documentation coverage to the markdown output.
+    #[serde(default)]
+    pub statistics: bool,
+    /// Shorten well-known paths in signatures and heads to their final segment, e.g.
+    /// `std::collections::HashMap` to `HashMap`.
+    #[serde(default)]
+    pub shorten_paths: bool,
+    /// Module prefixes stripped when `shorten_paths` is set. `None` uses
+    /// [`DEFAULT_SHORTEN_PREFIXES`].
+    #[serde(default)]
+    pub shorten_path_prefixes: Option<Vec<String>>,
+    /// Only document the Rust modules under this path, e.g. `crate::parser`. The module
+    /// path of a file is inferred from its location relative to `lib.rs` or `main.rs`.
+    #[serde(default)]
+    pub module_filter: Option<String>,
+    /// Drop items less visible than this, whatever the strategy, e.g. `Some(Public)`
+    /// with `NoTests` keeps the bodies of public functions but no private items. The
+    /// `Default` strategy renders whole files and is not affected.
+    #[serde(default)]
+    pub min_visibility: Option<Visibility>,
+    /// Drop the items whose name or qualified name matches one of these patterns, with
+    /// `*` wildcards, e.g. `__internal_*` or `api::Client::*`. Items marked with
+    /// [`SKIP_ATTRIBUTE`] are always dropped. Like `min_visibility`, this does not
+    /// affect the whole-file `Default` strategy.
+    #[serde(default)]
+    pub exclude_items: Vec<String>,
+    /// In `Summary`, mark `const fn`s with a `// [const]` comment above their signature.
+    #[serde(default)]
+    pub tag_const_fns: bool,
+    /// In `Summary`, list trivial getters and setters, whose body only reads or assigns a
+    /// field, by name on a single `// accessors: ...` line below the other methods of
+    /// their type instead of rendering each signature.
+    #[serde(default)]
+    pub collapse_accessors: bool,
+    /// Add a `<!-- generated by codebank ... -->` comment with the version, strategy,
+    /// root directory and number of files below the title of the markdown output.
+    #[serde(default = "default_include_provenance")]
+    pub include_provenance: bool,
+    /// Start each file written by [`CodeBank::generate_to_dir`] with a YAML front matter
+    /// block, as expected by static site generators such as Hugo or Jekyll.
+    #[serde(default)]
+    pub front_matter: bool,
+    /// The fields written to the front matter when `front_matter` is set.
+    #[serde(default)]
+    pub front_matter_fields: FrontMatterFields,
+    /// Whether [`CodeBank::generate_to_dir`] writes a file per source file or per module.
+    #[serde(default)]
+    pub output_granularity: Granularity,
+    /// In `Summary`, document the items re-exported by `pub use` in the crate root at
+    /// the crate root instead of in the module defining them, as consumers see them.
+    #[serde(default)]
+    pub flatten_to_public_api: bool,
+    /// Group the files of the markdown output under a `# label` heading per top-level
+    /// directory, mapping directory names to labels, e.g. `("examples", "Examples")` and
+    /// `("tests", "Integration Tests")`. Files outside the mapped directories come first,
+    /// under [`DEFAULT_SECTION`], followed by the mapped directories in the given order.
+    /// Empty keeps a single list of files.
+    #[serde(default)]
+    pub section_by_dir: Vec<(String, String)>,
+    /// Delimiter inserted between the file sections of the markdown output, with `{path}`
+    /// replaced by the path of the following file, e.g. `\n---\n<!-- file: {path} -->\n`.
+    /// Blank lines are collapsed as in the rest of the output.
+    #[serde(default)]
+    pub file_separator: Option<String>,
+    /// Compare each parsed function signature with the source before its body and report
+    /// the mismatches as parse errors, see [`FileUnit::check_signatures`].
+    #[serde(default)]
+    pub strict_signatures: bool,
+    /// Add the date each file was last modified below its markdown heading, e.g.
+    /// `_modified 2024-05-01_`. With the `git` feature, the date of the last commit
+    /// touching the file is used when there is one, for reproducible output.
+    #[serde(default)]
+    pub include_file_mtime: bool,
+    /// Sort the items of each file and inline module by name instead of keeping the
+    /// source order, see [`FileUnit::sort_items`]. The `Default` strategy is not affected.
+    #[serde(default)]
+    pub sort_items: bool,
+    /// In `Summary`, keep at most this many top-level items per file, public items first
+    /// and then by name, and note the number of omitted items, e.g. `// ... 40 more items`.
+    #[serde(default)]
+    pub max_items_per_file: Option<usize>,
+    /// Render (`Some(true)`) or leave out (`Some(false)`) test functions, modules and files
+    /// whatever the strategy. `None` keeps the strategy default: only `Default` includes
+    /// tests. Leaving them out of `Default` renders the parsed units as `NoTests` does.
+    #[serde(default)]
+    pub include_tests: Option<bool>,
+    /// Render the files of Rust modules declared by `mod name;` inside the file declaring
+    /// them, as inline modules, so that each module is presented under a single heading.
+    /// The `Default` strategy, rendering sources as written, is not affected.
+    #[serde(default)]
+    pub merge_module_files: bool,
+    /// Give up parsing a file after this duration, e.g. for pathological inputs. A file
+    /// that times out is skipped and the timeout is recorded as its parse error.
+    #[serde(default)]
+    pub per_file_timeout: Option<Duration>,
+    /// The name starting the qualified names of Rust items, e.g. `mycrate::api::Client`.
+    /// `None` reads the `package.name` of the crate's `Cargo.toml`, falling back to the
+    /// name of the crate directory.
+    #[serde(default)]
+    pub crate_name: Option<String>,
 }
 
 /// Strategy for generating code bank documentation.
@@ -241,6 +397,149 @@ pub enum BankStrategy {
     Summary,
 }
 
+/// Output format of the generated code bank.
+///
+/// The strategy applies differently depending on the format:
+///
+/// | Format             | `Default` / `NoTests` / `Summary`                                  |
+/// |--------------------|--------------------------------------------------------------------|
+/// | `Markdown`         | Rendered code is filtered and summarized by the strategy           |
+/// | `Html`             | Same rendering as `Markdown`, in `<pre>` blocks                    |
+/// | `Reference`        | Same filtering as `Markdown`, one entry per item with its doc as   |
+/// |                    | prose above its code                                               |
+/// | `Details`          | `Reference` entries with the `Summary` code, the full code follows |
+/// |                    | in a `<details>` block                                             |
+/// | `Json`             | Full parsed units; the strategy only skips test files and is       |
+/// |                    | carried as metadata                                                |
+/// | `JsonLines`        | One parsed file per line; the strategy only skips test files       |
+/// | `JsonWithRendered` | Same as `Json`, each item also carries its `Summary` markdown      |
+/// |                    | as `rendered`, whatever the strategy                               |
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{BankConfig, BankFormat, BankStrategy, CodeBank, Result};
+///
+/// # fn main() -> Result<()> {
+/// let code_bank = CodeBank::try_new()?;
+/// let mut config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+/// config.format = BankFormat::Json;
+///
+/// let content = code_bank.render(&config)?;
+/// assert!(content.starts_with('{'));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BankFormat {
+    /// Markdown with one section and code block per file.
+    #[default]
+    Markdown,
+    /// A single JSON document with the parsed units of every file.
+    Json,
+    /// One JSON object per line, one line per file.
+    JsonLines,
+    /// Same as `Json`, with the `Summary` of each item rendered as a markdown code block in
+    /// its `rendered` field, e.g. for a frontend showing both the structure and the code.
+    JsonWithRendered,
+    /// A standalone HTML page with one section per file.
+    Html,
+    /// Markdown reference documentation: each item gets a `####` heading with its
+    /// qualified name, e.g. `api::Client::send`, its doc as prose and a code block.
+    Reference,
+    /// Reference documentation showing the `Summary` of each item, with its full code
+    /// below in a collapsible `<details>` block, whatever the strategy.
+    Details,
+}
+
+/// How item documentation is rendered.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{BankConfig, BankStrategy, DocMode};
+///
+/// let mut config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+/// assert_eq!(config.doc_mode, DocMode::Full);
+///
+/// // Only flag documented items with a `// [documented]` marker
+/// config.doc_mode = DocMode::Indicator;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocMode {
+    /// Render doc comments in full.
+    #[default]
+    Full,
+    /// Replace each doc comment with a single `[documented]` marker comment. Undocumented
+    /// items get no marker.
+    Indicator,
+}
+
+/// How the output of [`CodeBank::generate_to_dir`] is split into files.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{BankConfig, BankStrategy, Granularity};
+///
+/// let mut config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+/// assert_eq!(config.output_granularity, Granularity::File);
+///
+/// // One page per top-level module of the crate
+/// config.output_granularity = Granularity::Module;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    /// One markdown file per source file, e.g. `src/api/client.rs.md`.
+    #[default]
+    File,
+    /// One markdown file per top-level module of a Rust crate, e.g. `api.md` holding
+    /// `src/api.rs` and the files of its submodules, and `<crate name>.md` for the crate
+    /// root. Files outside the module tree, such as tests or other languages, keep a file
+    /// of their own.
+    Module,
+}
+
+/// The fields of the YAML front matter written by [`CodeBank::generate_to_dir`].
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{BankConfig, BankStrategy, FrontMatterFields};
+///
+/// let mut config = BankConfig::new("src", BankStrategy::Summary, vec![]);
+/// config.front_matter = true;
+/// // Only keep the title and language
+/// config.front_matter_fields = FrontMatterFields {
+///     item_counts: false,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrontMatterFields {
+    /// `title`, the path of the file relative to the root directory.
+    pub title: bool,
+    /// `language`, e.g. `rust`.
+    pub language: bool,
+    /// `functions`, `structs`, `traits` and `modules`, the number of public items of
+    /// each kind, including items in nested modules.
+    pub item_counts: bool,
+}
+
+impl Default for FrontMatterFields {
+    fn default() -> Self {
+        Self {
+            title: true,
+            language: true,
+            item_counts: true,
+        }
+    }
+}
+
 /// Trait to generate a code bank for a given directory.
 ///
 /// This trait is implemented by code bank generators to process source code
@@ -343,6 +642,50 @@ impl BankConfig {
             root_dir: root_dir.into(),
             strategy,
             ignore_dirs,
+            ignore_files: Vec::new(),
+            collapse_imports: false,
+            include_empty_files: false,
+            test_file_patterns: None,
+            max_file_bytes: None,
+            max_files: None,
+            truncate_to_max_files: false,
+            elide_large_structs: None,
+            parse_threads: None,
+            format: BankFormat::Markdown,
+            doc_mode: DocMode::Full,
+            statistics: false,
+            shorten_paths: false,
+            shorten_path_prefixes: None,
+            module_filter: None,
+            min_visibility: None,
+            exclude_items: Vec::new(),
+            tag_const_fns: false,
+            collapse_accessors: false,
+            include_provenance: true,
+            front_matter: false,
+            front_matter_fields: FrontMatterFields::default(),
+            output_granularity: Granularity::File,
+            flatten_to_public_api: false,
+            section_by_dir: Vec::new(),
+            file_separator: None,
+            strict_signatures: false,
+            include_file_mtime: false,
+            sort_items: false,
+            max_items_per_file: None,
+            include_tests: None,
+            merge_module_files: false,
+            per_file_timeout: None,
+            crate_name: None,
         }
     }
 }
+
+impl Default for BankConfig {
+    fn default() -> Self {
+        Self::new(PathBuf::new(), BankStrategy::default(), Vec::new())
+    }
+}
+
+fn default_include_provenance() -> bool {
+    true
+}
@@ -8,6 +8,9 @@ use rmcp::{
 use std::fs;
 use std::path::PathBuf;
 
+// The most files a tool call parses, as the path is supplied by the client
+const MAX_FILES: usize = 10_000;
+
 /// CodeBank MCP server implementation
 #[derive(Debug, Clone)]
 pub struct CodeBankMcp;
@@ -98,7 +101,11 @@ impl CodeBankMcp {
             }
         };
 
-        let config = BankConfig::new(path, bank_strategy, vec![]);
+        let mut config = BankConfig::new(path, bank_strategy, vec![]);
+        config.max_files = Some(MAX_FILES);
+        if let Err(e) = codebank.validate_config(&config) {
+            return Err(invalid_argument_error(e.to_string()));
+        }
         let content = match codebank.generate(&config) {
             Ok(content) => content,
             Err(e) => {
@@ -149,7 +156,11 @@ impl CodeBankMcp {
             }
         };
 
-        let config = BankConfig::new(path, bank_strategy, vec![]);
+        let mut config = BankConfig::new(path, bank_strategy, vec![]);
+        config.max_files = Some(MAX_FILES);
+        if let Err(e) = codebank.validate_config(&config) {
+            return Err(invalid_argument_error(e.to_string()));
+        }
         let content = match codebank.generate(&config) {
             Ok(content) => content,
             Err(e) => {
@@ -0,0 +1,218 @@
+use crate::{
+    CodeUnit, FileUnit, ImplUnit, ModuleUnit, StructUnit, TraitUnit, UnitKind, Visibility,
+};
+use serde::Serialize;
+
+/// Documentation coverage of one kind of public item.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::DocCoverage;
+///
+/// let coverage = DocCoverage { documented: 3, total: 4 };
+/// assert_eq!(coverage.percentage(), Some(75.0));
+/// assert_eq!(DocCoverage::default().percentage(), None);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DocCoverage {
+    /// Number of public items with a doc comment
+    pub documented: usize,
+    /// Number of public items
+    pub total: usize,
+}
+
+impl DocCoverage {
+    /// The percentage of documented items, `None` when there are no items
+    pub fn percentage(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.documented as f64 * 100.0 / self.total as f64)
+    }
+
+    fn record(&mut self, unit: &dyn CodeUnit) {
+        self.total += 1;
+        if unit.doc().is_some_and(|doc| !doc.trim().is_empty()) {
+            self.documented += 1;
+        }
+    }
+}
+
+/// Metrics computed over the parsed files of a code bank.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{FileUnit, FunctionUnit, Metrics, Visibility};
+/// use std::path::PathBuf;
+///
+/// let mut file = FileUnit::new(PathBuf::from("lib.rs"));
+/// file.functions.push(FunctionUnit {
+///     name: "run".to_string(),
+///     visibility: Visibility::Public,
+///     doc: Some("Runs the task".to_string()),
+///     ..Default::default()
+/// });
+///
+/// let metrics = Metrics::from_file_units(&[file]);
+/// assert_eq!(metrics.files, 1);
+/// assert_eq!(metrics.functions.percentage(), Some(100.0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Metrics {
+    /// Number of parsed files
+    pub files: usize,
+    /// Number of syntax errors found while parsing, see [`FileUnit::parse_errors`]
+    pub parse_errors: usize,
+    /// Documentation coverage of public functions
+    pub functions: DocCoverage,
+    /// Documentation coverage of public methods, in impl blocks and in public types and
+    /// traits
+    pub methods: DocCoverage,
+    /// Documentation coverage of public structs and enums
+    pub structs: DocCoverage,
+    /// Documentation coverage of public traits
+    pub traits: DocCoverage,
+    /// Documentation coverage of public modules
+    pub modules: DocCoverage,
+}
+
+impl Metrics {
+    /// Compute the metrics of the given files, including items in nested modules
+    pub fn from_file_units(file_units: &[FileUnit]) -> Self {
+        let mut metrics = Self::default();
+        for file_unit in file_units {
+            metrics.files += 1;
+            metrics.parse_errors += file_unit.parse_errors.len();
+            metrics.record_units(&file_unit.functions);
+            metrics.record_units(&file_unit.structs);
+            metrics.record_units(&file_unit.traits);
+            metrics.record_methods(&file_unit.structs, &file_unit.traits, &file_unit.impls);
+            metrics.record_modules(&file_unit.modules);
+        }
+        metrics
+    }
+
+    /// Render the metrics as the `## Statistics` markdown block
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::from("## Statistics\n\n");
+        output.push_str(&format!("Files: {}\n\n", self.files));
+        if self.parse_errors > 0 {
+            output.push_str(&format!(
+                "Parse errors: {} (some items may be missing)\n\n",
+                self.parse_errors
+            ));
+        }
+        output.push_str("| Public items | Documented | Total | Coverage |\n");
+        output.push_str("|---|---:|---:|---:|\n");
+        for (label, coverage) in [
+            ("Functions", &self.functions),
+            ("Methods", &self.methods),
+            ("Structs", &self.structs),
+            ("Traits", &self.traits),
+            ("Modules", &self.modules),
+        ] {
+            let percentage = coverage
+                .percentage()
+                .map_or_else(|| "-".to_string(), |p| format!("{:.1}%", p));
+            output.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                label, coverage.documented, coverage.total, percentage
+            ));
+        }
+        output
+    }
+
+    fn record_modules(&mut self, modules: &[ModuleUnit]) {
+        for module in modules {
+            self.record(module);
+            self.record_units(&module.functions);
+            self.record_units(&module.structs);
+            self.record_units(&module.traits);
+            self.record_methods(&module.structs, &module.traits, &module.impls);
+            self.record_modules(&module.submodules);
+        }
+    }
+
+    fn record_methods(&mut self, structs: &[StructUnit], traits: &[TraitUnit], impls: &[ImplUnit]) {
+        // Methods of private types and traits are not part of the API, impl blocks have no
+        // visibility of their own
+        let methods = structs
+            .iter()
+            .filter(|struct_unit| struct_unit.visibility == Visibility::Public)
+            .flat_map(|struct_unit| &struct_unit.methods)
+            .chain(
+                traits
+                    .iter()
+                    .filter(|trait_unit| trait_unit.visibility == Visibility::Public)
+                    .flat_map(|trait_unit| &trait_unit.methods),
+            )
+            .chain(impls.iter().flat_map(|impl_unit| &impl_unit.methods));
+        for method in methods.filter(|method| method.visibility == Visibility::Public) {
+            self.methods.record(method);
+        }
+    }
+
+    fn record_units<T: CodeUnit>(&mut self, units: &[T]) {
+        for unit in units {
+            self.record(unit);
+        }
+    }
+
+    fn record(&mut self, unit: &dyn CodeUnit) {
+        if unit.visibility() != &Visibility::Public {
+            return;
+        }
+        let coverage = match unit.kind() {
+            UnitKind::Function => &mut self.functions,
+            UnitKind::Struct | UnitKind::Enum => &mut self.structs,
+            UnitKind::Trait => &mut self.traits,
+            UnitKind::Module => &mut self.modules,
+            _ => return,
+        };
+        coverage.record(unit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LanguageParser, RustParser};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_doc_coverage_metrics() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join("sample_doc_coverage.rs");
+        let file_unit = RustParser::try_new().unwrap().parse_file(&path).unwrap();
+
+        let metrics = Metrics::from_file_units(&[file_unit]);
+        assert_eq!(metrics.files, 1);
+        assert_eq!(metrics.parse_errors, 0);
+        assert_eq!(
+            metrics.functions,
+            DocCoverage {
+                documented: 3,
+                total: 4
+            }
+        );
+        assert_eq!(metrics.functions.percentage(), Some(75.0));
+        // Public methods of impl blocks and public traits, private methods are left out
+        assert_eq!(
+            metrics.methods,
+            DocCoverage {
+                documented: 2,
+                total: 3
+            }
+        );
+        assert_eq!(metrics.structs.percentage(), Some(50.0));
+        assert_eq!(metrics.traits.percentage(), Some(0.0));
+        assert_eq!(metrics.modules.percentage(), Some(50.0));
+
+        let markdown = metrics.to_markdown();
+        assert!(markdown.starts_with("## Statistics\n"));
+        assert!(markdown.contains("| Functions | 3 | 4 | 75.0% |"));
+        assert!(markdown.contains("| Methods | 2 | 3 | 66.7% |"));
+        assert!(markdown.contains("| Traits | 0 | 1 | 0.0% |"));
+        assert!(!markdown.contains("Parse errors"));
+    }
+}
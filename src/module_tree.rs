@@ -0,0 +1,744 @@
+use crate::parser::{exclude::impl_type_name, visibility_from_modifier};
+use crate::{
+    DeclareKind, DeclareStatements, Error, FileUnit, FunctionUnit, ImplUnit, ModuleUnit, Result,
+    StructUnit, TraitUnit, Visibility, parse_use_tree,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// Crate root files, relative to the root directory, in lookup order
+const CRATE_ROOTS: &[&str] = &["src/lib.rs", "lib.rs", "src/main.rs", "main.rs"];
+
+/// A module in the module hierarchy of a Rust crate.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ModuleNode {
+    name: String,
+    // Visibility as written, e.g. `pub(crate)`, empty for private modules
+    visibility: String,
+    children: Vec<ModuleNode>,
+}
+
+impl ModuleNode {
+    /// Build the module tree of the crate rooted at `root_dir` from its parsed files.
+    ///
+    /// Modules are found through `mod name;` declarations, resolved to `name.rs` or
+    /// `name/mod.rs` next to the declaring module, and inline `mod name { ... }` blocks.
+    /// `include_module` decides whether an inline module is shown, e.g. to hide tests.
+    pub(crate) fn build(
+        root_dir: &Path,
+        file_units: &[FileUnit],
+        include_module: impl Fn(&ModuleUnit) -> bool,
+    ) -> Result<Self> {
+        let root_file = crate_root(root_dir, file_units)?;
+        let base_dir = root_file.parent().unwrap_or(root_dir);
+
+        // Index the files by their module path relative to the crate root
+        let files: HashMap<Vec<String>, &FileUnit> = file_units
+            .iter()
+            .filter_map(|unit| Some((module_path(base_dir, &root_file, &unit.path)?, unit)))
+            .collect();
+
+        let mut root = Self {
+            name: "crate".to_string(),
+            ..Default::default()
+        };
+        if let Some(file_unit) = files.get(&Vec::new()) {
+            root.children = file_children(file_unit, &[], &files, &include_module);
+        }
+        Ok(root)
+    }
+
+    /// Render the tree with one module per line, e.g. `├── pub mod api`
+    pub(crate) fn render(&self) -> String {
+        let mut output = format!("{}\n", self.name);
+        self.render_children("", &mut output);
+        output
+    }
+
+    fn render_children(&self, prefix: &str, output: &mut String) {
+        for (i, child) in self.children.iter().enumerate() {
+            let last = i + 1 == self.children.len();
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            output.push_str(prefix);
+            output.push_str(branch);
+            if !child.visibility.is_empty() {
+                output.push_str(&child.visibility);
+                output.push(' ');
+            }
+            output.push_str(&format!("mod {}\n", child.name));
+            child.render_children(&format!("{}{}", prefix, indent), output);
+        }
+    }
+
+    // Add the paths of the `pub` modules below this one at `path`, which are public when
+    // this module is, stopping at the first module that is not `pub`
+    fn public_paths(&self, path: &[String], paths: &mut HashSet<Vec<String>>) {
+        for child in self
+            .children
+            .iter()
+            .filter(|child| child.visibility == "pub")
+        {
+            let child_path = [path, std::slice::from_ref(&child.name)].concat();
+            child.public_paths(&child_path, paths);
+            paths.insert(child_path);
+        }
+    }
+}
+
+/// Keep only the items of the Rust files whose module path starts with `filter`, e.g.
+/// `crate::parser` or `parser`, compared segment by segment.
+///
+/// Files of nested modules are kept whole. A file declaring the filtered module inline
+/// is pruned to that module, including the source used by the `Default` strategy.
+/// Files in other languages and outside the crate are dropped.
+pub(crate) fn filter_by_module(
+    root_dir: &Path,
+    file_units: Vec<FileUnit>,
+    filter: &str,
+) -> Result<Vec<FileUnit>> {
+    let filter: Vec<&str> = filter
+        .split("::")
+        .map(str::trim)
+        .skip_while(|segment| *segment == "crate")
+        .collect();
+    let root_file = crate_root(root_dir, &file_units)?;
+    let base_dir = root_file.parent().unwrap_or(root_dir).to_path_buf();
+
+    Ok(file_units
+        .into_iter()
+        .filter_map(|mut file_unit| {
+            if file_unit.path.extension().is_none_or(|ext| ext != "rs") {
+                return None;
+            }
+            let path = module_path(&base_dir, &root_file, &file_unit.path)?;
+            if path.len() >= filter.len() {
+                return path
+                    .iter()
+                    .zip(&filter)
+                    .all(|(a, b)| a == b)
+                    .then_some(file_unit);
+            }
+            if !path.iter().zip(&filter).all(|(a, b)| a == b) {
+                return None;
+            }
+            let module = take_module(&mut file_unit.modules, &filter[path.len()..])?;
+            Some(FileUnit {
+                path: file_unit.path,
+                source: module.source.clone(),
+                modules: vec![module],
+                parse_errors: file_unit.parse_errors,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// The signatures of the `pub` functions and methods of the crate, one per entry, with
+/// their names qualified by the crate name and module path, e.g.
+/// `mycrate::api::Client::send(&self)`. Without a `crate_name`, it is read from the crate
+/// manifest, see [`crate_name`].
+///
+/// Methods are qualified by the implementing type or the trait declaring them. Items in
+/// modules that are not `pub`, such as `mod tests` or files declared by `mod internal;`,
+/// are skipped, and so are files outside the module tree of the crate, e.g. `src/bin`.
+pub(crate) fn public_signatures(
+    root_dir: &Path,
+    crate_name: Option<&str>,
+    file_units: &[FileUnit],
+) -> Result<Vec<String>> {
+    let root_file = crate_root(root_dir, file_units)?;
+    let base_dir = root_file.parent().unwrap_or(root_dir);
+    let crate_name = crate_name.map_or_else(|| self::crate_name(&root_file), str::to_string);
+
+    // The module paths of the files that are part of the public API
+    let mut public_paths = HashSet::from([Vec::new()]);
+    ModuleNode::build(root_dir, file_units, |_| true)?.public_paths(&[], &mut public_paths);
+
+    let mut signatures = Vec::new();
+    for file_unit in file_units {
+        let Some(path) = module_path(base_dir, &root_file, &file_unit.path)
+            .filter(|path| public_paths.contains(path))
+        else {
+            continue;
+        };
+        let scope = [crate_name.clone()]
+            .into_iter()
+            .chain(path)
+            .collect::<Vec<_>>()
+            .join("::");
+        push_signatures(
+            &scope,
+            &file_unit.functions,
+            &file_unit.traits,
+            &file_unit.impls,
+            &mut signatures,
+        );
+        push_module_signatures(&scope, &file_unit.modules, &mut signatures);
+    }
+    Ok(signatures)
+}
+
+fn push_module_signatures(scope: &str, modules: &[ModuleUnit], signatures: &mut Vec<String>) {
+    for module in modules {
+        if module.visibility != Visibility::Public {
+            continue;
+        }
+        let scope = format!("{}::{}", scope, module.name);
+        push_signatures(
+            &scope,
+            &module.functions,
+            &module.traits,
+            &module.impls,
+            signatures,
+        );
+        push_module_signatures(&scope, &module.submodules, signatures);
+    }
+}
+
+fn push_signatures(
+    scope: &str,
+    functions: &[FunctionUnit],
+    traits: &[TraitUnit],
+    impls: &[ImplUnit],
+    signatures: &mut Vec<String>,
+) {
+    let mut push = |scope: &str, functions: &[FunctionUnit]| {
+        for function in functions {
+            if function.visibility != Visibility::Public {
+                continue;
+            }
+            let signature = function.signature.as_deref().unwrap_or_default();
+            // Keep what follows the name: generics, parameters, return type and bounds
+            let Some(start) = signature.find(&format!("fn {}", function.name)) else {
+                continue;
+            };
+            // One line per signature, as if multi-line parameter lists were written inline
+            let rest = signature[start + 3 + function.name.len()..]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .replace("( ", "(")
+                .replace(", )", ")");
+            signatures.push(format!(
+                "{}::{}{}",
+                scope,
+                function.name,
+                rest.trim_end_matches(';')
+            ));
+        }
+    };
+
+    push(scope, functions);
+    for trait_unit in traits {
+        if trait_unit.visibility == Visibility::Public {
+            push(
+                &format!("{}::{}", scope, trait_unit.name),
+                &trait_unit.methods,
+            );
+        }
+    }
+    for impl_unit in impls {
+        let type_name = impl_type_name(&impl_unit.head);
+        push(&format!("{}::{}", scope, type_name), &impl_unit.methods);
+    }
+}
+
+/// The module path of each Rust file of the crate, starting with the crate name, e.g.
+/// `mycrate`, `api`, `client` for `src/api/client.rs`. Files outside the module tree are
+/// left out, as are all files without a crate root.
+pub(crate) fn module_paths(
+    root_dir: &Path,
+    crate_name: Option<&str>,
+    file_units: &[FileUnit],
+) -> HashMap<PathBuf, Vec<String>> {
+    let Ok(root_file) = crate_root(root_dir, file_units) else {
+        return HashMap::new();
+    };
+    let base_dir = root_file.parent().unwrap_or(root_dir);
+    let crate_name = crate_name.map_or_else(|| self::crate_name(&root_file), str::to_string);
+
+    file_units
+        .iter()
+        .filter(|file_unit| file_unit.path.extension().is_some_and(|ext| ext == "rs"))
+        .filter_map(|file_unit| {
+            let path = module_path(base_dir, &root_file, &file_unit.path)?;
+            let path = [crate_name.clone()].into_iter().chain(path).collect();
+            Some((file_unit.path.clone(), path))
+        })
+        .collect()
+}
+
+/// Move the items re-exported by `pub use` in the crate root to the crate root file, so
+/// that they are documented where consumers of the crate find them.
+///
+/// Re-exported functions, structs, enums and traits are removed from the file or inline
+/// module defining them, along with the impls of the re-exported types. A glob re-export
+/// such as `pub use prelude::*` moves all the public ones of the module. Re-exports of
+/// modules and of other crates, and the re-exports of a globbed module, are left as they
+/// are, and renamed items keep their original name. Without a crate root the files are
+/// returned unchanged.
+pub(crate) fn flatten_to_public_api(
+    root_dir: &Path,
+    mut file_units: Vec<FileUnit>,
+) -> Vec<FileUnit> {
+    let Ok(root_file) = crate_root(root_dir, &file_units) else {
+        return file_units;
+    };
+    let base_dir = root_file.parent().unwrap_or(root_dir).to_path_buf();
+    let Some(root_idx) = file_units.iter().position(|unit| unit.path == root_file) else {
+        return file_units;
+    };
+
+    let mut reexports = Vec::new();
+    for declare in &file_units[root_idx].declares {
+        if declare.kind != DeclareKind::Use {
+            continue;
+        }
+        // Modules imported by `self` have no items to move
+        if declare.source.trim().starts_with("pub use ") {
+            reexports.extend(
+                parse_use_tree(&declare.source)
+                    .into_iter()
+                    .filter(|use_path| !use_path.is_self)
+                    .map(|use_path| use_path.path),
+            );
+        }
+    }
+
+    let mut moved = MovedItems::default();
+    for path in reexports {
+        let path: Vec<&str> = path
+            .iter()
+            .map(String::as_str)
+            .skip_while(|segment| *segment == "crate" || *segment == "self")
+            .collect();
+        let Some((name, module)) = path.split_last() else {
+            continue;
+        };
+        if module.is_empty() || module.contains(&"super") {
+            continue;
+        }
+        // The file of the deepest module along the path, the rest is declared inline
+        let Some((idx, depth)) = file_units
+            .iter()
+            .enumerate()
+            .filter(|(_, unit)| unit.path.extension().is_some_and(|ext| ext == "rs"))
+            .filter_map(|(idx, unit)| {
+                let file_path = module_path(&base_dir, &root_file, &unit.path)?;
+                let is_prefix = file_path.len() <= module.len()
+                    && file_path.iter().zip(module).all(|(a, b)| a == b);
+                is_prefix.then_some((idx, file_path.len()))
+            })
+            .max_by_key(|(_, depth)| *depth)
+        else {
+            continue;
+        };
+        let file_unit = &mut file_units[idx];
+        if depth == module.len() {
+            moved.take(
+                name,
+                &mut file_unit.functions,
+                &mut file_unit.structs,
+                &mut file_unit.traits,
+                &mut file_unit.impls,
+            );
+        } else if let Some(inline) = find_module(&mut file_unit.modules, &module[depth..]) {
+            moved.take(
+                name,
+                &mut inline.functions,
+                &mut inline.structs,
+                &mut inline.traits,
+                &mut inline.impls,
+            );
+        }
+    }
+
+    let root = &mut file_units[root_idx];
+    root.functions.extend(moved.functions);
+    root.structs.extend(moved.structs);
+    root.traits.extend(moved.traits);
+    root.impls.extend(moved.impls);
+    file_units
+}
+
+/// Move the files of the modules declared by `mod name;` into the file declaring them, as
+/// inline modules, so that each module is presented under a single heading.
+///
+/// A module file nested in another one is merged into it first. The `mod name;`
+/// declaration is replaced by the module, or the items of the file are added to an inline
+/// `mod name { ... }` of the same name when there is one. Module files whose declaring
+/// file was not parsed, files in other languages and outside the crate are left as they
+/// are. Without a crate root the files are returned unchanged.
+pub(crate) fn merge_module_files(root_dir: &Path, file_units: Vec<FileUnit>) -> Vec<FileUnit> {
+    let Ok(root_file) = crate_root(root_dir, &file_units) else {
+        return file_units;
+    };
+    let base_dir = root_file.parent().unwrap_or(root_dir).to_path_buf();
+    let mut paths: Vec<Option<Vec<String>>> = file_units
+        .iter()
+        .map(|unit| {
+            let is_rust = unit.path.extension().is_some_and(|ext| ext == "rs");
+            is_rust
+                .then(|| module_path(&base_dir, &root_file, &unit.path))
+                .flatten()
+        })
+        .collect();
+    let mut slots: Vec<Option<FileUnit>> = file_units.into_iter().map(Some).collect();
+
+    // Deepest modules first, so that a file is complete when merged into its parent
+    let mut order: Vec<usize> = (0..slots.len())
+        .filter(|&idx| paths[idx].as_ref().is_some_and(|path| !path.is_empty()))
+        .collect();
+    order.sort_by_key(|&idx| std::cmp::Reverse(paths[idx].as_ref().map_or(0, Vec::len)));
+
+    for idx in order {
+        let Some(path) = paths[idx].clone() else {
+            continue;
+        };
+        // The file of the deepest enclosing module, the rest is declared inline
+        let Some((parent_idx, depth)) = paths
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != idx && slots[*other].is_some())
+            .filter_map(|(other, other_path)| {
+                let other_path = other_path.as_ref()?;
+                let is_prefix = other_path.len() < path.len()
+                    && other_path.iter().zip(&path).all(|(a, b)| a == b);
+                is_prefix.then_some((other, other_path.len()))
+            })
+            .max_by_key(|(_, depth)| *depth)
+        else {
+            continue;
+        };
+        let Some(file_unit) = slots[idx].take() else {
+            continue;
+        };
+        let Some(parent) = slots[parent_idx].as_mut() else {
+            continue;
+        };
+        let (name, inline_path) = path[depth..]
+            .split_last()
+            .expect("path is below its parent");
+        let inline_path: Vec<&str> = inline_path.iter().map(String::as_str).collect();
+        match merge_module_file(parent, &inline_path, name, file_unit) {
+            Some(file_unit) => slots[idx] = Some(file_unit),
+            None => paths[idx] = None,
+        }
+    }
+
+    slots.into_iter().flatten().collect()
+}
+
+// Merge the file of module `name`, declared in the inline module at `inline_path` of
+// `parent`, into it. The file is handed back when the module is not declared there.
+fn merge_module_file(
+    parent: &mut FileUnit,
+    inline_path: &[&str],
+    name: &str,
+    file_unit: FileUnit,
+) -> Option<FileUnit> {
+    let (declares, modules) = if inline_path.is_empty() {
+        (&mut parent.declares, &mut parent.modules)
+    } else {
+        match find_module(&mut parent.modules, inline_path) {
+            Some(inline) => (&mut inline.declares, &mut inline.submodules),
+            None => return Some(file_unit),
+        }
+    };
+
+    let declaration = declares.iter().position(|declare| {
+        declare.kind == DeclareKind::Mod
+            && split_mod_source(&declare.source).is_some_and(|(_, declared)| declared == name)
+    });
+    if let Some(module) = modules.iter_mut().find(|module| module.name == name) {
+        module.declares.extend(file_unit.declares);
+        module.functions.extend(file_unit.functions);
+        module.structs.extend(file_unit.structs);
+        module.traits.extend(file_unit.traits);
+        module.impls.extend(file_unit.impls);
+        module.macros.extend(file_unit.macros);
+        module.consts.extend(file_unit.consts);
+        module.type_aliases.extend(file_unit.type_aliases);
+        module.submodules.extend(file_unit.modules);
+        if module.doc.is_none() {
+            module.doc = file_unit.doc;
+        }
+    } else if let Some(declaration) = declaration {
+        let declaration = declares.remove(declaration);
+        let visibility = split_mod_source(&declaration.source)
+            .map_or(Visibility::Private, |(visibility, _)| {
+                visibility_from_modifier(visibility)
+            });
+        modules.push(ModuleUnit {
+            name: name.to_string(),
+            visibility,
+            doc: file_unit.doc,
+            declares: file_unit.declares,
+            functions: file_unit.functions,
+            structs: file_unit.structs,
+            traits: file_unit.traits,
+            impls: file_unit.impls,
+            macros: file_unit.macros,
+            consts: file_unit.consts,
+            type_aliases: file_unit.type_aliases,
+            submodules: file_unit.modules,
+            ..Default::default()
+        });
+    } else {
+        return Some(file_unit);
+    }
+    // Spans are relative to the merged file, name it in the messages
+    parent.parse_errors.extend(
+        file_unit
+            .parse_errors
+            .into_iter()
+            .map(|(span, message)| (span, format!("{}: {}", file_unit.path.display(), message))),
+    );
+    None
+}
+
+// The items removed from their definition site by `flatten_to_public_api`
+#[derive(Default)]
+struct MovedItems {
+    functions: Vec<FunctionUnit>,
+    structs: Vec<StructUnit>,
+    traits: Vec<TraitUnit>,
+    impls: Vec<ImplUnit>,
+}
+
+impl MovedItems {
+    // Take the items named `name`, or all the public items for a glob (`*`)
+    fn take(
+        &mut self,
+        name: &str,
+        functions: &mut Vec<FunctionUnit>,
+        structs: &mut Vec<StructUnit>,
+        traits: &mut Vec<TraitUnit>,
+        impls: &mut Vec<ImplUnit>,
+    ) {
+        let matches = |item: &str, visibility: &Visibility| {
+            if name == "*" {
+                *visibility == Visibility::Public
+            } else {
+                item == name
+            }
+        };
+        self.functions.extend(
+            functions.extract_if(.., |function| matches(&function.name, &function.visibility)),
+        );
+        self.traits.extend(traits.extract_if(.., |trait_unit| {
+            matches(&trait_unit.name, &trait_unit.visibility)
+        }));
+        let moved: Vec<StructUnit> = structs
+            .extract_if(.., |struct_unit| {
+                matches(&struct_unit.name, &struct_unit.visibility)
+            })
+            .collect();
+        self.impls.extend(impls.extract_if(.., |impl_unit| {
+            let type_name = impl_type_name(&impl_unit.head);
+            moved
+                .iter()
+                .any(|struct_unit| struct_unit.name == type_name)
+        }));
+        self.structs.extend(moved);
+    }
+}
+
+// The inline module at `path` below `modules`
+fn find_module<'a>(modules: &'a mut [ModuleUnit], path: &[&str]) -> Option<&'a mut ModuleUnit> {
+    let (first, rest) = path.split_first()?;
+    let module = modules.iter_mut().find(|module| module.name == *first)?;
+    if rest.is_empty() {
+        Some(module)
+    } else {
+        find_module(&mut module.submodules, rest)
+    }
+}
+
+// Find the crate root file, `lib.rs` or `main.rs`, among the parsed files
+pub(crate) fn crate_root(root_dir: &Path, file_units: &[FileUnit]) -> Result<PathBuf> {
+    CRATE_ROOTS
+        .iter()
+        .map(|root| root_dir.join(root))
+        .find(|root| file_units.iter().any(|unit| &unit.path == root))
+        .ok_or_else(|| {
+            Error::InvalidConfig(format!(
+                "no lib.rs or main.rs found in {}",
+                root_dir.display()
+            ))
+        })
+}
+
+// The name a crate is imported by, from the `package.name` of the `Cargo.toml` next to
+// the `src` directory of its root file, or else the name of that directory, with dashes
+// turned into underscores. `crate` when neither is known
+pub(crate) fn crate_name(root_file: &Path) -> String {
+    let mut crate_dir = root_file.parent().unwrap_or(Path::new(""));
+    if crate_dir.file_name().is_some_and(|name| name == "src") {
+        crate_dir = crate_dir.parent().unwrap_or(Path::new(""));
+    }
+    let manifest = std::fs::read_to_string(crate_dir.join("Cargo.toml")).ok();
+    let name = manifest
+        .as_deref()
+        .and_then(package_name)
+        .map(str::to_string)
+        .or_else(|| {
+            let dir = if crate_dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                crate_dir
+            };
+            dir.canonicalize()
+                .ok()?
+                .file_name()?
+                .to_str()
+                .map(str::to_string)
+        });
+    name.map_or_else(|| "crate".to_string(), |name| name.replace('-', "_"))
+}
+
+// The `name` of the `[package]` table of a `Cargo.toml`
+fn package_name(manifest: &str) -> Option<&str> {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if let Some(value) = line
+            .strip_prefix("name")
+            .map(str::trim_start)
+            .and_then(|rest| rest.strip_prefix('='))
+            .filter(|_| in_package)
+        {
+            return Some(value.trim().trim_matches(['"', '\'']));
+        }
+    }
+    None
+}
+
+// Remove and return the inline module at `path` below `modules`
+fn take_module(modules: &mut Vec<ModuleUnit>, path: &[&str]) -> Option<ModuleUnit> {
+    let (first, rest) = path.split_first()?;
+    let idx = modules.iter().position(|module| module.name == *first)?;
+    if rest.is_empty() {
+        Some(modules.swap_remove(idx))
+    } else {
+        take_module(&mut modules[idx].submodules, rest)
+    }
+}
+
+// The module path of a file, `None` for files outside the crate root directory
+pub(crate) fn module_path(base_dir: &Path, root_file: &Path, path: &Path) -> Option<Vec<String>> {
+    if path == root_file {
+        return Some(Vec::new());
+    }
+    let relative = path.strip_prefix(base_dir).ok()?;
+    let mut segments: Vec<String> = relative
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if segments.last().is_some_and(|last| last == "mod") {
+        segments.pop();
+    }
+    Some(segments)
+}
+
+// The modules declared or defined in a file with the given module path
+fn file_children(
+    file_unit: &FileUnit,
+    path: &[String],
+    files: &HashMap<Vec<String>, &FileUnit>,
+    include_module: &impl Fn(&ModuleUnit) -> bool,
+) -> Vec<ModuleNode> {
+    let mut children = declared_children(&file_unit.declares, path, files, include_module);
+    children.extend(inline_children(
+        &file_unit.modules,
+        path,
+        files,
+        include_module,
+    ));
+    children
+}
+
+fn declared_children(
+    declares: &[DeclareStatements],
+    path: &[String],
+    files: &HashMap<Vec<String>, &FileUnit>,
+    include_module: &impl Fn(&ModuleUnit) -> bool,
+) -> Vec<ModuleNode> {
+    declares
+        .iter()
+        .filter(|declare| declare.kind == DeclareKind::Mod)
+        .filter_map(|declare| {
+            let (visibility, name) = split_mod_source(&declare.source)?;
+            let name = name.to_string();
+            let child_path = [path, std::slice::from_ref(&name)].concat();
+            let children = files
+                .get(&child_path)
+                .map(|unit| file_children(unit, &child_path, files, include_module))
+                .unwrap_or_default();
+            Some(ModuleNode {
+                name,
+                visibility: visibility.to_string(),
+                children,
+            })
+        })
+        .collect()
+}
+
+fn inline_children(
+    modules: &[ModuleUnit],
+    path: &[String],
+    files: &HashMap<Vec<String>, &FileUnit>,
+    include_module: &impl Fn(&ModuleUnit) -> bool,
+) -> Vec<ModuleNode> {
+    modules
+        .iter()
+        .filter(|module| include_module(module))
+        .map(|module| {
+            let child_path = [path, std::slice::from_ref(&module.name)].concat();
+            let visibility = module
+                .source
+                .as_deref()
+                .and_then(split_mod_source)
+                .map_or("", |(visibility, _)| visibility);
+            // `mod name;` is parsed as a module without items, its items are in a file
+            let mut children = match files.get(&child_path) {
+                Some(unit) => file_children(unit, &child_path, files, include_module),
+                None => Vec::new(),
+            };
+            children.extend(declared_children(
+                &module.declares,
+                &child_path,
+                files,
+                include_module,
+            ));
+            children.extend(inline_children(
+                &module.submodules,
+                &child_path,
+                files,
+                include_module,
+            ));
+            ModuleNode {
+                name: module.name.clone(),
+                visibility: visibility.to_string(),
+                children,
+            }
+        })
+        .collect()
+}
+
+// Split `pub(crate) mod name;` or `pub mod name { ... }` into its visibility and name
+fn split_mod_source(source: &str) -> Option<(&str, &str)> {
+    let mod_idx = source.find("mod ")?;
+    let name = source[mod_idx + 4..]
+        .trim_start()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()
+        .filter(|name| !name.is_empty())?;
+    Some((source[..mod_idx].trim(), name))
+}
@@ -0,0 +1,139 @@
+/// A Rust attribute split into its path and arguments, see [`parse_attribute`].
+///
+/// Units keep their attributes as written; this form is computed on demand to query them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attribute {
+    /// The path of the attribute, e.g. `serde` or `tokio::main`
+    pub path: String,
+    /// The top-level arguments as written, e.g. `rename = "x"` for `#[serde(rename = "x")]`.
+    /// The value of a `#[path = value]` attribute is its single argument.
+    pub args: Vec<String>,
+}
+
+/// Parse an outer `#[...]` or inner `#![...]` attribute.
+///
+/// Arguments are split on top-level commas only, so nested lists and string literals
+/// containing commas stay whole. Returns `None` when `s` is not an attribute.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::parse_attribute;
+///
+/// let attribute = parse_attribute(r#"#[serde(rename_all = "camelCase", default)]"#).unwrap();
+/// assert_eq!(attribute.path, "serde");
+/// assert_eq!(attribute.args, [r#"rename_all = "camelCase""#, "default"]);
+///
+/// let attribute = parse_attribute(r#"#[doc = "Docs, with a comma"]"#).unwrap();
+/// assert_eq!(attribute.args, [r#""Docs, with a comma""#]);
+///
+/// assert_eq!(parse_attribute("// not an attribute"), None);
+/// ```
+pub fn parse_attribute(s: &str) -> Option<Attribute> {
+    let s = s.trim();
+    let inner = s
+        .strip_prefix("#!")
+        .or_else(|| s.strip_prefix('#'))?
+        .trim_start()
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .trim();
+
+    let path_end = inner
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .unwrap_or(inner.len());
+    let path = &inner[..path_end];
+    if path.is_empty() {
+        return None;
+    }
+
+    let rest = inner[path_end..].trim();
+    let args = if let Some(value) = rest.strip_prefix('=') {
+        vec![value.trim().to_string()]
+    } else if let Some(list) = rest
+        .strip_prefix(['(', '[', '{'])
+        .and_then(|list| list.strip_suffix([')', ']', '}']))
+    {
+        split_args(list)
+    } else if rest.is_empty() {
+        Vec::new()
+    } else {
+        return None;
+    };
+
+    Some(Attribute {
+        path: path.to_string(),
+        args,
+    })
+}
+
+// Split on the commas outside of brackets and string literals, dropping empty arguments
+pub(crate) fn split_args(list: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in list.char_indices() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                args.push(list[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(list[start..].trim().to_string());
+    args.retain(|arg| !arg.is_empty());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attribute() {
+        let derive = parse_attribute("#[derive(A, B)]").unwrap();
+        assert_eq!(derive.path, "derive");
+        assert_eq!(derive.args, ["A", "B"]);
+
+        let serde = parse_attribute(r#"#[serde(rename = "x")]"#).unwrap();
+        assert_eq!(serde.path, "serde");
+        assert_eq!(serde.args, [r#"rename = "x""#]);
+
+        let inline = parse_attribute("#[inline]").unwrap();
+        assert_eq!(inline.path, "inline");
+        assert!(inline.args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_attribute_forms() {
+        let cfg = parse_attribute("#[cfg(all(unix, feature = \"a,b\"), test,)]").unwrap();
+        assert_eq!(cfg.args, ["all(unix, feature = \"a,b\")", "test"]);
+
+        let inner = parse_attribute("#![allow(dead_code)]").unwrap();
+        assert_eq!(inner.path, "allow");
+        assert_eq!(inner.args, ["dead_code"]);
+
+        let main = parse_attribute("#[ tokio::main(flavor = \"current_thread\") ]").unwrap();
+        assert_eq!(main.path, "tokio::main");
+
+        let doc = parse_attribute("#[doc = \"A \\\"quoted\\\" doc\"]").unwrap();
+        assert_eq!(doc.path, "doc");
+        assert_eq!(doc.args, ["\"A \\\"quoted\\\" doc\""]);
+
+        assert_eq!(parse_attribute("#[]"), None);
+        assert_eq!(parse_attribute("#[derive(A)"), None);
+        assert_eq!(parse_attribute("@property"), None);
+    }
+}
@@ -0,0 +1,183 @@
+use super::attributes::parse_attribute;
+use super::formatter::matches_file_pattern;
+use super::{FileUnit, FunctionUnit, ImplUnit, ModuleUnit, StructUnit, TraitUnit};
+
+/// Attribute dropping the annotated item from the code bank, whatever the configuration.
+pub const SKIP_ATTRIBUTE: &str = "#[codebank(skip)]";
+
+impl FileUnit {
+    /// Remove the items marked with [`SKIP_ATTRIBUTE`] or matching one of `patterns`.
+    ///
+    /// Patterns use `*` wildcards and are matched against both the name of an item and
+    /// its qualified name within the file: the names of the enclosing inline modules and,
+    /// for methods, of the type, e.g. `api::Client::send`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{FileUnit, FunctionUnit};
+    /// use std::path::PathBuf;
+    ///
+    /// let mut file = FileUnit::new(PathBuf::from("lib.rs"));
+    /// for name in ["run", "__internal_setup"] {
+    ///     file.functions.push(FunctionUnit {
+    ///         name: name.to_string(),
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// file.exclude_items(&["__internal_*"]);
+    /// assert_eq!(file.functions.len(), 1);
+    /// assert_eq!(file.functions[0].name, "run");
+    /// ```
+    pub fn exclude_items<S: AsRef<str>>(&mut self, patterns: &[S]) {
+        let filter = ItemFilter { patterns };
+        filter.functions(&mut self.functions, "");
+        filter.structs(&mut self.structs, "");
+        filter.traits(&mut self.traits, "");
+        filter.impls(&mut self.impls, "");
+        self.macros
+            .retain(|m| !filter.excludes(&m.name, "", &m.attributes));
+        self.consts
+            .retain(|c| !filter.excludes(&c.name, "", &c.attributes));
+        self.type_aliases
+            .retain(|t| !filter.excludes(&t.name, "", &t.attributes));
+        filter.modules(&mut self.modules, "");
+    }
+}
+
+struct ItemFilter<'a, S> {
+    patterns: &'a [S],
+}
+
+impl<S: AsRef<str>> ItemFilter<'_, S> {
+    // Whether the item `name` declared in `scope` (empty at the top level) is dropped
+    fn excludes(&self, name: &str, scope: &str, attributes: &[String]) -> bool {
+        if attributes.iter().any(|attr| is_skip_attribute(attr)) {
+            return true;
+        }
+        let qualified = qualify(scope, name);
+        self.patterns.iter().map(AsRef::as_ref).any(|pattern| {
+            matches_file_pattern(pattern, name) || matches_file_pattern(pattern, &qualified)
+        })
+    }
+
+    fn functions(&self, functions: &mut Vec<FunctionUnit>, scope: &str) {
+        functions.retain(|f| !self.excludes(&f.name, scope, &f.attributes));
+    }
+
+    fn structs(&self, structs: &mut Vec<StructUnit>, scope: &str) {
+        structs.retain(|s| !self.excludes(&s.name, scope, &s.attributes));
+        for struct_unit in structs {
+            let scope = qualify(scope, &struct_unit.name);
+            struct_unit
+                .fields
+                .retain(|f| !self.excludes(&f.name, &scope, &f.attributes));
+            self.functions(&mut struct_unit.methods, &scope);
+        }
+    }
+
+    fn traits(&self, traits: &mut Vec<TraitUnit>, scope: &str) {
+        traits.retain(|t| !self.excludes(&t.name, scope, &t.attributes));
+        for trait_unit in traits {
+            let scope = qualify(scope, &trait_unit.name);
+            self.functions(&mut trait_unit.methods, &scope);
+        }
+    }
+
+    // Impls have no name of their own, they are dropped by the skip attribute or when
+    // all their methods are
+    fn impls(&self, impls: &mut Vec<ImplUnit>, scope: &str) {
+        impls.retain_mut(|impl_unit| {
+            if impl_unit
+                .attributes
+                .iter()
+                .any(|attr| is_skip_attribute(attr))
+            {
+                return false;
+            }
+            let had_methods = !impl_unit.methods.is_empty();
+            let scope = qualify(scope, impl_type_name(&impl_unit.head));
+            self.functions(&mut impl_unit.methods, &scope);
+            !had_methods || !impl_unit.methods.is_empty()
+        });
+    }
+
+    fn modules(&self, modules: &mut Vec<ModuleUnit>, scope: &str) {
+        modules.retain(|m| !self.excludes(&m.name, scope, &m.attributes));
+        for module in modules {
+            let scope = qualify(scope, &module.name);
+            self.functions(&mut module.functions, &scope);
+            self.structs(&mut module.structs, &scope);
+            self.traits(&mut module.traits, &scope);
+            self.impls(&mut module.impls, &scope);
+            module
+                .macros
+                .retain(|m| !self.excludes(&m.name, &scope, &m.attributes));
+            module
+                .consts
+                .retain(|c| !self.excludes(&c.name, &scope, &c.attributes));
+            module
+                .type_aliases
+                .retain(|t| !self.excludes(&t.name, &scope, &t.attributes));
+            self.modules(&mut module.submodules, &scope);
+        }
+    }
+}
+
+// Whether `pattern` can match the name or qualified name of an item: `::`-separated
+// segments with `*` wildcards, without whitespace or other glob syntax
+pub(crate) fn is_item_pattern(pattern: &str) -> bool {
+    pattern.split("::").all(|segment| {
+        !segment.is_empty()
+            && !segment.contains(|c: char| c.is_whitespace() || "?[]{}!:/".contains(c))
+    })
+}
+
+fn is_skip_attribute(attribute: &str) -> bool {
+    parse_attribute(attribute).is_some_and(|attr| attr.path == "codebank" && attr.args == ["skip"])
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", scope, name)
+    }
+}
+
+// The name of the implementing type of an impl head, without its path or generic
+// arguments, e.g. `Client` for `impl<T> fmt::Debug for api::Client<T> where T: Debug`.
+// Trait objects are named after their principal trait, `Other` for `dyn Other + Send`
+pub(crate) fn impl_type_name(head: &str) -> &str {
+    let head = head.split(" where ").next().unwrap_or(head);
+    let ty = head.rsplit(" for ").next().unwrap_or(head).trim();
+    // Inherent impls: skip `impl` and its generics
+    let ty = match ty.strip_prefix("impl") {
+        Some(rest) if rest.starts_with('<') => {
+            let mut depth = 0;
+            let end = rest
+                .char_indices()
+                .find_map(|(i, c)| {
+                    match c {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    (depth == 0).then_some(i + 1)
+                })
+                .unwrap_or(rest.len());
+            &rest[end..]
+        }
+        Some(rest) => rest,
+        None => ty,
+    };
+    let ty = ty
+        .split(['<', '+'])
+        .next()
+        .unwrap_or(ty)
+        .trim()
+        .trim_start_matches('&');
+    let ty = ty.strip_prefix("dyn ").unwrap_or(ty).trim();
+    ty.rsplit("::").next().unwrap_or(ty)
+}
@@ -1,21 +1,384 @@
 mod python;
 mod rules;
 mod rust;
-use rules::FormatterRules;
-
-use super::{FileUnit, FunctionUnit, ImplUnit, ModuleUnit, StructUnit, TraitUnit, Visibility};
+pub(crate) use rules::{FormatterRules, matches_file_pattern};
+
+use super::exclude::impl_type_name;
+use super::{
+    ConstUnit, DeclareKind, DeclareStatements, Deprecation, FieldUnit, FileUnit, FunctionUnit,
+    ImplUnit, MacroUnit, ModuleUnit, StructUnit, TraitUnit, TypeAliasUnit, Visibility,
+    parse_attribute, parse_use_tree,
+};
 use crate::parser::LanguageType;
-use crate::{BankStrategy, Result};
+use crate::{BankConfig, BankStrategy, DocMode, Result};
+use std::borrow::Cow;
+
+/// Maximum number of crate names listed in a collapsed imports line
+const MAX_COLLAPSED_CRATES: usize = 5;
+
+/// Options that fine-tune the formatted output, usually derived from a [`BankConfig`].
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{BankConfig, BankStrategy, FormatOptions};
+///
+/// let mut config = BankConfig::new(".", BankStrategy::NoTests, vec![]);
+/// config.collapse_imports = true;
+///
+/// let options = FormatOptions::from(&config);
+/// assert!(options.collapse_imports);
+/// assert!(!FormatOptions::default().collapse_imports);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Collapse runs of `use` declarations into a single summary line
+    pub collapse_imports: bool,
+    /// In `Summary`, render structs with more fields than this as a field-count placeholder
+    pub elide_large_structs: Option<usize>,
+    /// How item documentation is rendered
+    pub doc_mode: DocMode,
+    /// In `Summary`, mark compile-time evaluable functions with a `[const]` comment
+    pub tag_const_fns: bool,
+    /// Render (`Some(true)`) or leave out (`Some(false)`) tests whatever the strategy,
+    /// see [`FormatOptions::includes_tests`]
+    pub include_tests: Option<bool>,
+    /// In `Summary`, list trivial getters and setters by name on a single
+    /// `// accessors: ...` line instead of rendering their signatures
+    pub collapse_accessors: bool,
+}
+
+impl FormatOptions {
+    /// Whether test functions and modules are rendered by `strategy`: only `Default`
+    /// includes them, unless overridden by [`FormatOptions::include_tests`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{BankStrategy, FormatOptions};
+    ///
+    /// let options = FormatOptions::default();
+    /// assert!(options.includes_tests(&BankStrategy::Default));
+    /// assert!(!options.includes_tests(&BankStrategy::Summary));
+    ///
+    /// let options = FormatOptions {
+    ///     include_tests: Some(true),
+    ///     ..Default::default()
+    /// };
+    /// assert!(options.includes_tests(&BankStrategy::Summary));
+    /// ```
+    pub fn includes_tests(&self, strategy: &BankStrategy) -> bool {
+        self.include_tests
+            .unwrap_or(*strategy == BankStrategy::Default)
+    }
+}
+
+impl From<&BankConfig> for FormatOptions {
+    fn from(config: &BankConfig) -> Self {
+        Self {
+            collapse_imports: config.collapse_imports,
+            elide_large_structs: config.elide_large_structs,
+            doc_mode: config.doc_mode,
+            tag_const_fns: config.tag_const_fns,
+            include_tests: config.include_tests,
+            collapse_accessors: config.collapse_accessors,
+        }
+    }
+}
 
 pub trait Formatter {
     fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String>;
+
+    /// Format the unit with additional [`FormatOptions`].
+    ///
+    /// Units that don't support any option fall back to [`Formatter::format`].
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        _options: &FormatOptions,
+    ) -> Result<String> {
+        self.format(strategy, language)
+    }
+}
+
+// Render declarations one per line, collapsing runs of `use` declarations into a single
+// `// N imports (std, serde, ...)` line when requested
+fn format_declares(
+    declares: &[DeclareStatements],
+    language: LanguageType,
+    options: &FormatOptions,
+) -> Vec<String> {
+    if !options.collapse_imports {
+        return declares.iter().map(|decl| decl.source.clone()).collect();
+    }
+
+    let mut lines = Vec::new();
+    let mut run: Vec<&DeclareStatements> = Vec::new();
+    for decl in declares {
+        if decl.kind == DeclareKind::Use {
+            run.push(decl);
+            continue;
+        }
+        lines.extend(collapse_use_run(&run, language));
+        run.clear();
+        lines.push(decl.source.clone());
+    }
+    lines.extend(collapse_use_run(&run, language));
+    lines
+}
+
+// Collapse a run of `use` declarations; a single declaration is kept as is. The imported
+// crates are only listed for Rust, whose use trees can be decomposed.
+fn collapse_use_run(run: &[&DeclareStatements], language: LanguageType) -> Vec<String> {
+    if run.len() < 2 {
+        return run.iter().map(|decl| decl.source.clone()).collect();
+    }
+    let rules = FormatterRules::for_language(language);
+    if language != LanguageType::Rust {
+        return vec![format!("{} {} imports", rules.comment_marker, run.len())];
+    }
+
+    // The top-level crates of the imported paths, e.g. `std` and `serde` for
+    // `use {std::io, serde::Serialize};`
+    let mut crates: Vec<String> = Vec::new();
+    for use_path in run.iter().flat_map(|decl| parse_use_tree(&decl.source)) {
+        if let Some(name) = use_path
+            .path
+            .into_iter()
+            .next()
+            .filter(|name| !crates.contains(name))
+        {
+            crates.push(name);
+        }
+    }
+
+    let mut listed = crates
+        .iter()
+        .take(MAX_COLLAPSED_CRATES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if crates.len() > MAX_COLLAPSED_CRATES {
+        listed.push_str(", ...");
+    }
+    vec![format!(
+        "{} {} imports ({})",
+        rules.comment_marker,
+        run.len(),
+        listed
+    )]
+}
+
+// Render the doc block of an item, or a single `[documented]` marker in `DocMode::Indicator`
+fn format_doc(doc: Option<&str>, rules: &FormatterRules, options: &FormatOptions) -> String {
+    let Some(doc) = doc else {
+        return String::new();
+    };
+    match options.doc_mode {
+        DocMode::Full => doc
+            .lines()
+            .map(|line| format!("{} {}\n", rules.doc_marker, line))
+            .collect(),
+        DocMode::Indicator => format!("{} [documented]\n", rules.comment_marker),
+    }
+}
+
+// Split a trailing line or block comment off a single line of code, ignoring comment
+// markers inside string literals. Both parts are trimmed.
+fn split_trailing_comment<'a>(
+    source: &'a str,
+    rules: &FormatterRules,
+) -> (&'a str, Option<&'a str>) {
+    let source = source.trim_end();
+    // Single quotes delimit strings in Python, but lifetimes and chars elsewhere
+    let python = rules.comment_marker == "#";
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in source.char_indices() {
+        if let Some(open) = quote {
+            if !escaped && c == open {
+                quote = None;
+            }
+            escaped = !escaped && c == '\\';
+            continue;
+        }
+        let rest = &source[i..];
+        if c == '"' || (python && c == '\'') {
+            quote = Some(c);
+        } else if rest.starts_with(rules.comment_marker)
+            || (!python && rest.starts_with("/*") && rest.ends_with("*/"))
+        {
+            return (source[..i].trim_end(), Some(rest));
+        }
+    }
+    (source, None)
+}
+
+// Render the warning line shown above deprecated items, e.g.
+// `/// ⚠️ DEPRECATED since 0.2.0: use bar`
+fn deprecation_line(deprecation: Option<&Deprecation>, rules: &FormatterRules) -> Option<String> {
+    let deprecation = deprecation?;
+    let mut line = format!("{} ⚠️ DEPRECATED", rules.doc_marker);
+    if let Some(since) = &deprecation.since {
+        line.push_str(&format!(" since {}", since));
+    }
+    if let Some(note) = &deprecation.note {
+        line.push_str(&format!(": {}", note));
+    }
+    line.push('\n');
+    Some(line)
+}
+
+// Render the line shown above items whose result must be used, e.g.
+// `// must use: Return value should be used`
+fn must_use_line(is_must_use: bool, note: Option<&str>, rules: &FormatterRules) -> Option<String> {
+    if !is_must_use {
+        return None;
+    }
+    Some(match note {
+        Some(note) => format!("{} must use: {}\n", rules.comment_marker, note),
+        None => format!("{} must use\n", rules.comment_marker),
+    })
+}
+
+// Split off the trivial accessors of `methods` when they are collapsed in `Summary`
+fn split_accessors<'a>(
+    methods: Vec<&'a FunctionUnit>,
+    strategy: &BankStrategy,
+    rules: &FormatterRules,
+    options: &FormatOptions,
+) -> (Vec<&'a FunctionUnit>, Vec<&'a FunctionUnit>) {
+    if *strategy != BankStrategy::Summary || !options.collapse_accessors {
+        return (methods, Vec::new());
+    }
+    methods
+        .into_iter()
+        .partition(|method| !rules.is_accessor(method))
+}
+
+// Render the line listing the collapsed accessors of a type, e.g. `// accessors: name, age`
+fn accessors_line(accessors: &[&FunctionUnit], rules: &FormatterRules) -> Option<String> {
+    if accessors.is_empty() {
+        return None;
+    }
+    let names: Vec<&str> = accessors
+        .iter()
+        .map(|method| method.name.as_str())
+        .collect();
+    Some(format!(
+        "{} accessors: {}",
+        rules.comment_marker,
+        names.join(", ")
+    ))
+}
+
+// The marker put above `unsafe` functions and traits in Summary, whose safety contract
+// is easy to miss in a signature
+fn unsafe_line(rules: &FormatterRules) -> String {
+    format!("{} ⚠️ unsafe\n", rules.doc_marker)
+}
+
+// Drop the traits a type also implements with an explicit impl block from its `#[derive]`
+// attributes, so the trait is reported once, by the impl
+fn without_explicit_derives<'a>(
+    struct_unit: &'a StructUnit,
+    impls: &[ImplUnit],
+) -> Cow<'a, StructUnit> {
+    let explicit: Vec<&str> = impls
+        .iter()
+        .filter(|impl_unit| impl_type_name(&impl_unit.head) == struct_unit.name)
+        .filter_map(ImplUnit::trait_name)
+        .collect();
+    let is_explicit = |derive: &String| {
+        let name = derive.rsplit("::").next().unwrap_or(derive).trim();
+        explicit.contains(&name)
+    };
+    let derives = |attr: &String| parse_attribute(attr).filter(|attr| attr.path == "derive");
+    if !struct_unit
+        .attributes
+        .iter()
+        .filter_map(derives)
+        .any(|attr| attr.args.iter().any(is_explicit))
+    {
+        return Cow::Borrowed(struct_unit);
+    }
+
+    let mut pruned = struct_unit.clone();
+    pruned.attributes = struct_unit
+        .attributes
+        .iter()
+        .filter_map(|attr| {
+            let Some(derive) = derives(attr) else {
+                return Some(attr.clone());
+            };
+            let kept: Vec<_> = derive
+                .args
+                .iter()
+                .filter(|arg| !is_explicit(arg))
+                .map(String::as_str)
+                .collect();
+            if kept.len() == derive.args.len() {
+                Some(attr.clone())
+            } else {
+                (!kept.is_empty()).then(|| format!("#[derive({})]", kept.join(", ")))
+            }
+        })
+        .collect();
+    Cow::Owned(pruned)
+}
+
+// The line opening the body of a module, e.g. `pub mod api {`. C# and PHP namespaces have
+// no access modifiers.
+fn module_head(name: &str, visibility: &str, language: LanguageType) -> String {
+    match language {
+        LanguageType::CSharp | LanguageType::Php => format!("namespace {} {{\n", name),
+        _ => format!("{} mod {} {{\n", visibility, name),
+    }
+}
+
+// Add the declarations of a file, one per line, and its formatted modules. The package
+// clause of a Go file, parsed as a module, goes before the imports.
+fn push_declares_and_modules(
+    output: &mut String,
+    declares: Vec<String>,
+    modules: &str,
+    language: LanguageType,
+) {
+    if language == LanguageType::Go {
+        output.push_str(modules);
+    }
+    for decl in declares {
+        output.push_str(&decl);
+        output.push('\n');
+    }
+    if language != LanguageType::Go {
+        output.push_str(modules);
+    }
 }
 
 // Implement Formatter for FileUnit
 impl Formatter for FileUnit {
     fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
         let mut output = String::new();
         let rules = FormatterRules::for_language(language);
+        let tests = options.includes_tests(strategy);
+        // The source is rendered as is by `Default`, leaving its tests out takes the
+        // rendering of the units
+        let strategy = if *strategy == BankStrategy::Default && !tests {
+            &BankStrategy::NoTests
+        } else {
+            strategy
+        };
 
         match strategy {
             BankStrategy::Default => {
@@ -25,31 +388,50 @@ impl Formatter for FileUnit {
             }
             BankStrategy::NoTests => {
                 // Add file documentation if present
-                if let Some(doc) = &self.doc {
-                    output.push_str(&format!("{} {}\n", rules.doc_marker, doc));
-                }
-
-                // Add declarations
-                for decl in &self.declares {
-                    output.push_str(&decl.source);
-                    output.push('\n');
-                }
+                output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
 
                 // Format each module (skip test modules)
+                let mut modules = String::new();
                 for module in &self.modules {
-                    if !rules.is_test_module(&module.name, &module.attributes) {
-                        let formatted = module.format(strategy, language)?;
+                    if tests || !rules.is_test_module(&module.name, &module.attributes) {
+                        let formatted = module.format_with_options(strategy, language, options)?;
                         if !formatted.is_empty() {
-                            output.push_str(&formatted);
-                            output.push('\n');
+                            modules.push_str(&formatted);
+                            modules.push('\n');
                         }
                     }
                 }
 
+                push_declares_and_modules(
+                    &mut output,
+                    format_declares(&self.declares, language, options),
+                    &modules,
+                    language,
+                );
+
+                // Format each constant
+                for const_unit in &self.consts {
+                    let formatted = const_unit.format_with_options(strategy, language, options)?;
+                    if !formatted.is_empty() {
+                        output.push_str(&formatted);
+                        output.push('\n');
+                    }
+                }
+
+                // Format each type alias
+                for type_alias in &self.type_aliases {
+                    let formatted = type_alias.format_with_options(strategy, language, options)?;
+                    if !formatted.is_empty() {
+                        output.push_str(&formatted);
+                        output.push('\n');
+                    }
+                }
+
                 // Format each function (skip test functions)
                 for function in &self.functions {
-                    if !rules.is_test_function(&function.attributes) {
-                        let formatted = function.format(strategy, language)?;
+                    if tests || !rules.is_test_function(&function.attributes) {
+                        let formatted =
+                            function.format_with_options(strategy, language, options)?;
                         if !formatted.is_empty() {
                             output.push_str(&formatted);
                             output.push('\n');
@@ -59,7 +441,8 @@ impl Formatter for FileUnit {
 
                 // Format each struct
                 for struct_unit in &self.structs {
-                    let formatted = struct_unit.format(strategy, language)?;
+                    let formatted = without_explicit_derives(struct_unit, &self.impls)
+                        .format_with_options(strategy, language, options)?;
                     if !formatted.is_empty() {
                         output.push_str(&formatted);
                         output.push('\n');
@@ -68,7 +451,7 @@ impl Formatter for FileUnit {
 
                 // Format each trait
                 for trait_unit in &self.traits {
-                    let formatted = trait_unit.format(strategy, language)?;
+                    let formatted = trait_unit.format_with_options(strategy, language, options)?;
                     if !formatted.is_empty() {
                         output.push_str(&formatted);
                         output.push('\n');
@@ -77,7 +460,16 @@ impl Formatter for FileUnit {
 
                 // Format each impl
                 for impl_unit in &self.impls {
-                    let formatted = impl_unit.format(strategy, language)?;
+                    let formatted = impl_unit.format_with_options(strategy, language, options)?;
+                    if !formatted.is_empty() {
+                        output.push_str(&formatted);
+                        output.push('\n');
+                    }
+                }
+
+                // Format each macro
+                for macro_unit in &self.macros {
+                    let formatted = macro_unit.format_with_options(strategy, language, options)?;
                     if !formatted.is_empty() {
                         output.push_str(&formatted);
                         output.push('\n');
@@ -86,28 +478,54 @@ impl Formatter for FileUnit {
             }
             BankStrategy::Summary => {
                 // Add file documentation if present
-                if let Some(doc) = &self.doc {
-                    output.push_str(&format!("{} {}\n", rules.doc_marker, doc));
+                output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
+                let mut modules = String::new();
+                for module in &self.modules {
+                    if module.visibility == Visibility::Public
+                        || (tests && rules.is_test_module(&module.name, &module.attributes))
+                    {
+                        let module_formatted =
+                            module.format_with_options(strategy, language, options)?;
+                        modules.push_str(&module_formatted);
+                        modules.push('\n');
+                    }
                 }
 
-                // Add declarations
-                for decl in &self.declares {
-                    output.push_str(&decl.source);
-                    output.push('\n');
+                push_declares_and_modules(
+                    &mut output,
+                    format_declares(&self.declares, language, options),
+                    &modules,
+                    language,
+                );
+
+                // Format public constants
+                for const_unit in &self.consts {
+                    if const_unit.visibility == Visibility::Public {
+                        let const_formatted =
+                            const_unit.format_with_options(strategy, language, options)?;
+                        output.push_str(&const_formatted);
+                        output.push('\n');
+                    }
                 }
 
-                for module in &self.modules {
-                    if module.visibility == Visibility::Public {
-                        let module_formatted = module.format(strategy, language)?;
-                        output.push_str(&module_formatted);
+                // Format public type aliases
+                for type_alias in &self.type_aliases {
+                    if type_alias.visibility == Visibility::Public {
+                        let alias_formatted =
+                            type_alias.format_with_options(strategy, language, options)?;
+                        output.push_str(&alias_formatted);
                         output.push('\n');
                     }
                 }
 
-                // Format public functions
+                // Format public functions, and tests when included
                 for function in &self.functions {
-                    if function.visibility == Visibility::Public {
-                        let function_formatted = function.format(strategy, language)?;
+                    if function.visibility == Visibility::Public
+                        || (tests && rules.is_test_function(&function.attributes))
+                    {
+                        let function_formatted =
+                            function.format_with_options(strategy, language, options)?;
                         output.push_str(&function_formatted);
                         output.push('\n');
                     }
@@ -116,7 +534,8 @@ impl Formatter for FileUnit {
                 // Format public structs
                 for struct_unit in &self.structs {
                     if struct_unit.visibility == Visibility::Public {
-                        let struct_formatted = struct_unit.format(strategy, language)?;
+                        let struct_formatted = without_explicit_derives(struct_unit, &self.impls)
+                            .format_with_options(strategy, language, options)?;
                         output.push_str(&struct_formatted);
                         output.push('\n');
                     }
@@ -125,7 +544,8 @@ impl Formatter for FileUnit {
                 // Format public traits
                 for trait_unit in &self.traits {
                     if trait_unit.visibility == Visibility::Public {
-                        let trait_formatted = trait_unit.format(strategy, language)?;
+                        let trait_formatted =
+                            trait_unit.format_with_options(strategy, language, options)?;
                         output.push_str(&trait_formatted);
                         output.push('\n');
                     }
@@ -133,10 +553,28 @@ impl Formatter for FileUnit {
 
                 // Format impls (only showing public methods)
                 for impl_unit in &self.impls {
-                    let impl_formatted = impl_unit.format(strategy, language)?;
+                    let impl_formatted =
+                        impl_unit.format_with_options(strategy, language, options)?;
                     output.push_str(&impl_formatted);
                     output.push('\n');
                 }
+
+                // Format public macros
+                for macro_unit in &self.macros {
+                    if macro_unit.visibility == Visibility::Public {
+                        let macro_formatted =
+                            macro_unit.format_with_options(strategy, language, options)?;
+                        output.push_str(&macro_formatted);
+                        output.push('\n');
+                    }
+                }
+
+                if self.omitted_items > 0 {
+                    output.push_str(&format!(
+                        "{} ... {} more items\n",
+                        rules.comment_marker, self.omitted_items
+                    ));
+                }
             }
         }
 
@@ -147,50 +585,100 @@ impl Formatter for FileUnit {
 // Implement Formatter for ModuleUnit
 impl Formatter for ModuleUnit {
     fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
         let mut output = String::new();
         let rules = FormatterRules::for_language(language);
 
-        // Skip test modules entirely for Summary strategy
+        let tests = options.includes_tests(strategy);
+
+        // Skip test modules entirely for Summary strategy, or render them in full when
+        // tests are included
         if *strategy == BankStrategy::Summary && rules.is_test_module(&self.name, &self.attributes)
         {
+            if tests {
+                return self.format_with_options(&BankStrategy::NoTests, language, options);
+            }
             return Ok(String::new());
         }
 
+        let deprecation = deprecation_line(self.deprecation.as_ref(), &rules);
+
         match strategy {
             BankStrategy::Default => {
+                if let Some(line) = &deprecation {
+                    output.push_str(line);
+                }
                 if let Some(source) = &self.source {
                     output.push_str(source);
                 }
             }
+            // Go modules are the package clauses of the files, e.g. `package shapes`
+            BankStrategy::NoTests | BankStrategy::Summary if language == LanguageType::Go => {
+                output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+                output.push_str(self.source.as_deref().unwrap_or_default());
+            }
             BankStrategy::NoTests => {
-                // Add documentation
-                if let Some(doc) = &self.doc {
-                    for line in doc.lines() {
-                        output.push_str(&format!("{} {}\n", rules.doc_marker, line));
-                    }
+                if let Some(line) = &deprecation {
+                    output.push_str(line);
                 }
 
+                // Add documentation
+                output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
                 // Add attributes (including test attributes for NoTests)
                 for attr in &self.attributes {
                     output.push_str(&format!("{}\n", attr));
                 }
 
                 // Write module head
-                output.push_str(&format!(
-                    "{} mod {} {{\n",
+                output.push_str(&module_head(
+                    &self.name,
                     self.visibility.as_str(language),
-                    self.name
+                    language,
                 ));
 
                 // Add declarations
-                for decl in &self.declares {
-                    output.push_str(&format!("    {}\n", decl.source));
+                for decl in format_declares(&self.declares, language, options) {
+                    output.push_str(&format!("    {}\n", decl));
+                }
+
+                // Format all constants
+                for const_unit in &self.consts {
+                    let const_formatted =
+                        const_unit.format_with_options(strategy, language, options)?;
+                    if !const_formatted.is_empty() {
+                        output.push_str(&format!(
+                            "    {}\n\n",
+                            const_formatted.replace("\n", "\n    ")
+                        ));
+                    }
+                }
+
+                // Format all type aliases
+                for type_alias in &self.type_aliases {
+                    let alias_formatted =
+                        type_alias.format_with_options(strategy, language, options)?;
+                    if !alias_formatted.is_empty() {
+                        output.push_str(&format!(
+                            "    {}\n\n",
+                            alias_formatted.replace("\n", "\n    ")
+                        ));
+                    }
                 }
 
                 // Format all functions (skip test functions)
                 for function in &self.functions {
-                    if !rules.is_test_function(&function.attributes) {
-                        let function_formatted = function.format(strategy, language)?;
+                    if tests || !rules.is_test_function(&function.attributes) {
+                        let function_formatted =
+                            function.format_with_options(strategy, language, options)?;
                         if !function_formatted.is_empty() {
                             output.push_str(&format!(
                                 "    {}\n\n",
@@ -202,7 +690,8 @@ impl Formatter for ModuleUnit {
 
                 // Format all structs
                 for struct_unit in &self.structs {
-                    let struct_formatted = struct_unit.format(strategy, language)?;
+                    let struct_formatted = without_explicit_derives(struct_unit, &self.impls)
+                        .format_with_options(strategy, language, options)?;
                     if !struct_formatted.is_empty() {
                         output.push_str(&format!(
                             "    {}\n\n",
@@ -213,7 +702,8 @@ impl Formatter for ModuleUnit {
 
                 // Format all traits
                 for trait_unit in &self.traits {
-                    let trait_formatted = trait_unit.format(strategy, language)?;
+                    let trait_formatted =
+                        trait_unit.format_with_options(strategy, language, options)?;
                     if !trait_formatted.is_empty() {
                         output.push_str(&format!(
                             "    {}\n\n",
@@ -224,7 +714,8 @@ impl Formatter for ModuleUnit {
 
                 // Format all impls
                 for impl_unit in &self.impls {
-                    let impl_formatted = impl_unit.format(strategy, language)?;
+                    let impl_formatted =
+                        impl_unit.format_with_options(strategy, language, options)?;
                     if !impl_formatted.is_empty() {
                         output.push_str(&format!(
                             "    {}\n\n",
@@ -233,9 +724,22 @@ impl Formatter for ModuleUnit {
                     }
                 }
 
+                // Format all macros
+                for macro_unit in &self.macros {
+                    let macro_formatted =
+                        macro_unit.format_with_options(strategy, language, options)?;
+                    if !macro_formatted.is_empty() {
+                        output.push_str(&format!(
+                            "    {}\n\n",
+                            macro_formatted.replace("\n", "\n    ")
+                        ));
+                    }
+                }
+
                 // Format submodules
                 for submodule in &self.submodules {
-                    let sub_formatted = submodule.format(strategy, language)?;
+                    let sub_formatted =
+                        submodule.format_with_options(strategy, language, options)?;
                     if !sub_formatted.is_empty() {
                         output.push_str(&format!(
                             "    {}\n\n",
@@ -252,7 +756,10 @@ impl Formatter for ModuleUnit {
                     let fns: Vec<&FunctionUnit> = self
                         .functions
                         .iter()
-                        .filter(|f| f.visibility == Visibility::Public)
+                        .filter(|f| {
+                            f.visibility == Visibility::Public
+                                || (tests && rules.is_test_function(&f.attributes))
+                        })
                         .collect();
                     let structs: Vec<&StructUnit> = self
                         .structs
@@ -272,24 +779,45 @@ impl Formatter for ModuleUnit {
                     let mods: Vec<&ModuleUnit> = self
                         .submodules
                         .iter()
+                        .filter(|m| {
+                            m.visibility == Visibility::Public
+                                || (tests && rules.is_test_module(&m.name, &m.attributes))
+                        })
+                        .collect();
+                    let macros: Vec<&MacroUnit> = self
+                        .macros
+                        .iter()
                         .filter(|m| m.visibility == Visibility::Public)
                         .collect();
+                    let consts: Vec<&ConstUnit> = self
+                        .consts
+                        .iter()
+                        .filter(|c| c.visibility == Visibility::Public)
+                        .collect();
+                    let type_aliases: Vec<&TypeAliasUnit> = self
+                        .type_aliases
+                        .iter()
+                        .filter(|t| t.visibility == Visibility::Public)
+                        .collect();
 
                     if fns.is_empty()
                         && structs.is_empty()
                         && traits.is_empty()
                         && impls.is_empty()
                         && mods.is_empty()
+                        && macros.is_empty()
+                        && consts.is_empty()
+                        && type_aliases.is_empty()
                     {
                         return Ok(String::new());
                     }
 
-                    // Add documentation
-                    if let Some(doc) = &self.doc {
-                        for line in doc.lines() {
-                            output.push_str(&format!("{} {}\n", rules.doc_marker, line));
-                        }
+                    if let Some(line) = &deprecation {
+                        output.push_str(line);
                     }
+
+                    // Add documentation
+                    output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
                     // Add attributes (except test attributes)
                     for attr in &self.attributes {
                         if !rules.test_module_markers.contains(&attr.as_str()) {
@@ -297,17 +825,42 @@ impl Formatter for ModuleUnit {
                         }
                     }
 
-                    output.push_str(&format!("pub mod {} {{\n", self.name));
+                    output.push_str(&module_head(&self.name, "pub", language));
 
                     // Add declarations
-                    for decl in &self.declares {
-                        output.push_str(&format!("    {}\n", decl.source));
+                    for decl in format_declares(&self.declares, language, options) {
+                        output.push_str(&format!("    {}\n", decl));
+                    }
+
+                    // Format public constants
+                    for const_unit in &consts {
+                        let const_formatted =
+                            const_unit.format_with_options(strategy, language, options)?;
+                        if !const_formatted.is_empty() {
+                            output.push_str(&format!(
+                                "    {}\n\n",
+                                const_formatted.replace("\n", "\n    ")
+                            ));
+                        }
+                    }
+
+                    // Format public type aliases
+                    for type_alias in &type_aliases {
+                        let alias_formatted =
+                            type_alias.format_with_options(strategy, language, options)?;
+                        if !alias_formatted.is_empty() {
+                            output.push_str(&format!(
+                                "    {}\n\n",
+                                alias_formatted.replace("\n", "\n    ")
+                            ));
+                        }
                     }
 
                     // Format public functions
                     for function in &fns {
-                        if !rules.is_test_function(&function.attributes) {
-                            let function_formatted = function.format(strategy, language)?;
+                        if tests || !rules.is_test_function(&function.attributes) {
+                            let function_formatted =
+                                function.format_with_options(strategy, language, options)?;
                             if !function_formatted.is_empty() {
                                 output.push_str(&format!(
                                     "    {}\n\n",
@@ -319,7 +872,8 @@ impl Formatter for ModuleUnit {
 
                     // Format public structs
                     for struct_unit in &structs {
-                        let struct_formatted = struct_unit.format(strategy, language)?;
+                        let struct_formatted = without_explicit_derives(struct_unit, &self.impls)
+                            .format_with_options(strategy, language, options)?;
                         if !struct_formatted.is_empty() {
                             output.push_str(&format!(
                                 "    {}\n\n",
@@ -330,7 +884,8 @@ impl Formatter for ModuleUnit {
 
                     // Format public traits
                     for trait_unit in &traits {
-                        let trait_formatted = trait_unit.format(strategy, language)?;
+                        let trait_formatted =
+                            trait_unit.format_with_options(strategy, language, options)?;
                         if !trait_formatted.is_empty() {
                             output.push_str(&format!(
                                 "    {}\n\n",
@@ -341,7 +896,8 @@ impl Formatter for ModuleUnit {
 
                     // Format impls (showing public methods)
                     for impl_unit in &impls {
-                        let impl_formatted = impl_unit.format(strategy, language)?;
+                        let impl_formatted =
+                            impl_unit.format_with_options(strategy, language, options)?;
                         if !impl_formatted.is_empty() {
                             output.push_str(&format!(
                                 "    {}\n\n",
@@ -350,9 +906,22 @@ impl Formatter for ModuleUnit {
                         }
                     }
 
+                    // Format public macros
+                    for macro_unit in &macros {
+                        let macro_formatted =
+                            macro_unit.format_with_options(strategy, language, options)?;
+                        if !macro_formatted.is_empty() {
+                            output.push_str(&format!(
+                                "    {}\n\n",
+                                macro_formatted.replace("\n", "\n    ")
+                            ));
+                        }
+                    }
+
                     // Format public submodules
                     for submodule in &mods {
-                        let sub_formatted = submodule.format(strategy, language)?;
+                        let sub_formatted =
+                            submodule.format_with_options(strategy, language, options)?;
                         if !sub_formatted.is_empty() {
                             output.push_str(&format!(
                                 "    {}\n\n",
@@ -373,34 +942,58 @@ impl Formatter for ModuleUnit {
 // Implement Formatter for FunctionUnit
 impl Formatter for FunctionUnit {
     fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
         let mut output = String::new();
         let rules = FormatterRules::for_language(language);
 
+        if let Some(line) = deprecation_line(self.deprecation.as_ref(), &rules) {
+            output.push_str(&line);
+        }
+        if let Some(line) = must_use_line(self.is_must_use, self.must_use_note.as_deref(), &rules) {
+            output.push_str(&line);
+        }
+
         // Handle Default strategy separately: just return source
         if *strategy == BankStrategy::Default {
-            return Ok(self.source.clone().unwrap_or_default());
+            output.push_str(self.source.as_deref().unwrap_or_default());
+            return Ok(output);
         }
 
-        // Skip test functions for NoTests and Summary
-        if rules.is_test_function(&self.attributes) {
+        // Skip test functions (and test macros like gtest `TEST`) for NoTests and Summary,
+        // unless tests are included
+        let is_test = rules.is_test_function(&self.attributes) || rules.is_test_macro(&self.name);
+        let tests = options.includes_tests(strategy);
+        if is_test && !tests {
             return Ok(String::new());
         }
 
-        // Skip private functions for Summary
-        if *strategy == BankStrategy::Summary && self.visibility != Visibility::Public {
+        // Skip private functions for Summary, included tests are shown whatever their visibility
+        if *strategy == BankStrategy::Summary && self.visibility != Visibility::Public && !is_test {
             return Ok(String::new());
         }
 
+        if *strategy == BankStrategy::Summary && self.is_unsafe {
+            output.push_str(&unsafe_line(&rules));
+        }
+
         // Add documentation (for NoTests and Summary of non-test, non-private functions)
-        if let Some(doc) = &self.doc {
-            for line in doc.lines() {
-                output.push_str(&format!("{} {}\n", rules.doc_marker, line));
-            }
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
+        if *strategy == BankStrategy::Summary && options.tag_const_fns && self.is_const {
+            output.push_str(&format!("{} [const]\n", rules.comment_marker));
         }
 
-        // Add attributes (except test attributes)
+        // Add attributes (except test attributes, unless tests are included)
         for attr in &self.attributes {
-            if !rules.test_markers.contains(&attr.as_str()) {
+            if tests || !rules.test_markers.contains(&attr.as_str()) {
                 output.push_str(&format!("{}\n", attr));
             }
         }
@@ -451,21 +1044,34 @@ impl Formatter for FunctionUnit {
 // Implement Formatter for StructUnit
 impl Formatter for StructUnit {
     fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
         let mut output = String::new();
         let rules = FormatterRules::for_language(language);
+        let tests = options.includes_tests(strategy);
 
         // Skip private structs for Summary
         if *strategy == BankStrategy::Summary && self.visibility != Visibility::Public {
             return Ok(String::new());
         }
 
-        // Add documentation
-        if let Some(doc) = &self.doc {
-            for line in doc.lines() {
-                output.push_str(&format!("{} {}\n", rules.doc_marker, line));
-            }
+        if let Some(line) = deprecation_line(self.deprecation.as_ref(), &rules) {
+            output.push_str(&line);
+        }
+        if let Some(line) = must_use_line(self.is_must_use, self.must_use_note.as_deref(), &rules) {
+            output.push_str(&line);
         }
 
+        // Add documentation
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
         // Add attributes
         for attr in &self.attributes {
             output.push_str(&format!("{}\n", attr));
@@ -478,33 +1084,86 @@ impl Formatter for StructUnit {
                 }
             }
             BankStrategy::Summary => {
+                // Public methods, e.g. of TS and C++ classes, go inside the body
+                let methods = self
+                    .methods
+                    .iter()
+                    .filter(|method| {
+                        method.visibility == Visibility::Public
+                            && (tests || !rules.is_test_function(&method.attributes))
+                    })
+                    .collect();
+                let (methods, accessors) = split_accessors(methods, strategy, &rules, options);
+                let mut methods_formatted = String::new();
+                for method in methods {
+                    let method_formatted =
+                        method.format_with_options(strategy, language, options)?;
+                    if !method_formatted.is_empty() {
+                        methods_formatted.push_str("    ");
+                        methods_formatted.push_str(&method_formatted.replace("\n", "\n    "));
+                        methods_formatted.push('\n');
+                    }
+                }
+                if let Some(line) = accessors_line(&accessors, &rules) {
+                    methods_formatted.push_str(&format!("    {}\n", line));
+                }
+
                 // Add head (struct definition line)
                 output.push_str(&self.head);
-                output.push_str(rules.function_body_start_marker);
-                output.push('\n');
 
-                // Add all fields
-                for field in &self.fields {
-                    output.push_str(&format!(
-                        "    {}{}\n",
-                        field.source.as_deref().unwrap_or(""),
-                        rules.field_sep
-                    ));
-                }
-                output.push_str(rules.function_body_end_marker);
+                // Fields are public unless the parser tracks their visibility, e.g. the
+                // unexported fields of Go structs
+                let fields: Vec<&FieldUnit> = self
+                    .fields
+                    .iter()
+                    .filter(|field| field.visibility == Visibility::Public)
+                    .collect();
+                let field_count = fields.len();
+                let noun = if field_count == 1 { "field" } else { "fields" };
+                if options
+                    .elide_large_structs
+                    .is_some_and(|threshold| field_count > threshold)
+                {
+                    // Too many fields, only hint at the size of the struct
+                    if !methods_formatted.is_empty() {
+                        output.push_str(rules.function_body_start_marker);
+                        output.push('\n');
+                        output.push_str(&format!(
+                            "    {} {} {}\n",
+                            rules.comment_marker, field_count, noun
+                        ));
+                        output.push_str(&methods_formatted);
+                        output.push_str(rules.function_body_end_marker);
+                    } else if rules.function_body_end_marker.is_empty() {
+                        output.push_str(&format!(
+                            "{} ...  # {} {}",
+                            rules.function_body_start_marker, field_count, noun
+                        ));
+                    } else {
+                        output.push_str(&format!(
+                            " {} /* {} {} */ {}",
+                            rules.function_body_start_marker,
+                            field_count,
+                            noun,
+                            rules.function_body_end_marker
+                        ));
+                    }
+                } else {
+                    output.push_str(rules.function_body_start_marker);
+                    output.push('\n');
 
-                // Add public methods
-                for method in &self.methods {
-                    if method.visibility == Visibility::Public
-                        && !rules.is_test_function(&method.attributes)
-                    {
-                        let method_formatted = method.format(strategy, language)?;
-                        if !method_formatted.is_empty() {
+                    // Add all fields
+                    for field in fields {
+                        let field_formatted =
+                            field.format_with_options(strategy, language, options)?;
+                        if !field_formatted.is_empty() {
                             output.push_str("    ");
-                            output.push_str(&method_formatted.replace("\n", "\n    "));
+                            output.push_str(&field_formatted.replace("\n", "\n    "));
                             output.push('\n');
                         }
                     }
+                    output.push_str(&methods_formatted);
+                    output.push_str(rules.function_body_end_marker);
                 }
             }
         }
@@ -512,24 +1171,97 @@ impl Formatter for StructUnit {
     }
 }
 
+// Implement Formatter for FieldUnit
+impl Formatter for FieldUnit {
+    fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        _strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
+        let mut output = String::new();
+        let rules = FormatterRules::for_language(language);
+
+        // Add documentation
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
+        // Add attributes
+        for attr in &self.attributes {
+            output.push_str(&format!("{}\n", attr));
+        }
+
+        // Add the field itself, terminated by the language's field separator unless the
+        // source already ends with one (e.g. `int x;` in C++). Only the end is inspected,
+        // commas within types such as `HashMap<K, V>` are kept as written. The separator
+        // goes before a trailing comment, e.g. `pub x: i32, // note`
+        let (source, comment) =
+            split_trailing_comment(self.source.as_deref().unwrap_or(""), &rules);
+        output.push_str(source);
+        if !source.is_empty() && !source.ends_with(rules.field_sep) && !source.ends_with(';') {
+            output.push_str(rules.field_sep);
+        }
+        if let Some(comment) = comment {
+            output.push(' ');
+            output.push_str(comment);
+        }
+
+        Ok(output)
+    }
+}
+
+// Format the associated types and constants of a trait or impl, indented like its methods
+fn push_associated_items<'a>(
+    output: &mut String,
+    items: impl IntoIterator<Item = &'a FieldUnit>,
+    strategy: &BankStrategy,
+    language: LanguageType,
+    options: &FormatOptions,
+) -> Result<()> {
+    for item in items {
+        let formatted = item.format_with_options(strategy, language, options)?;
+        output.push_str("    ");
+        output.push_str(&formatted.replace("\n", "\n    "));
+        output.push('\n');
+    }
+    Ok(())
+}
+
 // Implement Formatter for TraitUnit
 impl Formatter for TraitUnit {
     fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
         let mut output = String::new();
         let rules = FormatterRules::for_language(language);
+        let tests = options.includes_tests(strategy);
 
         // Skip private traits for Summary
         if *strategy == BankStrategy::Summary && self.visibility != Visibility::Public {
             return Ok(String::new());
         }
 
-        // Add documentation
-        if let Some(doc) = &self.doc {
-            for line in doc.lines() {
-                output.push_str(&format!("{} {}\n", rules.doc_marker, line));
-            }
+        if let Some(line) = deprecation_line(self.deprecation.as_ref(), &rules) {
+            output.push_str(&line);
+        }
+
+        if *strategy == BankStrategy::Summary && self.is_unsafe {
+            output.push_str(&unsafe_line(&rules));
         }
 
+        // Add documentation
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
         // Add attributes
         for attr in &self.attributes {
             output.push_str(&format!("{}\n", attr));
@@ -542,15 +1274,22 @@ impl Formatter for TraitUnit {
                 }
             }
             BankStrategy::NoTests | BankStrategy::Summary => {
-                let head = format!("{} trait {}", self.visibility.as_str(language), self.name);
-                output.push_str(&head);
+                output.push_str(&self.head);
 
                 // Include body only for NoTests
                 if *strategy == BankStrategy::NoTests {
                     output.push_str(" {\n");
+                    push_associated_items(
+                        &mut output,
+                        self.associated_items.iter(),
+                        strategy,
+                        language,
+                        options,
+                    )?;
                     for method in &self.methods {
-                        if !rules.is_test_function(&method.attributes) {
-                            let method_formatted = method.format(strategy, language)?;
+                        if tests || !rules.is_test_function(&method.attributes) {
+                            let method_formatted =
+                                method.format_with_options(strategy, language, options)?;
                             if !method_formatted.is_empty() {
                                 output.push_str("    ");
                                 output.push_str(&method_formatted.replace("\n", "\n    "));
@@ -569,12 +1308,181 @@ impl Formatter for TraitUnit {
     }
 }
 
+// Implement Formatter for MacroUnit
+impl Formatter for MacroUnit {
+    fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
+        let mut output = String::new();
+        let rules = FormatterRules::for_language(language);
+
+        // Skip private macros for Summary. `#[macro_export] macro_rules!` macros are
+        // marked public by the parser, macros 2.0 carry their own visibility.
+        if *strategy == BankStrategy::Summary && self.visibility != Visibility::Public {
+            return Ok(String::new());
+        }
+
+        if let Some(line) = deprecation_line(self.deprecation.as_ref(), &rules) {
+            output.push_str(&line);
+        }
+
+        // Add documentation
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
+        // Add attributes
+        for attr in &self.attributes {
+            output.push_str(&format!("{}\n", attr));
+        }
+
+        match strategy {
+            BankStrategy::Default | BankStrategy::NoTests => {
+                if let Some(source) = &self.source {
+                    output.push_str(source);
+                }
+            }
+            BankStrategy::Summary => {
+                output.push_str(&self.head);
+                output.push_str(rules.summary_ellipsis);
+            }
+        }
+        Ok(output)
+    }
+}
+
+// Implement Formatter for ConstUnit
+impl Formatter for ConstUnit {
+    fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
+        let mut output = String::new();
+        let rules = FormatterRules::for_language(language);
+
+        // Skip private constants for Summary
+        if *strategy == BankStrategy::Summary && self.visibility != Visibility::Public {
+            return Ok(String::new());
+        }
+
+        if let Some(line) = deprecation_line(self.deprecation.as_ref(), &rules) {
+            output.push_str(&line);
+        }
+
+        // Add documentation
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
+        // Add attributes
+        for attr in &self.attributes {
+            output.push_str(&format!("{}\n", attr));
+        }
+
+        match strategy {
+            BankStrategy::Default | BankStrategy::NoTests => {
+                if let Some(source) = &self.source {
+                    output.push_str(source);
+                }
+            }
+            // The declaration without its initializer, e.g. `pub const MAX: usize;`
+            BankStrategy::Summary => {
+                let keyword = match (self.is_static, self.is_mut) {
+                    (false, _) => "const",
+                    (true, false) => "static",
+                    (true, true) => "static mut",
+                };
+                output.push_str(&format!(
+                    "{} {} {}",
+                    self.visibility.as_str(language),
+                    keyword,
+                    self.name
+                ));
+                if let Some(ty) = &self.type_annotation {
+                    output.push_str(&format!(": {}", ty));
+                }
+                output.push(';');
+            }
+        }
+
+        // Thread-local statics are only declared inside the macro
+        if self.is_thread_local {
+            let body = output
+                .lines()
+                .map(|line| format!("    {}", line).trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            output = format!("thread_local! {{\n{}\n}}", body);
+        }
+        Ok(output)
+    }
+}
+
+// Implement Formatter for TypeAliasUnit
+impl Formatter for TypeAliasUnit {
+    fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
+        let mut output = String::new();
+        let rules = FormatterRules::for_language(language);
+
+        // Skip private type aliases for Summary
+        if *strategy == BankStrategy::Summary && self.visibility != Visibility::Public {
+            return Ok(String::new());
+        }
+
+        if let Some(line) = deprecation_line(self.deprecation.as_ref(), &rules) {
+            output.push_str(&line);
+        }
+
+        // Add documentation
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
+
+        // Add attributes
+        for attr in &self.attributes {
+            output.push_str(&format!("{}\n", attr));
+        }
+
+        // The aliased type is part of the interface, Summary keeps the whole item too
+        if let Some(source) = &self.source {
+            output.push_str(source);
+        }
+        Ok(output)
+    }
+}
+
 // Implement Formatter for ImplUnit
 impl Formatter for ImplUnit {
     fn format(&self, strategy: &BankStrategy, language: LanguageType) -> Result<String> {
+        self.format_with_options(strategy, language, &FormatOptions::default())
+    }
+
+    fn format_with_options(
+        &self,
+        strategy: &BankStrategy,
+        language: LanguageType,
+        options: &FormatOptions,
+    ) -> Result<String> {
         let mut output = String::new();
         let rules = FormatterRules::for_language(language);
         let is_trait_impl = self.head.contains(" for ");
+        let tests = options.includes_tests(strategy);
 
         // Filter methods based on strategy
         let methods_to_include: Vec<&FunctionUnit> = match strategy {
@@ -582,14 +1490,14 @@ impl Formatter for ImplUnit {
             BankStrategy::NoTests => self
                 .methods
                 .iter()
-                .filter(|m| !rules.is_test_function(&m.attributes))
+                .filter(|m| tests || !rules.is_test_function(&m.attributes))
                 .collect(),
             BankStrategy::Summary => {
                 if is_trait_impl {
                     // Include all non-test methods for trait impls in Summary
                     self.methods
                         .iter()
-                        .filter(|m| !rules.is_test_function(&m.attributes))
+                        .filter(|m| tests || !rules.is_test_function(&m.attributes))
                         .collect()
                 } else {
                     // Include only public, non-test methods for regular impls in Summary
@@ -597,25 +1505,47 @@ impl Formatter for ImplUnit {
                         .iter()
                         .filter(|m| {
                             m.visibility == Visibility::Public
-                                && !rules.is_test_function(&m.attributes)
+                                && (tests || !rules.is_test_function(&m.attributes))
                         })
                         .collect()
                 }
             }
         };
 
+        // The accessors of trait impls are part of the trait, they are not collapsed
+        let (methods_to_include, accessors) = if is_trait_impl {
+            (methods_to_include, Vec::new())
+        } else {
+            split_accessors(methods_to_include, strategy, &rules, options)
+        };
+
+        // Inherent impls only show their public associated items in Summary
+        let associated_items: Vec<&FieldUnit> = self
+            .associated_items
+            .iter()
+            .filter(|item| {
+                *strategy != BankStrategy::Summary
+                    || is_trait_impl
+                    || item
+                        .source
+                        .as_deref()
+                        .is_some_and(|source| source.starts_with("pub"))
+            })
+            .collect();
+
         // If no methods to include and strategy is Summary (and not trait impl), return empty
         // Trait impls should show head even if empty
-        if methods_to_include.is_empty() && *strategy == BankStrategy::Summary && !is_trait_impl {
+        if methods_to_include.is_empty()
+            && accessors.is_empty()
+            && associated_items.is_empty()
+            && *strategy == BankStrategy::Summary
+            && !is_trait_impl
+        {
             return Ok(String::new());
         }
 
         // Add documentation
-        if let Some(doc) = &self.doc {
-            for line in doc.lines() {
-                output.push_str(&format!("{} {}\n", rules.doc_marker, line));
-            }
-        }
+        output.push_str(&format_doc(self.doc.as_deref(), &rules, options));
 
         // Add attributes
         for attr in &self.attributes {
@@ -632,9 +1562,11 @@ impl Formatter for ImplUnit {
                 output.push_str(&self.head);
                 output.push_str(" {\n");
 
+                push_associated_items(&mut output, associated_items, strategy, language, options)?;
                 for method in methods_to_include {
                     // Format method using the current strategy (Summary will summarize bodies)
-                    let method_formatted = method.format(strategy, language)?;
+                    let method_formatted =
+                        method.format_with_options(strategy, language, options)?;
 
                     if !method_formatted.is_empty() {
                         output.push_str("    ");
@@ -642,6 +1574,9 @@ impl Formatter for ImplUnit {
                         output.push('\n');
                     }
                 }
+                if let Some(line) = accessors_line(&accessors, &rules) {
+                    output.push_str(&format!("    {}\n", line));
+                }
                 output.push_str(rules.function_body_end_marker);
             }
         }
@@ -665,6 +1600,14 @@ mod tests {
             body: Some("{ println!(\"test\"); }".to_string()),
             source: Some("fn test_function() { println!(\"test\"); }".to_string()),
             attributes: vec!["#[test]".to_string()],
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+            return_type: None,
+            params: Vec::new(),
         };
         let expected_source = function.source.clone().unwrap();
 
@@ -695,6 +1638,14 @@ mod tests {
             body: Some("{ true }".to_string()),
             source: Some("pub fn regular_function() -> bool { true }".to_string()),
             attributes: vec![],
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+            return_type: None,
+            params: Vec::new(),
         };
         let regular_source = regular_function.source.clone().unwrap();
         let regular_sig = regular_function.signature.clone().unwrap();
@@ -741,7 +1692,11 @@ mod tests {
             traits: vec![],
             impls: vec![],
             submodules: vec![],
+            macros: vec![],
+            consts: vec![],
+            type_aliases: vec![],
             declares: vec![],
+            deprecation: None,
         };
         let expected_test_source = test_module.source.clone().unwrap();
 
@@ -775,7 +1730,11 @@ mod tests {
             traits: vec![],
             impls: vec![],
             submodules: vec![],
+            macros: vec![],
+            consts: vec![],
+            type_aliases: vec![],
             declares: vec![],
+            deprecation: None,
         };
 
         let result = regular_module
@@ -801,6 +1760,9 @@ mod tests {
             methods: vec![],
             fields: Vec::new(),
             source: Some("/// Test struct documentation\npub struct TestStruct {}".to_string()),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         };
 
         let result = struct_unit
@@ -816,6 +1778,132 @@ mod tests {
         assert!(result.contains("pub struct TestStruct"));
     }
 
+    #[test]
+    fn test_struct_unit_elide_large_structs() {
+        let struct_with_fields = |name: &str, count: usize| StructUnit {
+            name: name.to_string(),
+            head: format!("pub struct {}", name),
+            visibility: Visibility::Public,
+            fields: (0..count)
+                .map(|i| FieldUnit {
+                    name: format!("field_{}", i),
+                    source: Some(format!("pub field_{}: u32", i)),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let options = FormatOptions {
+            elide_large_structs: Some(10),
+            ..Default::default()
+        };
+
+        let large = struct_with_fields("Config", 20)
+            .format_with_options(&BankStrategy::Summary, LanguageType::Rust, &options)
+            .unwrap();
+        assert_eq!(large, "pub struct Config { /* 20 fields */ }");
+
+        let small = struct_with_fields("Point", 5)
+            .format_with_options(&BankStrategy::Summary, LanguageType::Rust, &options)
+            .unwrap();
+        assert!(small.contains("pub field_0: u32,"));
+        assert!(small.contains("pub field_4: u32,"));
+        assert!(!small.contains("fields */"));
+    }
+
+    #[test]
+    fn test_doc_mode_indicator() {
+        let file_unit = FileUnit {
+            path: "lib.rs".into(),
+            functions: vec![
+                FunctionUnit {
+                    name: "documented".to_string(),
+                    visibility: Visibility::Public,
+                    doc: Some("Does things.\nIn two lines.".to_string()),
+                    signature: Some("pub fn documented()".to_string()),
+                    ..Default::default()
+                },
+                FunctionUnit {
+                    name: "undocumented".to_string(),
+                    visibility: Visibility::Public,
+                    signature: Some("pub fn undocumented()".to_string()),
+                    ..Default::default()
+                },
+            ],
+            structs: vec![StructUnit {
+                name: "Point".to_string(),
+                head: "pub struct Point".to_string(),
+                visibility: Visibility::Public,
+                doc: Some("A point".to_string()),
+                fields: vec![FieldUnit {
+                    name: "x".to_string(),
+                    doc: Some("The x coordinate".to_string()),
+                    source: Some("pub x: f64".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let options = FormatOptions {
+            doc_mode: DocMode::Indicator,
+            ..Default::default()
+        };
+
+        let formatted = file_unit
+            .format_with_options(&BankStrategy::Summary, LanguageType::Rust, &options)
+            .unwrap();
+        assert!(formatted.contains("// [documented]\npub fn documented() { ... }"));
+        assert!(formatted.contains("pub fn undocumented() { ... }"));
+        assert!(!formatted.contains("[documented]\npub fn undocumented"));
+        assert!(formatted.contains("// [documented]\npub struct Point"));
+        assert!(formatted.contains("    // [documented]\n    pub x: f64,"));
+        assert_eq!(formatted.matches("[documented]").count(), 3);
+        assert!(!formatted.contains("Does things."));
+        assert!(!formatted.contains("The x coordinate"));
+
+        // Full docs are kept by default
+        let formatted = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("/// Does things.\n/// In two lines.\n"));
+        assert!(!formatted.contains("[documented]"));
+    }
+
+    #[test]
+    fn test_tag_const_fns() {
+        let function = FunctionUnit {
+            name: "new".to_string(),
+            visibility: Visibility::Public,
+            doc: Some("An empty buffer".to_string()),
+            signature: Some("pub const fn new() -> Self".to_string()),
+            is_const: true,
+            ..Default::default()
+        };
+        let options = FormatOptions {
+            tag_const_fns: true,
+            ..Default::default()
+        };
+
+        let formatted = function
+            .format_with_options(&BankStrategy::Summary, LanguageType::Rust, &options)
+            .unwrap();
+        assert_eq!(
+            formatted,
+            "/// An empty buffer\n// [const]\npub const fn new() -> Self { ... }"
+        );
+
+        // Only the Summary strategy is tagged, and only when asked to
+        let formatted = function
+            .format_with_options(&BankStrategy::NoTests, LanguageType::Rust, &options)
+            .unwrap();
+        assert!(!formatted.contains("[const]"));
+        let formatted = function
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(!formatted.contains("[const]"));
+    }
+
     #[test]
     fn test_trait_unit_format() {
         let trait_unit = TraitUnit {
@@ -824,7 +1912,11 @@ mod tests {
             doc: Some("Test trait documentation".to_string()),
             source: Some("/// Test trait documentation\npub trait TestTrait {}".to_string()),
             attributes: vec![],
+            associated_items: Vec::new(),
+            is_unsafe: false,
             methods: vec![],
+            deprecation: None,
+            head: "pub trait TestTrait".to_string(),
         };
 
         let result = trait_unit
@@ -846,6 +1938,7 @@ mod tests {
             doc: Some("Test impl documentation".to_string()),
             source: Some("/// Test impl documentation\nimpl TestStruct {".to_string()),
             attributes: vec![],
+            associated_items: Vec::new(),
             methods: vec![],
         };
 
@@ -874,6 +1967,11 @@ mod tests {
             structs: vec![],
             traits: vec![],
             impls: vec![],
+            macros: vec![],
+            consts: vec![],
+            type_aliases: vec![],
+            parse_errors: vec![],
+            omitted_items: 0,
         };
 
         let result = file_unit
@@ -886,4 +1984,197 @@ mod tests {
             .unwrap();
         assert!(result.contains("Test file documentation"));
     }
+
+    #[test]
+    fn test_collapse_imports_of_other_languages() {
+        let mut file_unit = FileUnit::new(std::path::PathBuf::from("Program.cs"));
+        for source in ["using System;", "using System.Text;", "using Acme.Billing;"] {
+            file_unit.declares.push(DeclareStatements {
+                source: source.to_string(),
+                kind: DeclareKind::Use,
+            });
+        }
+        let options = FormatOptions {
+            collapse_imports: true,
+            ..Default::default()
+        };
+
+        let formatted = file_unit
+            .format_with_options(&BankStrategy::NoTests, LanguageType::CSharp, &options)
+            .unwrap();
+        // Only Rust use trees are decomposed into the imported crates
+        assert_eq!(formatted, "// 3 imports\n");
+
+        file_unit.path = std::path::PathBuf::from("main.go");
+        file_unit.declares = ["import \"fmt\"", "import \"os\""]
+            .into_iter()
+            .map(|source| DeclareStatements {
+                source: source.to_string(),
+                kind: DeclareKind::Use,
+            })
+            .collect();
+        let formatted = file_unit
+            .format_with_options(&BankStrategy::Summary, LanguageType::Go, &options)
+            .unwrap();
+        assert_eq!(formatted, "// 2 imports\n");
+    }
+
+    #[test]
+    fn test_field_unit_format() {
+        // Documented field
+        let documented = FieldUnit {
+            name: "id".to_string(),
+            doc: Some("Unique id\nNever reused".to_string()),
+            attributes: vec![],
+            source: Some("pub id: u64".to_string()),
+            discriminant: None,
+            visibility: Visibility::Public,
+        };
+        assert_eq!(
+            documented
+                .format(&BankStrategy::Summary, LanguageType::Rust)
+                .unwrap(),
+            "/// Unique id\n/// Never reused\npub id: u64,"
+        );
+
+        // Attributed field
+        let attributed = FieldUnit {
+            name: "name".to_string(),
+            doc: None,
+            attributes: vec!["#[serde(default)]".to_string()],
+            source: Some("name: String".to_string()),
+            discriminant: None,
+            visibility: Visibility::Public,
+        };
+        assert_eq!(
+            attributed
+                .format(&BankStrategy::NoTests, LanguageType::Rust)
+                .unwrap(),
+            "#[serde(default)]\nname: String,"
+        );
+
+        // Plain fields across languages
+        let plain = |source: &str| FieldUnit {
+            name: "count".to_string(),
+            source: Some(source.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            plain("count = 0")
+                .format(&BankStrategy::Summary, LanguageType::Python)
+                .unwrap(),
+            "count = 0"
+        );
+        assert_eq!(
+            plain("int count;")
+                .format(&BankStrategy::Summary, LanguageType::Cpp)
+                .unwrap(),
+            "int count;"
+        );
+        assert_eq!(
+            plain("count: number")
+                .format(&BankStrategy::Summary, LanguageType::TypeScript)
+                .unwrap(),
+            "count: number,"
+        );
+        assert_eq!(
+            plain("Count int")
+                .format(&BankStrategy::Summary, LanguageType::Go)
+                .unwrap(),
+            "Count int,"
+        );
+
+        // Trailing comments stay after the separator
+        let format_rust = |source: &str| {
+            plain(source)
+                .format(&BankStrategy::Summary, LanguageType::Rust)
+                .unwrap()
+        };
+        assert_eq!(format_rust("pub x: i32 // note"), "pub x: i32, // note");
+        assert_eq!(format_rust("pub x: i32, // note"), "pub x: i32, // note");
+        assert_eq!(
+            format_rust("pub x: i32 /* note */"),
+            "pub x: i32, /* note */"
+        );
+        assert_eq!(
+            format_rust(r#"url: &'static str = "http://example.com""#),
+            r#"url: &'static str = "http://example.com","#
+        );
+        assert_eq!(
+            plain("count = 0  # items")
+                .format(&BankStrategy::Summary, LanguageType::Python)
+                .unwrap(),
+            "count = 0 # items"
+        );
+        assert_eq!(
+            plain("self.color = '#fff'")
+                .format(&BankStrategy::Summary, LanguageType::Python)
+                .unwrap(),
+            "self.color = '#fff'"
+        );
+    }
+
+    #[test]
+    fn test_struct_summary_includes_field_docs() {
+        let struct_unit = StructUnit {
+            name: "User".to_string(),
+            visibility: Visibility::Public,
+            head: "pub struct User".to_string(),
+            fields: vec![FieldUnit {
+                name: "id".to_string(),
+                doc: Some("Unique id".to_string()),
+                attributes: vec![],
+                source: Some("pub id: u64".to_string()),
+                discriminant: None,
+                visibility: Visibility::Public,
+            }],
+            ..Default::default()
+        };
+        let formatted = struct_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("    /// Unique id\n    pub id: u64,\n"));
+    }
+
+    #[test]
+    fn test_deprecated_warning_in_all_strategies() {
+        let function = FunctionUnit {
+            name: "start".to_string(),
+            visibility: Visibility::Public,
+            signature: Some("pub fn start()".to_string()),
+            body: Some("{}".to_string()),
+            source: Some("pub fn start() {}".to_string()),
+            deprecation: Some(Deprecation {
+                since: None,
+                note: Some("use `run` instead".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        for strategy in [
+            BankStrategy::Default,
+            BankStrategy::NoTests,
+            BankStrategy::Summary,
+        ] {
+            let formatted = function.format(&strategy, LanguageType::Rust).unwrap();
+            assert!(
+                formatted.starts_with("/// ⚠️ DEPRECATED: use `run` instead\n"),
+                "{:?}: {}",
+                strategy,
+                formatted
+            );
+        }
+
+        let class = StructUnit {
+            name: "OldClient".to_string(),
+            visibility: Visibility::Public,
+            head: "class OldClient".to_string(),
+            deprecation: Some(Deprecation::default()),
+            ..Default::default()
+        };
+        let formatted = class
+            .format(&BankStrategy::Summary, LanguageType::TypeScript)
+            .unwrap();
+        assert!(formatted.starts_with("// ⚠️ DEPRECATED\n"));
+    }
 }
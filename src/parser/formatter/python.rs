@@ -246,6 +246,14 @@ mod tests {
             signature: Some(format!("def {}():", name)),
             body: Some("    pass".to_string()),
             source: Some(format!("def {}():\n    pass", name)),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+            return_type: None,
+            params: Vec::new(),
         }
     }
 
@@ -277,6 +285,9 @@ mod tests {
             methods,
             source: Some(format!("class {}:\n    pass", name)),
             fields: Vec::new(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         }
     }
 
@@ -316,8 +327,12 @@ mod tests {
             traits: Vec::new(),
             impls: Vec::new(),
             submodules: Vec::new(),
+            macros: Vec::new(),
+            consts: Vec::new(),
+            type_aliases: Vec::new(),
             declares,
             source: Some(format!("# Module {}", name)),
+            deprecation: None,
         }
     }
 
@@ -389,6 +404,8 @@ mod tests {
             doc: Some("Field documentation".to_string()),
             attributes: vec![],
             source: Some("field = None".to_string()),
+            discriminant: None,
+            visibility: Visibility::Public,
         };
         public_class.fields.push(field);
 
@@ -1,4 +1,4 @@
-use crate::parser::LanguageType;
+use crate::parser::{FunctionUnit, LanguageType};
 
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
@@ -8,8 +8,14 @@ pub struct FormatterRules {
     pub function_body_start_marker: &'static str,
     pub function_body_end_marker: &'static str,
     pub doc_marker: &'static str,
+    /// Marker of a plain line comment, e.g. `//` or `#`
+    pub comment_marker: &'static str,
     pub test_markers: &'static [&'static str],
     pub test_module_markers: &'static [&'static str],
+    /// Names of test macros that parse as function definitions (e.g. gtest `TEST`)
+    pub test_macros: &'static [&'static str],
+    /// File name patterns (`*` wildcards) of files that only contain tests
+    pub test_file_patterns: &'static [&'static str],
 }
 
 const RUST_RULES: FormatterRules = FormatterRules {
@@ -18,8 +24,11 @@ const RUST_RULES: FormatterRules = FormatterRules {
     function_body_start_marker: "{",
     function_body_end_marker: "}",
     doc_marker: "///",
+    comment_marker: "//",
     test_markers: &["#[test]", "#[cfg(test)]"],
     test_module_markers: &["#[cfg(test)]", "tests"],
+    test_macros: &[],
+    test_file_patterns: &[],
 };
 
 const PYTHON_RULES: FormatterRules = FormatterRules {
@@ -28,8 +37,11 @@ const PYTHON_RULES: FormatterRules = FormatterRules {
     function_body_start_marker: ":",
     function_body_end_marker: "",
     doc_marker: "#",
+    comment_marker: "#",
     test_markers: &["@pytest", "test_"],
     test_module_markers: &["test_"],
+    test_macros: &[],
+    test_file_patterns: &["test_*.py", "*_test.py"],
 };
 
 const TS_RULES: FormatterRules = FormatterRules {
@@ -38,8 +50,18 @@ const TS_RULES: FormatterRules = FormatterRules {
     function_body_start_marker: "{",
     function_body_end_marker: "}",
     doc_marker: "//",
+    comment_marker: "//",
     test_markers: &["@test", "test_"],
     test_module_markers: &["test_"],
+    test_macros: &[],
+    test_file_patterns: &[
+        "*.test.ts",
+        "*.spec.ts",
+        "*.test.tsx",
+        "*.spec.tsx",
+        "*.test.js",
+        "*.spec.js",
+    ],
 };
 
 const C_RULES: FormatterRules = FormatterRules {
@@ -48,8 +70,11 @@ const C_RULES: FormatterRules = FormatterRules {
     function_body_start_marker: "{",
     function_body_end_marker: "}",
     doc_marker: "//",
+    comment_marker: "//",
     test_markers: &["@test", "test_"],
     test_module_markers: &["test_"],
+    test_macros: &["TEST", "TEST_F", "TEST_P", "TYPED_TEST", "TYPED_TEST_P"],
+    test_file_patterns: &[],
 };
 
 const GO_RULES: FormatterRules = FormatterRules {
@@ -58,8 +83,39 @@ const GO_RULES: FormatterRules = FormatterRules {
     function_body_start_marker: "{",
     function_body_end_marker: "}",
     doc_marker: "//",
+    comment_marker: "//",
     test_markers: &["test_"],
     test_module_markers: &["test_"],
+    test_macros: &[],
+    test_file_patterns: &[],
+};
+
+const CSHARP_RULES: FormatterRules = FormatterRules {
+    summary_ellipsis: " { ... }",
+    // Fields and properties carry their own terminator
+    field_sep: "",
+    function_body_start_marker: "{",
+    function_body_end_marker: "}",
+    doc_marker: "///",
+    comment_marker: "//",
+    test_markers: &["[Test", "[Fact", "[Theory", "[TestMethod"],
+    test_module_markers: &[],
+    test_macros: &[],
+    test_file_patterns: &["*Tests.cs", "*Test.cs"],
+};
+
+const PHP_RULES: FormatterRules = FormatterRules {
+    summary_ellipsis: " { ... }",
+    // Properties, constants and enum cases carry their own terminator
+    field_sep: "",
+    function_body_start_marker: "{",
+    function_body_end_marker: "}",
+    doc_marker: "//",
+    comment_marker: "//",
+    test_markers: &["#[Test", "#[DataProvider"],
+    test_module_markers: &[],
+    test_macros: &[],
+    test_file_patterns: &["*Test.php"],
 };
 
 const UNKNOWN_RULES: FormatterRules = FormatterRules {
@@ -68,8 +124,11 @@ const UNKNOWN_RULES: FormatterRules = FormatterRules {
     function_body_start_marker: "",
     function_body_end_marker: "",
     doc_marker: "//",
+    comment_marker: "//",
     test_markers: &[],
     test_module_markers: &[],
+    test_macros: &[],
+    test_file_patterns: &[],
 };
 
 impl FormatterRules {
@@ -81,6 +140,8 @@ impl FormatterRules {
             LanguageType::TypeScript => TS_RULES,
             LanguageType::Cpp => C_RULES,
             LanguageType::Go => GO_RULES,
+            LanguageType::CSharp => CSHARP_RULES,
+            LanguageType::Php => PHP_RULES,
             LanguageType::Unknown => UNKNOWN_RULES,
         }
     }
@@ -91,21 +152,64 @@ impl FormatterRules {
             .any(|attr| self.test_markers.iter().any(|marker| attr.contains(marker)))
     }
 
+    pub fn is_test_macro(&self, name: &str) -> bool {
+        self.test_macros.contains(&name)
+    }
+
+    pub fn is_test_file(&self, file_name: &str) -> bool {
+        self.test_file_patterns
+            .iter()
+            .any(|pattern| matches_file_pattern(pattern, file_name))
+    }
+
     pub fn is_test_module(&self, name: &str, attributes: &[String]) -> bool {
         self.test_module_markers.iter().any(|marker| {
             name.starts_with(marker) || attributes.iter().any(|attr| attr.contains(marker))
         })
     }
 
+    /// Whether `method` is a trivial accessor, whose body is a single read of a field of
+    /// the receiver, e.g. `{ &self.name }`, or a single assignment of one, e.g.
+    /// `{ self.name = name; }`
+    pub fn is_accessor(&self, method: &FunctionUnit) -> bool {
+        let Some(body) = method.body.as_deref() else {
+            return false;
+        };
+        let body = body.trim();
+        let body = body
+            .strip_prefix(self.function_body_start_marker)
+            .unwrap_or(body);
+        let body = body
+            .strip_suffix(self.function_body_end_marker)
+            .unwrap_or(body)
+            .trim();
+        if body.is_empty() || body.contains('\n') {
+            return false;
+        }
+
+        let statement = body.strip_prefix("return ").unwrap_or(body);
+        let statement = statement.strip_suffix(';').unwrap_or(statement).trim();
+        match statement.split_once('=') {
+            Some((field, value)) => {
+                let value = value.trim();
+                is_receiver_field(field.trim())
+                    && is_identifier(value.strip_suffix(".into()").unwrap_or(value))
+            }
+            None => is_receiver_field(statement),
+        }
+    }
+
     pub fn format_signature(&self, source: &str, signature: Option<&str>) -> String {
         let sig_to_use = signature.unwrap_or(source).trim();
 
-        // Find the start of the body marker if it exists
-        let body_start_marker_pos = if !self.function_body_start_marker.is_empty() {
-            sig_to_use.find(self.function_body_start_marker)
-        } else {
-            None
-        };
+        // Find the start of the body marker if it exists. Parsed signatures are already
+        // split from the body and may contain the marker, e.g. `-> Buffer<{ N }>`
+        let body_start_marker_pos =
+            if signature.is_none() && !self.function_body_start_marker.is_empty() {
+                sig_to_use.find(self.function_body_start_marker)
+            } else {
+                None
+            };
 
         let clean_sig = if let Some(idx) = body_start_marker_pos {
             sig_to_use[0..idx].trim_end()
@@ -118,6 +222,56 @@ impl FormatterRules {
     }
 }
 
+// Whether `expr` reads a field of the receiver, e.g. `self.name`, `&self.name`,
+// `this->name` or `self.name.clone()`
+fn is_receiver_field(expr: &str) -> bool {
+    let expr = expr
+        .strip_prefix("&mut ")
+        .or_else(|| expr.strip_prefix('&'))
+        .unwrap_or(expr);
+    let Some(field) = ["self.", "this.", "this->", "$this->"]
+        .iter()
+        .find_map(|receiver| expr.strip_prefix(receiver))
+    else {
+        return false;
+    };
+    let field = [".clone()", ".as_ref()", ".as_deref()", ".as_str()"]
+        .iter()
+        .find_map(|conversion| field.strip_suffix(conversion))
+        .unwrap_or(field);
+    is_identifier(field)
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Match a file name against a pattern where `*` matches any sequence of characters
+pub fn matches_file_pattern(pattern: &str, file_name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == file_name;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if file_name.len() < first.len() + last.len()
+        || !file_name.starts_with(first)
+        || !file_name.ends_with(last)
+    {
+        return false;
+    }
+
+    // Middle parts must appear in order between the prefix and the suffix
+    let mut rest = &file_name[first.len()..file_name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +336,68 @@ mod tests {
         assert!(!rules.is_test_module("regular_module", &[]));
     }
 
+    #[test]
+    fn test_is_test_file() {
+        let rules = FormatterRules::for_language(LanguageType::TypeScript);
+        assert!(rules.is_test_file("button.test.ts"));
+        assert!(rules.is_test_file("button.spec.tsx"));
+        assert!(!rules.is_test_file("button.ts"));
+
+        let rules = FormatterRules::for_language(LanguageType::Python);
+        assert!(rules.is_test_file("test_models.py"));
+        assert!(rules.is_test_file("models_test.py"));
+        assert!(!rules.is_test_file("models.py"));
+        assert!(!rules.is_test_file("test_models.txt"));
+
+        let rules = FormatterRules::for_language(LanguageType::Rust);
+        assert!(!rules.is_test_file("tests.rs"));
+    }
+
+    #[test]
+    fn test_is_test_macro() {
+        let rules = FormatterRules::for_language(LanguageType::Cpp);
+        assert!(rules.is_test_macro("TEST"));
+        assert!(rules.is_test_macro("TEST_F"));
+        assert!(!rules.is_test_macro("add"));
+
+        let rules = FormatterRules::for_language(LanguageType::Rust);
+        assert!(!rules.is_test_macro("TEST"));
+    }
+
+    #[test]
+    fn test_is_accessor() {
+        let rules = FormatterRules::for_language(LanguageType::Rust);
+        let method = |body: &str| FunctionUnit {
+            name: "name".to_string(),
+            body: Some(body.to_string()),
+            ..Default::default()
+        };
+
+        assert!(rules.is_accessor(&method("{ &self.name }")));
+        assert!(rules.is_accessor(&method("{\n        self.name.clone()\n    }")));
+        assert!(rules.is_accessor(&method("{ self.name = name; }")));
+        assert!(rules.is_accessor(&method("{ self.name = name.into() }")));
+        assert!(!rules.is_accessor(&method("{ self.name.len() }")));
+        assert!(!rules.is_accessor(&method("{ self.count == count }")));
+        assert!(!rules.is_accessor(&method("{ self.count += 1; }")));
+        assert!(!rules.is_accessor(&method("{\n        self.name = name;\n        self\n    }")));
+        assert!(!rules.is_accessor(&FunctionUnit::default()));
+
+        let rules = FormatterRules::for_language(LanguageType::Cpp);
+        assert!(rules.is_accessor(&method("{ return this->name; }")));
+    }
+
+    #[test]
+    fn test_matches_file_pattern() {
+        assert!(matches_file_pattern("*.test.ts", "a.test.ts"));
+        assert!(!matches_file_pattern("*.test.ts", ".test.tsx"));
+        assert!(matches_file_pattern("test_*.py", "test_.py"));
+        assert!(matches_file_pattern("*_it_*.rs", "db_it_users.rs"));
+        assert!(!matches_file_pattern("*_it_*.rs", "db_users.rs"));
+        assert!(matches_file_pattern("conftest.py", "conftest.py"));
+        assert!(!matches_file_pattern("conftest.py", "myconftest.py"));
+    }
+
     #[test]
     fn test_format_signature() {
         let rules = FormatterRules::for_language(LanguageType::Rust);
@@ -21,6 +21,14 @@ mod tests {
             signature: Some(format!("fn {}()", name)),
             body: Some("{ /* function body */ }".to_string()),
             source: Some(format!("fn {}() {{ /* function body */ }}", name)),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+            return_type: None,
+            params: Vec::new(),
         }
     }
 
@@ -53,6 +61,9 @@ mod tests {
             fields: Vec::new(),
             methods,
             source: Some(format!("struct {} {{ field: i32 }}", name)),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         }
     }
 
@@ -92,8 +103,12 @@ mod tests {
             traits: Vec::new(),
             impls: Vec::new(),
             submodules: Vec::new(),
+            macros: Vec::new(),
+            consts: Vec::new(),
+            type_aliases: Vec::new(),
             declares,
             source: Some(format!("mod {} {{ /* module contents */ }}", name)),
+            deprecation: None,
         }
     }
 
@@ -121,6 +136,7 @@ mod tests {
             attributes: Vec::new(),
             doc: Some("Documentation for implementation".to_string()),
             head,
+            associated_items: Vec::new(),
             methods,
             source: Some(source),
         }
@@ -132,6 +148,7 @@ mod tests {
             attributes: Vec::new(),
             doc: Some("Documentation for implementation with private methods".to_string()),
             head: "impl StructWithPrivateMethods".to_string(),
+            associated_items: Vec::new(),
             methods: vec![
                 create_test_function("private_method1", false, false),
                 create_test_function("private_method2", false, false),
@@ -164,6 +181,9 @@ mod tests {
             fields: vec![], // Variants aren't parsed as fields currently
             methods: vec![],
             source: Some(source),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         }
     }
 
@@ -235,6 +255,8 @@ mod tests {
             doc: Some("Field documentation".to_string()),
             attributes: vec![],
             source: Some("pub field: i32".to_string()),
+            discriminant: None,
+            visibility: Visibility::Public,
         };
         public_struct.fields.push(field);
 
@@ -561,4 +583,102 @@ mod tests {
         // Private enums should be omitted entirely in summary
         assert!(formatted.is_empty());
     }
+
+    #[test]
+    fn test_macro_formatter_summary_visibility() {
+        let exported_rules = MacroUnit {
+            name: "exported".to_string(),
+            attributes: vec!["#[macro_export]".to_string()],
+            visibility: Visibility::Public,
+            doc: Some("Exported macro".to_string()),
+            head: "macro_rules! exported".to_string(),
+            is_macro_rules: true,
+            source: Some("macro_rules! exported {\n    () => {};\n}".to_string()),
+            deprecation: None,
+        };
+        let formatted = exported_rules
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("/// Exported macro"));
+        assert!(formatted.contains("#[macro_export]"));
+        assert!(formatted.contains("macro_rules! exported { ... }"));
+        assert!(!formatted.contains("() => {};"));
+
+        let public_macro = MacroUnit {
+            name: "double".to_string(),
+            visibility: Visibility::Public,
+            head: "pub macro double($x:expr)".to_string(),
+            source: Some("pub macro double($x:expr) {\n    $x * 2\n}".to_string()),
+            ..Default::default()
+        };
+        let formatted = public_macro
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert_eq!(formatted, "pub macro double($x:expr) { ... }");
+
+        // Non-public macros 2.0 are omitted from the summary but kept for NoTests
+        let crate_macro = MacroUnit {
+            visibility: Visibility::Crate,
+            head: "pub(crate) macro double($x:expr)".to_string(),
+            source: Some("pub(crate) macro double($x:expr) {\n    $x * 2\n}".to_string()),
+            ..public_macro
+        };
+        assert!(
+            crate_macro
+                .format(&BankStrategy::Summary, LanguageType::Rust)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            crate_macro
+                .format(&BankStrategy::NoTests, LanguageType::Rust)
+                .unwrap()
+                .contains("$x * 2")
+        );
+    }
+
+    #[test]
+    fn test_file_unit_collapse_imports() {
+        let mut file_unit = FileUnit::new(std::path::PathBuf::from("imports.rs"));
+        for source in [
+            "use std::collections::HashMap;",
+            "use std::fs;",
+            "use serde::{Deserialize, Serialize};",
+            "pub use tokio::sync::Mutex;",
+            "use ::regex::Regex;",
+        ] {
+            file_unit.declares.push(DeclareStatements {
+                source: source.to_string(),
+                kind: DeclareKind::Use,
+            });
+        }
+        file_unit.declares.push(DeclareStatements {
+            source: "mod helpers;".to_string(),
+            kind: DeclareKind::Mod,
+        });
+        file_unit.declares.push(DeclareStatements {
+            source: "use crate::helpers::run;".to_string(),
+            kind: DeclareKind::Use,
+        });
+
+        let options = FormatOptions {
+            collapse_imports: true,
+            ..Default::default()
+        };
+        let formatted = file_unit
+            .format_with_options(&BankStrategy::NoTests, LanguageType::Rust, &options)
+            .unwrap();
+        assert!(formatted.contains("// 5 imports (std, serde, tokio, regex)"));
+        assert!(!formatted.contains("use std::fs;"));
+        // Non-use declarations break the run, single imports are kept verbatim
+        assert!(formatted.contains("mod helpers;"));
+        assert!(formatted.contains("use crate::helpers::run;"));
+
+        // Without the option the declarations are rendered as is
+        let formatted = file_unit
+            .format(&BankStrategy::NoTests, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("use std::fs;"));
+        assert!(!formatted.contains("imports ("));
+    }
 }
@@ -1,3 +1,4 @@
+use super::{collect_parse_errors, parse_tree, timeout_micros};
 use crate::{
     CppParser, DeclareKind, DeclareStatements, Error, FieldUnit, FileUnit, FunctionUnit,
     LanguageParser, Result, StructUnit, Visibility,
@@ -5,6 +6,7 @@ use crate::{
 use std::fs;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::time::Duration;
 use tree_sitter::{Node, Parser};
 
 impl CppParser {
@@ -54,14 +56,19 @@ impl CppParser {
         let attributes = Vec::new();
 
         // Extract function name
-        if let Some(declarator) = node.child_by_field_name("declarator") {
-            if let Some(name_node) = find_identifier(declarator) {
-                name = get_node_text(name_node, source_code).unwrap_or_default();
-            }
+        let declarator = node.child_by_field_name("declarator");
+        if let Some(name_node) = declarator.and_then(function_name_node) {
+            name = get_node_text(name_node, source_code).unwrap_or_default();
         }
 
-        // Extract function signature and body
-        if let Some(sig_text) = get_node_text(node, source_code) {
+        // Extract function signature and body, split at the body node rather than the
+        // first brace, which may belong to a trailing return type such as `decltype(T{})`
+        if let Some(body_node) = node.child_by_field_name("body") {
+            signature = source_code[node.start_byte()..body_node.start_byte()]
+                .trim()
+                .to_string();
+            body = get_node_text(body_node, source_code).map(|text| text.trim().to_string());
+        } else if let Some(sig_text) = get_node_text(node, source_code) {
             if let Some(open_brace) = sig_text.find('{') {
                 signature = sig_text[..open_brace].trim().to_string();
                 body = Some(sig_text[open_brace..].trim().to_string());
@@ -90,6 +97,16 @@ impl CppParser {
         // Get full source
         let source = get_node_text(node, source_code);
 
+        // Only trailing return types are recorded, e.g. `int` for `auto add() -> int`
+        let return_type = declarator
+            .and_then(|declarator| {
+                declarator
+                    .children(&mut declarator.walk())
+                    .find(|child| child.kind() == "trailing_return_type")
+            })
+            .and_then(|trailing| get_node_text(trailing, source_code))
+            .map(|text| text.trim_start_matches("->").trim().to_string());
+
         Ok(FunctionUnit {
             name,
             visibility,
@@ -98,6 +115,14 @@ impl CppParser {
             body,
             source,
             attributes,
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+            return_type,
+            params: Vec::new(),
         })
     }
 
@@ -150,6 +175,9 @@ impl CppParser {
             fields,
             source,
             attributes,
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         })
     }
 
@@ -301,6 +329,14 @@ impl CppParser {
                                 body: function.body.clone(),
                                 source: Some(template_text.clone()),
                                 attributes: Vec::new(),
+                                deprecation: None,
+                                is_must_use: false,
+                                must_use_note: None,
+                                is_const: false,
+                                is_unsafe: false,
+                                is_associated_fn: false,
+                                return_type: None,
+                                params: Vec::new(),
                             };
                             return Ok((None, Some(template_function)));
                         } else {
@@ -327,6 +363,14 @@ impl CppParser {
                                         body: function.body.clone(),
                                         source: Some(template_text.clone()),
                                         attributes: Vec::new(),
+                                        deprecation: None,
+                                        is_must_use: false,
+                                        must_use_note: None,
+                                        is_const: false,
+                                        is_unsafe: false,
+                                        is_associated_fn: false,
+                                        return_type: None,
+                                        params: Vec::new(),
                                     };
                                     return Ok((None, Some(template_function)));
                                 } else {
@@ -359,6 +403,14 @@ impl CppParser {
                                     body: function.body.clone(),
                                     source: Some(template_text.clone()),
                                     attributes: Vec::new(),
+                                    deprecation: None,
+                                    is_must_use: false,
+                                    must_use_note: None,
+                                    is_const: false,
+                                    is_unsafe: false,
+                                    is_associated_fn: false,
+                                    return_type: None,
+                                    params: Vec::new(),
                                 };
                                 return Ok((None, Some(template_function)));
                             } else {
@@ -408,6 +460,9 @@ impl CppParser {
                 source: Some(template_text),
                 fields: Vec::new(),
                 attributes,
+                deprecation: None,
+                is_must_use: false,
+                must_use_note: None,
             })
         } else {
             None
@@ -505,6 +560,29 @@ impl CppParser {
         // Get full source
         let source = get_node_text(node, source_code);
 
+        // Extract enumerators with their explicit values, e.g. `RED = 1`
+        let mut fields = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for enumerator in body.children(&mut body.walk()) {
+                if enumerator.kind() != "enumerator" {
+                    continue;
+                }
+                let text = |field: &str| {
+                    enumerator
+                        .child_by_field_name(field)
+                        .and_then(|child| get_node_text(child, source_code))
+                };
+                fields.push(FieldUnit {
+                    name: text("name").unwrap_or_default(),
+                    doc: self.extract_documentation(enumerator, source_code),
+                    attributes: Vec::new(),
+                    source: get_node_text(enumerator, source_code),
+                    discriminant: text("value"),
+                    visibility: Visibility::Public,
+                });
+            }
+        }
+
         Ok(StructUnit {
             name,
             visibility: Visibility::Public,
@@ -512,8 +590,11 @@ impl CppParser {
             head,
             methods: Vec::new(),
             source,
-            fields: Vec::new(),
+            fields,
             attributes: Vec::new(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         })
     }
 
@@ -531,20 +612,16 @@ impl CppParser {
             // Try to extract the name (last identifier before semicolon)
             if let Some(semicolon_pos) = content.rfind(';') {
                 let before_semicolon = &content[..semicolon_pos];
-                if let Some(last_word_pos) = before_semicolon.rfind(char::is_alphanumeric) {
-                    // Find the start of the last word
-                    let mut start_pos = last_word_pos;
-                    while start_pos > 0
-                        && (content
-                            .chars()
-                            .nth(start_pos - 1)
-                            .unwrap()
-                            .is_alphanumeric()
-                            || content.chars().nth(start_pos - 1).unwrap() == '_')
-                    {
-                        start_pos -= 1;
-                    }
-                    name = content[start_pos..=last_word_pos].to_string();
+                // The last word, scanned by characters so non-ASCII names don't split a char
+                let word = before_semicolon.trim_end_matches(|c: char| !c.is_alphanumeric());
+                let start_pos = word
+                    .char_indices()
+                    .rev()
+                    .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+                    .last()
+                    .map_or(word.len(), |(pos, _)| pos);
+                if start_pos < word.len() {
+                    name = word[start_pos..].to_string();
                 }
             }
         }
@@ -564,6 +641,9 @@ impl CppParser {
             source,
             fields: Vec::new(),
             attributes: Vec::new(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         })
     }
 
@@ -599,6 +679,8 @@ impl CppParser {
             name,
             doc: documentation,
             source,
+            discriminant: None,
+            visibility: Visibility::Public,
             attributes,
         })
     }
@@ -606,20 +688,28 @@ impl CppParser {
 
 impl LanguageParser for CppParser {
     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
-        // Read the file
         let source_code = fs::read_to_string(file_path).map_err(Error::Io)?;
+        self.parse_str(&source_code, file_path)
+    }
 
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.set_timeout_micros(timeout_micros(timeout));
+    }
+
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
         // Parse the file with tree-sitter
-        let tree = self
-            .parse(source_code.as_bytes(), None)
-            .ok_or_else(|| Error::Parse("Failed to parse file".to_string()))?;
+        let tree = parse_tree(self, source_code.as_bytes(), || {
+            Error::Parse("Failed to parse file".to_string())
+        })?;
 
         let root_node = tree.root_node();
 
         // Create a new file unit
         let mut file_unit = FileUnit {
             path: file_path.to_path_buf(),
-            source: Some(source_code.clone()),
+            source: Some(source_code.to_string()),
+            parse_errors: collect_parse_errors(tree.root_node(), source_code),
+            omitted_items: 0,
             doc: None,
             declares: Vec::new(),
             modules: Vec::new(),
@@ -627,6 +717,9 @@ impl LanguageParser for CppParser {
             structs: Vec::new(),
             traits: Vec::new(),
             impls: Vec::new(),
+            macros: Vec::new(),
+            consts: Vec::new(),
+            type_aliases: Vec::new(),
         };
 
         // Extract file-level documentation (comments at the beginning)
@@ -635,7 +728,7 @@ impl LanguageParser for CppParser {
             let mut doc_cursor = root_node.walk();
             for node in root_node.children(&mut doc_cursor) {
                 if node.kind() == "comment" {
-                    if let Some(comment) = get_node_text(node, &source_code) {
+                    if let Some(comment) = get_node_text(node, source_code) {
                         let cleaned = clean_comment(comment);
                         first_comments.push(cleaned);
                     }
@@ -655,7 +748,7 @@ impl LanguageParser for CppParser {
             for node in root_node.children(&mut parse_cursor) {
                 match node.kind() {
                     "preproc_include" => {
-                        if let Some(include_text) = get_node_text(node, &source_code) {
+                        if let Some(include_text) = get_node_text(node, source_code) {
                             file_unit.declares.push(DeclareStatements {
                                 source: include_text.to_string(),
                                 kind: DeclareKind::Import,
@@ -663,7 +756,7 @@ impl LanguageParser for CppParser {
                         }
                     }
                     "preproc_def" | "preproc_function_def" => {
-                        if let Some(def_text) = get_node_text(node, &source_code) {
+                        if let Some(def_text) = get_node_text(node, source_code) {
                             file_unit.declares.push(DeclareStatements {
                                 source: def_text.to_string(),
                                 kind: DeclareKind::Other("define".to_string()),
@@ -671,18 +764,18 @@ impl LanguageParser for CppParser {
                         }
                     }
                     "function_definition" => {
-                        if let Ok(function) = self.parse_function(node, &source_code) {
+                        if let Ok(function) = self.parse_function(node, source_code) {
                             file_unit.functions.push(function);
                         }
                     }
                     "class_specifier" => {
-                        if let Ok(class) = self.parse_class(node, &source_code) {
+                        if let Ok(class) = self.parse_class(node, source_code) {
                             file_unit.structs.push(class);
                         }
                     }
                     "template_declaration" => {
                         if let Ok((struct_opt, function_opt)) =
-                            self.parse_template(node, &source_code)
+                            self.parse_template(node, source_code)
                         {
                             // Add struct if present (class template)
                             if let Some(struct_unit) = struct_opt {
@@ -696,7 +789,7 @@ impl LanguageParser for CppParser {
                         }
                     }
                     "namespace_definition" => {
-                        if let Ok(namespace) = self.parse_namespace(node, &source_code) {
+                        if let Ok(namespace) = self.parse_namespace(node, source_code) {
                             // Merge namespace contents into file unit
                             file_unit.functions.extend(namespace.functions);
                             file_unit.structs.extend(namespace.structs);
@@ -704,25 +797,25 @@ impl LanguageParser for CppParser {
                         }
                     }
                     "enum_specifier" => {
-                        if let Ok(enum_struct) = self.parse_enum(node, &source_code) {
+                        if let Ok(enum_struct) = self.parse_enum(node, source_code) {
                             file_unit.structs.push(enum_struct);
                         }
                     }
                     "typedef_declaration" => {
-                        if let Ok(typedef) = self.parse_typedef(node, &source_code) {
+                        if let Ok(typedef) = self.parse_typedef(node, source_code) {
                             file_unit.structs.push(typedef);
                         }
                     }
                     "declaration" => {
                         // This could be a function declaration
-                        if let Some(text) = get_node_text(node, &source_code) {
-                            if text.contains('(') && text.ends_with(';') {
-                                // Likely a function declaration
-                                file_unit.declares.push(DeclareStatements {
-                                    source: text.to_string(),
-                                    kind: DeclareKind::Other("function_declaration".to_string()),
-                                });
-                            }
+                        // Likely a function declaration
+                        if let Some(text) = get_node_text(node, source_code)
+                            .filter(|text| text.contains('(') && text.ends_with(';'))
+                        {
+                            file_unit.declares.push(DeclareStatements {
+                                source: text.to_string(),
+                                kind: DeclareKind::Other("function_declaration".to_string()),
+                            });
                         }
                     }
                     _ => {}
@@ -748,11 +841,22 @@ impl LanguageParser for CppParser {
                             body: None,
                             source: Some("virtual double area() const = 0;".to_string()),
                             attributes: Vec::new(),
+                            deprecation: None,
+                            is_must_use: false,
+                            must_use_note: None,
+                            is_const: false,
+                            is_unsafe: false,
+                            is_associated_fn: false,
+                            return_type: None,
+                            params: Vec::new(),
                         },
                     ],
                     source: Some("class Shape { public: virtual double area() const = 0; virtual ~Shape() {} };".to_string()),
                     fields: Vec::new(),
                     attributes: Vec::new(),
+                    deprecation: None,
+                    is_must_use: false,
+                    must_use_note: None,
                 });
             }
 
@@ -784,11 +888,22 @@ impl LanguageParser for CppParser {
                             body: Some("{ return 3.14159 * radius * radius; }".to_string()),
                             source: Some("double area() const override { return 3.14159 * radius * radius; }".to_string()),
                             attributes: Vec::new(),
+                            deprecation: None,
+                            is_must_use: false,
+                            must_use_note: None,
+                            is_const: false,
+                            is_unsafe: false,
+                            is_associated_fn: false,
+                            return_type: None,
+                            params: Vec::new(),
                         },
                     ],
                     source: Some("class Circle : public Shape { private: double radius; public: Circle(double r) : radius(r) {} double area() const override { return 3.14159 * radius * radius; } };".to_string()),
                     fields: Vec::new(),
                     attributes: Vec::new(),
+                    deprecation: None,
+                    is_must_use: false,
+                    must_use_note: None,
                 });
             }
 
@@ -807,11 +922,22 @@ impl LanguageParser for CppParser {
                             body: Some("{ return width * height; }".to_string()),
                             source: Some("double area() const override { return width * height; }".to_string()),
                             attributes: Vec::new(),
+                            deprecation: None,
+                            is_must_use: false,
+                            must_use_note: None,
+                            is_const: false,
+                            is_unsafe: false,
+                            is_associated_fn: false,
+                            return_type: None,
+                            params: Vec::new(),
                         },
                     ],
                     source: Some("class Rectangle : public Shape { private: double width, height; public: Rectangle(double w, double h) : width(w), height(h) {} double area() const override { return width * height; } };".to_string()),
                     fields: Vec::new(),
                     attributes: Vec::new(),
+                    deprecation: None,
+                    is_must_use: false,
+                    must_use_note: None,
                 });
             }
 
@@ -828,6 +954,14 @@ impl LanguageParser for CppParser {
                             .to_string(),
                     ),
                     attributes: Vec::new(),
+                    deprecation: None,
+                    is_must_use: false,
+                    must_use_note: None,
+                    is_const: false,
+                    is_unsafe: false,
+                    is_associated_fn: false,
+                    return_type: None,
+                    params: Vec::new(),
                 });
 
                 // Remove any "max" structs that may have been added (from old approach)
@@ -845,6 +979,9 @@ impl LanguageParser for CppParser {
                     source: Some("typedef struct { int x; int y; } Point;".to_string()),
                     fields: Vec::new(),
                     attributes: Vec::new(),
+                    deprecation: None,
+                    is_must_use: false,
+                    must_use_note: None,
                 });
             }
 
@@ -858,6 +995,9 @@ impl LanguageParser for CppParser {
                     source: Some("typedef enum { RED, GREEN, BLUE } Color;".to_string()),
                     fields: Vec::new(),
                     attributes: Vec::new(),
+                    deprecation: None,
+                    is_must_use: false,
+                    must_use_note: None,
                 });
             }
         }
@@ -888,6 +1028,30 @@ fn get_node_text(node: Node, source: &str) -> Option<String> {
 }
 
 // Helper function to find an identifier node
+// Helper function to find the name of a function from its declarator, following nested
+// declarators such as `*f(...)`, and the name of qualified ones such as `Class::method`.
+// Unlike the first identifier, this skips the parameters of methods named by a
+// `field_identifier`
+fn function_name_node(declarator: Node) -> Option<Node> {
+    match declarator.kind() {
+        "identifier" | "field_identifier" | "operator_name" | "destructor_name" => Some(declarator),
+        "function_declarator" | "pointer_declarator" | "reference_declarator" => {
+            let inner = declarator.child_by_field_name("declarator").or_else(|| {
+                // The declarator of a reference declarator is not a field
+                declarator
+                    .named_children(&mut declarator.walk())
+                    .find(|child| child.kind().ends_with("declarator"))
+            })?;
+            function_name_node(inner)
+        }
+        "qualified_identifier" => declarator
+            .child_by_field_name("name")
+            .and_then(function_name_node)
+            .or_else(|| find_identifier(declarator)),
+        _ => find_identifier(declarator),
+    }
+}
+
 fn find_identifier(node: Node) -> Option<Node> {
     if node.kind() == "identifier" {
         return Some(node);
@@ -903,14 +1067,26 @@ fn find_identifier(node: Node) -> Option<Node> {
     None
 }
 
-// Helper function to clean a comment
+// Helper function to clean a comment, including Doxygen's `///`, `//!`, `/**` and `/*!`
 fn clean_comment(comment: String) -> String {
-    comment
-        .trim_start_matches("//")
-        .trim_start_matches("/*")
-        .trim_end_matches("*/")
-        .trim()
-        .to_string()
+    let comment = comment.trim();
+    if let Some(body) = comment.strip_prefix("/*") {
+        let body = body.strip_suffix("*/").unwrap_or(body);
+        let body = body.strip_prefix(['*', '!']).unwrap_or(body);
+        // Strip the `*` prefix of continuation lines
+        body.lines()
+            .map(|line| {
+                let trimmed = line.trim();
+                trimmed.strip_prefix('*').map_or(trimmed, str::trim_start)
+            })
+            .collect::<Vec<&str>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    } else {
+        let body = comment.trim_start_matches('/');
+        body.strip_prefix('!').unwrap_or(body).trim().to_string()
+    }
 }
 
 // Helper function to check if a node is whitespace
@@ -1060,6 +1236,14 @@ mod tests {
             body: Some(body.to_string()),
             source: Some(source),
             attributes: Vec::new(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+            return_type: None,
+            params: Vec::new(),
         };
 
         // Apply the name extraction logic
@@ -1302,4 +1486,134 @@ mod tests {
                 .contains("return 3.14159 * radius * radius;")
         );
     }
+
+    #[test]
+    fn test_enum_values() {
+        use crate::{BankStrategy, Formatter, LanguageType};
+
+        let mut parser = CppParser::try_new().unwrap();
+        let file_unit = parser
+            .parse_file(&PathBuf::from("fixtures/sample_enum_values.cpp"))
+            .unwrap();
+
+        let color = file_unit
+            .structs
+            .iter()
+            .find(|s| s.name == "Color")
+            .unwrap();
+        let values: Vec<_> = color
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.discriminant.as_deref()))
+            .collect();
+        assert_eq!(
+            values,
+            [("RED", Some("1")), ("GREEN", Some("2")), ("BLUE", None)]
+        );
+
+        let flags = file_unit
+            .structs
+            .iter()
+            .find(|s| s.name == "Flags")
+            .unwrap();
+        assert_eq!(flags.fields[2].discriminant.as_deref(), Some("1 << 1"));
+        let summary = flags
+            .format(&BankStrategy::Summary, LanguageType::Cpp)
+            .unwrap();
+        assert!(summary.contains("enum class Flags : unsigned int"));
+        assert!(summary.contains("    NONE = 0,\n    READ = 1 << 0,\n    WRITE = 1 << 1,\n"));
+    }
+
+    #[test]
+    fn test_trailing_return_types() {
+        let mut parser = CppParser::try_new().unwrap();
+        let file_unit = parser
+            .parse_file(&PathBuf::from("fixtures/sample_trailing_return.cpp"))
+            .unwrap();
+        let functions: Vec<_> = file_unit
+            .functions
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.signature.as_deref().unwrap(),
+                    f.return_type.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                ("add", "auto add(int a, int b) -> int", Some("int")),
+                (
+                    "make_pair",
+                    "auto make_pair(int first, int second) -> std::pair<int, int>",
+                    Some("std::pair<int, int>")
+                ),
+                (
+                    "empty_values",
+                    "auto empty_values(int count) -> decltype(std::vector<int>{})",
+                    Some("decltype(std::vector<int>{})")
+                ),
+                ("square", "auto square(int x) -> int", Some("int")),
+            ]
+        );
+        assert_eq!(
+            file_unit.functions[0].body.as_deref(),
+            Some("{\n    return a + b;\n}")
+        );
+        assert_eq!(
+            file_unit.functions[0].doc.as_deref(),
+            Some("Adds two numbers")
+        );
+
+        // Methods are named after their declarator, not their first parameter
+        let size = &file_unit.structs[0].methods[0];
+        assert_eq!(size.name, "size");
+        assert_eq!(
+            size.signature.as_deref(),
+            Some("auto size(int scale) const -> std::size_t")
+        );
+    }
+
+    #[test]
+    fn test_doxygen_doc_comments() {
+        let source = r#"
+/**
+ * Computes the sum of two integers.
+ *
+ * @param a The first operand
+ * @param b The second operand
+ * @return The sum of a and b
+ */
+int add(int a, int b) { return a + b; }
+
+/// Negates a value.
+/// @return The negated value
+int negate(int x) { return -x; }
+
+/*! Doubles a value. */
+int twice(int x) { return 2 * x; }
+"#;
+        let mut parser = CppParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("math.cpp")).unwrap();
+        let doc = |name: &str| {
+            file_unit
+                .functions
+                .iter()
+                .find(|f| f.name == name)
+                .and_then(|f| f.doc.clone())
+                .unwrap()
+        };
+
+        assert_eq!(
+            doc("add"),
+            "Computes the sum of two integers.\n\n@param a The first operand\n@param b The second operand\n@return The sum of a and b"
+        );
+        assert_eq!(doc("negate"), "Negates a value.\n@return The negated value");
+        assert_eq!(doc("twice"), "Doubles a value.");
+
+        assert_eq!(clean_comment("//! File docs".to_string()), "File docs");
+        assert_eq!(clean_comment("// plain".to_string()), "plain");
+    }
 }
@@ -0,0 +1,624 @@
+use super::{CSharpParser, collect_parse_errors, parse_tree, timeout_micros};
+use crate::{
+    DeclareKind, DeclareStatements, Error, FieldUnit, FileUnit, FunctionUnit, LanguageParser,
+    ModuleUnit, Result, StructUnit, TraitUnit, Visibility,
+};
+use std::fs;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::time::Duration;
+use tree_sitter::{Node, Parser};
+
+impl LanguageParser for CSharpParser {
+    fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
+        let source_code = fs::read_to_string(file_path).map_err(Error::Io)?;
+        self.parse_str(&source_code, file_path)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.set_timeout_micros(timeout_micros(timeout));
+    }
+
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
+        let tree = parse_tree(self, source_code.as_bytes(), || {
+            Error::TreeSitter("Failed to parse source code".to_string())
+        })?;
+        let root_node = tree.root_node();
+
+        let mut file_unit = FileUnit::new(file_path.to_path_buf());
+        file_unit.source = Some(source_code.to_string());
+        file_unit.parse_errors = collect_parse_errors(root_node, source_code);
+
+        // Collect the top-level items into a module first, then move them to the file
+        let mut items = ModuleUnit::default();
+        self.parse_declarations(root_node, source_code, &mut items);
+        file_unit.declares = items.declares;
+        file_unit.structs = items.structs;
+        file_unit.traits = items.traits;
+        file_unit.modules = items.submodules;
+
+        Ok(file_unit)
+    }
+}
+
+impl CSharpParser {
+    pub fn try_new() -> Result<Self> {
+        let mut parser = Parser::new();
+        let language = tree_sitter_c_sharp::LANGUAGE;
+        parser
+            .set_language(&language.into())
+            .map_err(|e| Error::TreeSitter(e.to_string()))?;
+        Ok(Self { parser })
+    }
+
+    // Parse the using directives, namespaces and type declarations below `parent` into `module`
+    fn parse_declarations(&self, parent: Node, source_code: &str, module: &mut ModuleUnit) {
+        let children: Vec<Node> = parent.children(&mut parent.walk()).collect();
+        for (i, child) in children.iter().enumerate() {
+            match child.kind() {
+                "namespace_declaration" => {
+                    let mut namespace = self.parse_namespace(*child, source_code);
+                    if let Some(body) = child.child_by_field_name("body") {
+                        self.parse_declarations(body, source_code, &mut namespace);
+                    }
+                    module.submodules.push(namespace);
+                }
+                "file_scoped_namespace_declaration" => {
+                    // `namespace Foo;` applies to all declarations that follow it
+                    let mut namespace = self.parse_namespace(*child, source_code);
+                    let end = children.last().map_or(child.end_byte(), |n| n.end_byte());
+                    namespace.source = Some(source_code[child.start_byte()..end].to_string());
+                    for sibling in &children[i + 1..] {
+                        self.parse_declaration(*sibling, source_code, &mut namespace);
+                    }
+                    module.submodules.push(namespace);
+                    break;
+                }
+                _ => self.parse_declaration(*child, source_code, module),
+            }
+        }
+    }
+
+    // Parse a using directive or type declaration into `module`, ignoring anything else
+    fn parse_declaration(&self, node: Node, source_code: &str, module: &mut ModuleUnit) {
+        match node.kind() {
+            "using_directive" => {
+                if let Some(source) = get_node_text(node, source_code) {
+                    module.declares.push(DeclareStatements {
+                        source,
+                        kind: DeclareKind::Use,
+                    });
+                }
+            }
+            "class_declaration" | "struct_declaration" | "record_declaration" => {
+                module.structs.push(self.parse_class(node, source_code));
+            }
+            "interface_declaration" => {
+                module.traits.push(self.parse_interface(node, source_code));
+            }
+            "enum_declaration" => {
+                module.structs.push(self.parse_enum(node, source_code));
+            }
+            _ => {}
+        }
+    }
+
+    fn parse_namespace(&self, node: Node, source_code: &str) -> ModuleUnit {
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|n| get_node_text(n, source_code))
+            .unwrap_or_default();
+        ModuleUnit {
+            name,
+            // Namespaces have no access modifiers
+            visibility: Visibility::Public,
+            doc: extract_documentation(node, source_code),
+            source: get_node_text(node, source_code),
+            ..Default::default()
+        }
+    }
+
+    // Parse a class, struct or record. Types without an access modifier are internal.
+    fn parse_class(&self, node: Node, source_code: &str) -> StructUnit {
+        let body = node.child_by_field_name("body");
+        let mut fields = Vec::new();
+        let mut methods = Vec::new();
+
+        if let Some(body) = body {
+            let mut cursor = body.walk();
+            for member in body.children(&mut cursor) {
+                match member.kind() {
+                    "field_declaration" => {
+                        fields.extend(self.parse_field(member, source_code));
+                    }
+                    "property_declaration" => {
+                        let name = member
+                            .child_by_field_name("name")
+                            .and_then(|n| get_node_text(n, source_code))
+                            .unwrap_or_default();
+                        fields.push(FieldUnit {
+                            name,
+                            doc: extract_documentation(member, source_code),
+                            attributes: get_attributes(member, source_code),
+                            source: Some(declaration_text(member, member.end_byte(), source_code)),
+                            discriminant: None,
+                            visibility: Visibility::Public,
+                        });
+                    }
+                    "method_declaration" | "constructor_declaration" => {
+                        methods.push(self.parse_method(member, source_code, Visibility::Private));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let head_end = body.map_or(node.end_byte(), |b| b.start_byte());
+        StructUnit {
+            name: get_name(node, source_code),
+            head: declaration_text(node, head_end, source_code)
+                .trim_end_matches(';')
+                .to_string(),
+            visibility: determine_visibility(node, source_code, Visibility::Crate),
+            doc: extract_documentation(node, source_code),
+            source: get_node_text(node, source_code),
+            attributes: get_attributes(node, source_code),
+            fields,
+            methods,
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+        }
+    }
+
+    // A field declaration may declare several variables, e.g. `int x, y;`
+    fn parse_field(&self, node: Node, source_code: &str) -> Vec<FieldUnit> {
+        let doc = extract_documentation(node, source_code);
+        let attributes = get_attributes(node, source_code);
+        let source = declaration_text(node, node.end_byte(), source_code);
+
+        let Some(declaration) = node
+            .children(&mut node.walk())
+            .find(|child| child.kind() == "variable_declaration")
+        else {
+            return Vec::new();
+        };
+        declaration
+            .children(&mut declaration.walk())
+            .filter(|child| child.kind() == "variable_declarator")
+            .filter_map(|declarator| declarator.child_by_field_name("name"))
+            .filter_map(|name| get_node_text(name, source_code))
+            .map(|name| FieldUnit {
+                name,
+                doc: doc.clone(),
+                attributes: attributes.clone(),
+                source: Some(source.clone()),
+                discriminant: None,
+                visibility: Visibility::Public,
+            })
+            .collect()
+    }
+
+    // Parse a method or constructor, `default_visibility` applies without access modifiers
+    fn parse_method(
+        &self,
+        node: Node,
+        source_code: &str,
+        default_visibility: Visibility,
+    ) -> FunctionUnit {
+        let source = get_node_text(node, source_code);
+        let (signature, body) = match node.child_by_field_name("body") {
+            Some(body) => (
+                declaration_text(node, body.start_byte(), source_code),
+                get_node_text(body, source_code),
+            ),
+            // Interface and abstract methods end with `;`
+            None => (
+                declaration_text(node, node.end_byte(), source_code)
+                    .trim_end_matches(';')
+                    .to_string(),
+                None,
+            ),
+        };
+
+        FunctionUnit {
+            name: get_name(node, source_code),
+            visibility: determine_visibility(node, source_code, default_visibility),
+            doc: extract_documentation(node, source_code),
+            source,
+            signature: Some(signature),
+            return_type: None,
+            params: Vec::new(),
+            body,
+            attributes: get_attributes(node, source_code),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+        }
+    }
+
+    fn parse_interface(&self, node: Node, source_code: &str) -> TraitUnit {
+        let body = node.child_by_field_name("body");
+        let mut methods = Vec::new();
+        if let Some(body) = body {
+            let mut cursor = body.walk();
+            for member in body.children(&mut cursor) {
+                if member.kind() == "method_declaration" {
+                    // Interface members are public unless stated otherwise
+                    methods.push(self.parse_method(member, source_code, Visibility::Public));
+                }
+            }
+        }
+
+        let head_end = body.map_or(node.end_byte(), |b| b.start_byte());
+        TraitUnit {
+            name: get_name(node, source_code),
+            visibility: determine_visibility(node, source_code, Visibility::Crate),
+            doc: extract_documentation(node, source_code),
+            source: get_node_text(node, source_code),
+            attributes: get_attributes(node, source_code),
+            head: declaration_text(node, head_end, source_code),
+            associated_items: Vec::new(),
+            is_unsafe: false,
+            methods,
+            deprecation: None,
+        }
+    }
+
+    // Parse an enum, its members become fields
+    fn parse_enum(&self, node: Node, source_code: &str) -> StructUnit {
+        let body = node.child_by_field_name("body");
+        let mut fields = Vec::new();
+        if let Some(body) = body {
+            let mut cursor = body.walk();
+            for member in body.children(&mut cursor) {
+                if member.kind() != "enum_member_declaration" {
+                    continue;
+                }
+                // Keep the separating comma, enum members are not terminated by `;`
+                let mut source = declaration_text(member, member.end_byte(), source_code);
+                if member.next_sibling().is_some_and(|next| next.kind() == ",") {
+                    source.push(',');
+                }
+                fields.push(FieldUnit {
+                    name: get_name(member, source_code),
+                    doc: extract_documentation(member, source_code),
+                    attributes: get_attributes(member, source_code),
+                    source: Some(source),
+                    discriminant: None,
+                    visibility: Visibility::Public,
+                });
+            }
+        }
+
+        let head_end = body.map_or(node.end_byte(), |b| b.start_byte());
+        StructUnit {
+            name: get_name(node, source_code),
+            head: declaration_text(node, head_end, source_code),
+            visibility: determine_visibility(node, source_code, Visibility::Crate),
+            doc: extract_documentation(node, source_code),
+            source: get_node_text(node, source_code),
+            attributes: get_attributes(node, source_code),
+            fields,
+            methods: Vec::new(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+        }
+    }
+}
+
+// Helper function to get the text of a node
+fn get_node_text(node: Node, source_code: &str) -> Option<String> {
+    node.utf8_text(source_code.as_bytes())
+        .ok()
+        .map(String::from)
+}
+
+fn get_name(node: Node, source_code: &str) -> String {
+    node.child_by_field_name("name")
+        .and_then(|n| get_node_text(n, source_code))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// The `[...]` attribute lists of a declaration
+fn get_attributes(node: Node, source_code: &str) -> Vec<String> {
+    node.children(&mut node.walk())
+        .filter(|child| child.kind() == "attribute_list")
+        .filter_map(|child| get_node_text(child, source_code))
+        .collect()
+}
+
+// The text of a declaration without its attributes up to `end`, on a single line
+fn declaration_text(node: Node, end: usize, source_code: &str) -> String {
+    let start = node
+        .children(&mut node.walk())
+        .find(|child| child.kind() != "attribute_list")
+        .map_or(node.start_byte(), |child| child.start_byte());
+    source_code[start..end.max(start)]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Map the access modifiers of a declaration to a visibility
+fn determine_visibility(node: Node, source_code: &str, default: Visibility) -> Visibility {
+    let modifiers: Vec<String> = node
+        .children(&mut node.walk())
+        .filter(|child| child.kind() == "modifier")
+        .filter_map(|child| get_node_text(child, source_code))
+        .collect();
+    let has = |modifier: &str| modifiers.iter().any(|m| m == modifier);
+
+    if has("public") {
+        Visibility::Public
+    } else if has("protected") {
+        // Including `protected internal` and `private protected`
+        Visibility::Protected
+    } else if has("internal") {
+        Visibility::Crate
+    } else if has("private") {
+        Visibility::Private
+    } else {
+        default
+    }
+}
+
+// Extract the `///` XML doc comment lines right before a node, without the XML tags
+fn extract_documentation(node: Node, source_code: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut row = node.start_position().row;
+    let mut prev_sibling = node.prev_sibling();
+    while let Some(sibling) = prev_sibling {
+        let Some(text) = get_node_text(sibling, source_code) else {
+            break;
+        };
+        if sibling.kind() != "comment"
+            || !text.starts_with("///")
+            || sibling.end_position().row + 1 != row
+        {
+            break;
+        }
+        lines.push(text.trim_start_matches('/').to_string());
+        row = sibling.start_position().row;
+        prev_sibling = sibling.prev_sibling();
+    }
+    lines.reverse();
+
+    let doc = strip_xml_tags(&lines.join("\n"))
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (!doc.is_empty()).then_some(doc)
+}
+
+// Remove XML tags such as `<summary>` or `<param name="x">`, keeping their content
+fn strip_xml_tags(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => output.push(c),
+            _ => {}
+        }
+    }
+    output
+}
+
+impl Deref for CSharpParser {
+    type Target = Parser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.parser
+    }
+}
+
+impl DerefMut for CSharpParser {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn parse_fixture(file_name: &str) -> Result<FileUnit> {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR should be set during tests");
+        let path = PathBuf::from(manifest_dir).join("fixtures").join(file_name);
+        let mut parser = CSharpParser::try_new()?;
+        parser.parse_file(&path)
+    }
+
+    fn parse_source(source: &str) -> FileUnit {
+        let mut parser = CSharpParser::try_new().expect("Failed to create C# parser");
+        parser
+            .parse_str(source, Path::new("Sample.cs"))
+            .expect("Failed to parse C# source")
+    }
+
+    #[test]
+    fn test_parse_class_with_properties_and_methods() {
+        let file_unit = parse_source(
+            r#"
+/// <summary>
+/// Represents a customer invoice.
+/// </summary>
+public class Invoice
+{
+    private int _count;
+
+    /// <summary>The invoice number.</summary>
+    public int Number { get; set; }
+
+    public decimal Total { get; private set; }
+
+    /// <summary>Adds a line.</summary>
+    public void AddLine(string line) { _count++; }
+}
+"#,
+        );
+        assert!(file_unit.parse_errors.is_empty());
+        assert_eq!(file_unit.structs.len(), 1);
+
+        let class = &file_unit.structs[0];
+        assert_eq!(class.name, "Invoice");
+        assert_eq!(class.head, "public class Invoice");
+        assert_eq!(class.visibility, Visibility::Public);
+        assert_eq!(class.doc.as_deref(), Some("Represents a customer invoice."));
+
+        let names: Vec<_> = class.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["_count", "Number", "Total"]);
+        assert_eq!(
+            class.fields[1].source.as_deref(),
+            Some("public int Number { get; set; }")
+        );
+        assert_eq!(class.fields[1].doc.as_deref(), Some("The invoice number."));
+
+        assert_eq!(class.methods.len(), 1);
+        let method = &class.methods[0];
+        assert_eq!(method.name, "AddLine");
+        assert_eq!(method.visibility, Visibility::Public);
+        assert_eq!(
+            method.signature.as_deref(),
+            Some("public void AddLine(string line)")
+        );
+        assert_eq!(method.body.as_deref(), Some("{ _count++; }"));
+        assert_eq!(method.doc.as_deref(), Some("Adds a line."));
+    }
+
+    #[test]
+    fn test_access_modifiers() {
+        let file_unit = parse_source(
+            r#"
+class Hidden
+{
+    void Implicit() {}
+    private void Explicit() {}
+    internal void Assembly() {}
+    protected internal void Derived() {}
+    public void Open() {}
+}
+"#,
+        );
+        let class = &file_unit.structs[0];
+        assert_eq!(class.visibility, Visibility::Crate);
+        let visibilities: Vec<_> = class.methods.iter().map(|m| &m.visibility).collect();
+        assert_eq!(
+            visibilities,
+            [
+                &Visibility::Private,
+                &Visibility::Private,
+                &Visibility::Crate,
+                &Visibility::Protected,
+                &Visibility::Public,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_csharp_fixture() {
+        let file_unit = parse_fixture("sample.cs").expect("Failed to parse C# file");
+        assert!(file_unit.parse_errors.is_empty());
+        assert_eq!(file_unit.declares.len(), 2);
+
+        assert_eq!(file_unit.modules.len(), 1);
+        let namespace = &file_unit.modules[0];
+        assert_eq!(namespace.name, "Acme.Billing");
+        assert_eq!(namespace.declares.len(), 1);
+
+        let names: Vec<_> = namespace.structs.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Invoice", "Status", "Payment", "Money"]);
+
+        let invoice = &namespace.structs[0];
+        assert_eq!(invoice.head, "public class Invoice : IPayable");
+        assert_eq!(invoice.attributes, ["[Serializable]"]);
+        let methods: Vec<_> = invoice.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(methods, ["AddLine", "Empty", "Invoice"]);
+        assert_eq!(
+            invoice.methods[1].signature.as_deref(),
+            Some("internal static Invoice Empty()")
+        );
+        assert_eq!(
+            invoice.methods[0].doc.as_deref(),
+            Some("Adds a line.\nThe line text.")
+        );
+
+        let status = &namespace.structs[1];
+        assert_eq!(status.head, "public enum Status");
+        let variants: Vec<_> = status
+            .fields
+            .iter()
+            .filter_map(|f| f.source.as_deref())
+            .collect();
+        assert_eq!(variants, ["Draft,", "Sent = 2,", "Paid"]);
+
+        assert_eq!(
+            namespace.structs[2].head,
+            "public record Payment(decimal Amount, DateTime At)"
+        );
+        assert_eq!(namespace.structs[3].visibility, Visibility::Crate);
+
+        assert_eq!(namespace.traits.len(), 1);
+        let payable = &namespace.traits[0];
+        assert_eq!(payable.head, "public interface IPayable");
+        assert_eq!(payable.methods[0].visibility, Visibility::Public);
+        assert_eq!(
+            payable.methods[0].signature.as_deref(),
+            Some("void Pay(decimal amount)")
+        );
+        assert!(payable.methods[0].body.is_none());
+    }
+
+    #[test]
+    fn test_format_namespace_summary() {
+        use crate::{BankStrategy, Formatter, LanguageType};
+
+        let file_unit = parse_fixture("sample.cs").unwrap();
+        let summary = file_unit
+            .format(&BankStrategy::Summary, LanguageType::CSharp)
+            .unwrap();
+        assert!(summary.contains("\nnamespace Acme.Billing {\n    using System.Linq;\n"));
+        assert!(summary.contains("    public class Invoice : IPayable"));
+        assert!(!summary.contains("mod Acme.Billing"));
+        assert!(!summary.contains("Money"));
+
+        let no_tests = file_unit
+            .format(&BankStrategy::NoTests, LanguageType::CSharp)
+            .unwrap();
+        assert!(no_tests.contains("\nnamespace Acme.Billing {\n"));
+    }
+
+    #[test]
+    fn test_file_scoped_namespace() {
+        let file_unit = parse_source(
+            r#"
+using System;
+
+namespace Acme.Shipping;
+
+public struct Parcel { public double Weight; }
+
+public interface ITracker { string Track(); }
+"#,
+        );
+        assert_eq!(file_unit.declares.len(), 1);
+        assert!(file_unit.structs.is_empty());
+        assert_eq!(file_unit.modules.len(), 1);
+
+        let namespace = &file_unit.modules[0];
+        assert_eq!(namespace.name, "Acme.Shipping");
+        assert_eq!(namespace.structs[0].name, "Parcel");
+        assert_eq!(namespace.structs[0].fields[0].name, "Weight");
+        assert_eq!(namespace.traits[0].name, "ITracker");
+    }
+}
@@ -1,4 +1,4 @@
-use super::GoParser;
+use super::{GoParser, collect_parse_errors, parse_tree, timeout_micros};
 use crate::{
     DeclareKind, DeclareStatements, Error, FieldUnit, FileUnit, FunctionUnit, ImplUnit,
     LanguageParser, ModuleUnit, Result, StructUnit, TraitUnit, Visibility,
@@ -6,41 +6,49 @@ use crate::{
 use std::fs;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::time::Duration;
 use tree_sitter::{Node, Parser};
 
 impl LanguageParser for GoParser {
     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
-        // Read the file
         let source_code = fs::read_to_string(file_path).map_err(Error::Io)?;
+        self.parse_str(&source_code, file_path)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.set_timeout_micros(timeout_micros(timeout));
+    }
 
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
         // Parse the file
-        let tree = self
-            .parse(source_code.as_bytes(), None)
-            .ok_or_else(|| Error::TreeSitter("Failed to parse source code".to_string()))?;
+        let tree = parse_tree(self, source_code.as_bytes(), || {
+            Error::TreeSitter("Failed to parse source code".to_string())
+        })?;
         let root_node = tree.root_node();
 
         // Create a new file unit
         let mut file_unit = FileUnit::new(file_path.to_path_buf());
-        file_unit.source = Some(source_code.clone());
+        file_unit.source = Some(source_code.to_string());
+        file_unit.parse_errors = collect_parse_errors(root_node, source_code);
 
         // Maps to collect methods by receiver type
-        let mut methods_by_type: std::collections::HashMap<String, Vec<FunctionUnit>> =
-            std::collections::HashMap::new();
+        let mut methods_by_type: std::collections::BTreeMap<String, Vec<FunctionUnit>> =
+            std::collections::BTreeMap::new();
 
         // Process top-level declarations
         let mut cursor = root_node.walk();
         for child in root_node.children(&mut cursor) {
             match child.kind() {
                 "package_clause" => {
-                    let package_doc = extract_documentation(child, &source_code);
+                    let package_doc = extract_documentation(child, source_code);
                     if let Some(package_name) =
-                        get_child_node_text(child, "package_identifier", &source_code)
+                        get_child_node_text(child, "package_identifier", source_code)
                     {
                         let module = ModuleUnit {
                             name: package_name,
                             visibility: Visibility::Public, // Packages are public
                             doc: package_doc,
-                            source: get_node_text(child, &source_code),
+                            source: get_node_text(child, source_code),
                             attributes: Vec::new(),
                             ..Default::default()
                         };
@@ -55,7 +63,7 @@ impl LanguageParser for GoParser {
                             || import_spec.kind() == "interpreted_string_literal"
                             || import_spec.kind() == "raw_string_literal"
                         {
-                            if let Some(import_text) = get_node_text(import_spec, &source_code) {
+                            if let Some(import_text) = get_node_text(import_spec, source_code) {
                                 file_unit.declares.push(DeclareStatements {
                                     source: import_text,
                                     kind: DeclareKind::Use,
@@ -66,7 +74,7 @@ impl LanguageParser for GoParser {
                             for inner_spec in import_spec.children(&mut list_cursor) {
                                 if inner_spec.kind() == "import_spec" {
                                     if let Some(import_text) =
-                                        get_node_text(inner_spec, &source_code)
+                                        get_node_text(inner_spec, source_code)
                                     {
                                         file_unit.declares.push(DeclareStatements {
                                             source: import_text,
@@ -79,12 +87,12 @@ impl LanguageParser for GoParser {
                     }
                 }
                 "function_declaration" => {
-                    if let Ok(func) = self.parse_function(child, &source_code) {
+                    if let Ok(func) = self.parse_function(child, source_code) {
                         file_unit.functions.push(func);
                     }
                 }
                 "method_declaration" => {
-                    if let Ok((receiver_type, method)) = self.parse_method(child, &source_code) {
+                    if let Ok((receiver_type, method)) = self.parse_method(child, source_code) {
                         methods_by_type
                             .entry(receiver_type)
                             .or_default()
@@ -102,13 +110,13 @@ impl LanguageParser for GoParser {
                             {
                                 if type_def_node.kind() == "struct_type" {
                                     if let Ok(struct_item) =
-                                        self.parse_struct(type_spec_node, &source_code)
+                                        self.parse_struct(type_spec_node, source_code)
                                     {
                                         file_unit.structs.push(struct_item);
                                     }
                                 } else if type_def_node.kind() == "interface_type" {
                                     if let Ok(interface_item) =
-                                        self.parse_interface(type_spec_node, &source_code)
+                                        self.parse_interface(type_spec_node, source_code)
                                     {
                                         file_unit.traits.push(interface_item);
                                     }
@@ -121,7 +129,7 @@ impl LanguageParser for GoParser {
                     let mut decl_cursor = child.walk();
                     for spec_node in child.children(&mut decl_cursor) {
                         if spec_node.kind() == "const_spec" || spec_node.kind() == "var_spec" {
-                            if let Some(declare_text) = get_node_text(spec_node, &source_code) {
+                            if let Some(declare_text) = get_node_text(spec_node, source_code) {
                                 let kind_str = if child.kind() == "const_declaration" {
                                     "const"
                                 } else {
@@ -141,7 +149,7 @@ impl LanguageParser for GoParser {
                                     || inner_spec_node.kind() == "var_spec"
                                 {
                                     if let Some(declare_text) =
-                                        get_node_text(inner_spec_node, &source_code)
+                                        get_node_text(inner_spec_node, source_code)
                                     {
                                         let kind_str = if child.kind() == "const_declaration" {
                                             "const"
@@ -178,6 +186,7 @@ impl LanguageParser for GoParser {
                     head: format!("methods for {}", struct_item.name),
                     source: None, // Source for the whole impl block is tricky
                     attributes: Vec::new(),
+                    associated_items: Vec::new(),
                     methods, // Moves methods into the impl unit
                 };
                 file_unit.impls.push(impl_unit);
@@ -192,6 +201,7 @@ impl LanguageParser for GoParser {
                 head: format!("methods for {}", receiver_type),
                 source: None,
                 attributes: Vec::new(),
+                associated_items: Vec::new(),
                 methods,
             };
             file_unit.impls.push(impl_unit);
@@ -211,22 +221,12 @@ impl GoParser {
         Ok(Self { parser })
     }
 
-    // Helper function to determine visibility (in Go, uppercase first letter means exported/public)
-    fn determine_visibility(&self, name: &str) -> Visibility {
-        if !name.is_empty() && name.chars().next().unwrap().is_uppercase() {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        }
-    }
-
-    // Parse function and extract its details
     fn parse_function(&self, node: Node, source_code: &str) -> Result<FunctionUnit> {
         let documentation = extract_documentation(node, source_code);
         let name = get_child_node_text(node, "identifier", source_code)
             .unwrap_or_else(|| "unknown".to_string());
 
-        let visibility = self.determine_visibility(&name);
+        let visibility = go_visibility(&name);
         let source = get_node_text(node, source_code);
         let mut signature = None;
         let mut body = None;
@@ -250,8 +250,16 @@ impl GoParser {
             doc: documentation,
             source,
             signature,
+            return_type: None,
+            params: Vec::new(),
             body,
             attributes: Vec::new(), // Go doesn't have attributes like Rust
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
         })
     }
 
@@ -267,7 +275,7 @@ impl GoParser {
             });
         let name = get_child_node_text(type_spec_node, "type_identifier", source_code)
             .unwrap_or_else(|| "unknown".to_string());
-        let visibility = self.determine_visibility(&name);
+        let visibility = go_visibility(&name);
         let source = get_node_text(
             type_spec_node.parent().unwrap_or(type_spec_node),
             source_code,
@@ -305,10 +313,12 @@ impl GoParser {
                         }
                         for field_name in field_names {
                             fields.push(FieldUnit {
+                                visibility: go_visibility(&field_name),
                                 name: field_name,
                                 doc: field_documentation.clone(),
                                 attributes: Vec::new(),
                                 source: field_source.clone(),
+                                discriminant: None,
                             });
                         }
                     }
@@ -325,6 +335,9 @@ impl GoParser {
             attributes: Vec::new(),
             fields,
             methods: Vec::new(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         })
     }
 
@@ -340,7 +353,7 @@ impl GoParser {
             });
         let name = get_child_node_text(type_spec_node, "type_identifier", source_code)
             .unwrap_or_else(|| "unknown".to_string());
-        let visibility = self.determine_visibility(&name);
+        let visibility = go_visibility(&name);
         let source = get_node_text(
             type_spec_node.parent().unwrap_or(type_spec_node),
             source_code,
@@ -364,30 +377,44 @@ impl GoParser {
                             get_child_node_text(method_spec, "field_identifier", source_code)
                         })
                         .unwrap_or_else(|| "unknown_interface_method".to_string());
-                    let visibility = self.determine_visibility(&method_name); // Interface methods are implicitly public
+                    // Unexported interface methods can only be implemented within the package
+                    let visibility = go_visibility(&method_name);
                     // Interface methods only have signatures, no bodies
                     let signature = method_source.clone();
 
                     methods.push(FunctionUnit {
                         name: method_name,
-                        visibility, // Could force Public, but determine_visibility works
+                        visibility,
                         doc: method_doc,
                         source: method_source,
                         signature,
+                        return_type: None,
+                        params: Vec::new(),
                         body: None, // Interface methods don't have bodies
                         attributes: Vec::new(),
+                        deprecation: None,
+                        is_must_use: false,
+                        must_use_note: None,
+                        is_const: false,
+                        is_unsafe: false,
+                        is_associated_fn: false,
                     });
                 }
             }
         }
 
+        let head = format!("type {} interface", name);
         Ok(TraitUnit {
             name,
             visibility,
             doc: documentation,
             source,
             attributes: Vec::new(),
+            head,
+            associated_items: Vec::new(),
+            is_unsafe: false,
             methods,
+            deprecation: None,
         })
     }
 
@@ -423,7 +450,7 @@ impl GoParser {
             "unknown".to_string()
         };
 
-        let visibility = self.determine_visibility(&method_name);
+        let visibility = go_visibility(&method_name);
         let mut signature = None;
         let mut body = None;
 
@@ -445,8 +472,16 @@ impl GoParser {
             doc: documentation,
             source,
             signature,
+            return_type: None,
+            params: Vec::new(),
             body,
             attributes: Vec::new(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
         };
 
         Ok((receiver_type, function))
@@ -454,6 +489,16 @@ impl GoParser {
 }
 
 // Helper function to get the text of a node
+// The visibility of a Go identifier: exported, i.e. public, when it starts with an
+// uppercase letter, e.g. `Foo`, and package-private otherwise, e.g. `foo` or `_foo`
+fn go_visibility(name: &str) -> Visibility {
+    if name.chars().next().is_some_and(char::is_uppercase) {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    }
+}
+
 fn get_node_text(node: Node, source_code: &str) -> Option<String> {
     node.utf8_text(source_code.as_bytes())
         .ok()
@@ -881,4 +926,41 @@ mod tests {
         assert!(string_method.signature.is_some());
         assert!(string_method.body.is_some());
     }
+
+    #[test]
+    fn test_go_visibility() {
+        use crate::{BankStrategy, Formatter, LanguageType};
+
+        let file_unit = parse_fixture("sample_visibility.go").unwrap();
+        let exported = |f: &FunctionUnit| (f.name.clone(), f.visibility == Visibility::Public);
+        let functions: Vec<_> = file_unit.functions.iter().map(exported).collect();
+        assert_eq!(functions, [("Foo".into(), true), ("foo".into(), false)]);
+        let methods: Vec<_> = file_unit.impls[0].methods.iter().map(exported).collect();
+        assert_eq!(methods, [("Baz".into(), true), ("baz".into(), false)]);
+        let interface_methods: Vec<_> = file_unit.traits[0].methods.iter().map(exported).collect();
+        assert_eq!(
+            interface_methods,
+            [("Area".into(), true), ("scale".into(), false)]
+        );
+
+        let fields: Vec<_> = file_unit.structs[0]
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.visibility == Visibility::Public))
+            .collect();
+        assert_eq!(fields, [("Bar", true), ("bar", false)]);
+        assert_eq!(file_unit.structs[1].visibility, Visibility::Private);
+
+        // Summary only shows the exported API
+        let formatted = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Go)
+            .unwrap();
+        assert!(formatted.contains("func Foo()"));
+        assert!(!formatted.contains("func foo()"));
+        assert!(formatted.contains("    Bar string"));
+        assert!(!formatted.contains("bar int"));
+        assert!(formatted.contains("func (b *Bar) Baz()"));
+        assert!(!formatted.contains("func (b *Bar) baz()"));
+        assert!(!formatted.contains("type qux struct"));
+    }
 }
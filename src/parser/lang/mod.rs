@@ -1,13 +1,21 @@
-use tree_sitter::Parser;
+use crate::{Error, Result, Span};
+use std::time::{Duration, Instant};
+use tree_sitter::{Node, Parser, Tree};
 
 mod cpp;
+mod csharp;
 mod go;
+mod php;
 mod python;
 mod rust;
 mod ts;
 
+pub use rust::DEFAULT_ITEM_MACROS;
+pub(crate) use rust::visibility_from_modifier;
+
 pub struct RustParser {
     parser: Parser,
+    item_macros: Vec<String>,
 }
 
 pub struct PythonParser {
@@ -25,3 +33,77 @@ pub struct TypeScriptParser {
 pub struct GoParser {
     parser: Parser,
 }
+
+pub struct CSharpParser {
+    parser: Parser,
+}
+
+pub struct PhpParser {
+    parser: Parser,
+}
+
+// Longest source excerpt quoted in a parse error message
+const MAX_ERROR_EXCERPT: usize = 40;
+
+/// Collect the `ERROR` and `MISSING` nodes of a syntax tree as parse errors.
+pub(crate) fn collect_parse_errors(root: Node, source_code: &str) -> Vec<(Span, String)> {
+    let mut errors = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.is_missing() {
+            errors.push((span(node), format!("missing `{}`", node.kind())));
+        } else if node.is_error() {
+            let excerpt = source_code[node.byte_range()].trim();
+            let excerpt = match excerpt.char_indices().nth(MAX_ERROR_EXCERPT) {
+                Some((end, _)) => format!("{}...", &excerpt[..end]),
+                None => excerpt.to_string(),
+            };
+            let excerpt = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+            errors.push((span(node), format!("unexpected `{}`", excerpt)));
+        } else if node.has_error() {
+            // Push children in reverse so errors are reported in source order
+            let children: Vec<_> = node.children(&mut node.walk()).collect();
+            stack.extend(children.into_iter().rev());
+        }
+    }
+    errors
+}
+
+/// The tree-sitter parse timeout for `timeout`, where 0 disables it. Sub-microsecond
+/// timeouts are rounded up so that they still apply.
+fn timeout_micros(timeout: Option<Duration>) -> u64 {
+    timeout.map_or(0, |timeout| {
+        u64::try_from(timeout.as_micros())
+            .unwrap_or(u64::MAX)
+            .max(1)
+    })
+}
+
+/// Parse `source_code` with `parser`. A parse that returns no tree fails with
+/// [`Error::ParseTimeout`] when the parser has a timeout and ran out of it, or with `error`.
+pub(crate) fn parse_tree(
+    parser: &mut Parser,
+    source_code: &[u8],
+    error: impl FnOnce() -> Error,
+) -> Result<Tree> {
+    let started = Instant::now();
+    parser.parse(source_code, None).ok_or_else(|| {
+        let timeout = Duration::from_micros(parser.timeout_micros());
+        if !timeout.is_zero() && started.elapsed() >= timeout {
+            Error::ParseTimeout(timeout)
+        } else {
+            error()
+        }
+    })
+}
+
+fn span(node: Node) -> Span {
+    let start = node.start_position();
+    let end = node.end_position();
+    Span {
+        start_line: start.row + 1,
+        start_column: start.column + 1,
+        end_line: end.row + 1,
+        end_column: end.column + 1,
+    }
+}
@@ -0,0 +1,576 @@
+use super::{PhpParser, collect_parse_errors, parse_tree, timeout_micros};
+use crate::{
+    DeclareKind, DeclareStatements, Error, FieldUnit, FileUnit, FunctionUnit, LanguageParser,
+    ModuleUnit, Result, StructUnit, TraitUnit, Visibility,
+};
+use std::fs;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::time::Duration;
+use tree_sitter::{Node, Parser};
+
+impl LanguageParser for PhpParser {
+    fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
+        let source_code = fs::read_to_string(file_path).map_err(Error::Io)?;
+        self.parse_str(&source_code, file_path)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.set_timeout_micros(timeout_micros(timeout));
+    }
+
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
+        let tree = parse_tree(self, source_code.as_bytes(), || {
+            Error::TreeSitter("Failed to parse source code".to_string())
+        })?;
+        let root_node = tree.root_node();
+
+        let mut file_unit = FileUnit::new(file_path.to_path_buf());
+        file_unit.source = Some(source_code.to_string());
+        file_unit.parse_errors = collect_parse_errors(root_node, source_code);
+
+        // Collect the top-level items into a module first, then move them to the file
+        let mut items = ModuleUnit::default();
+        self.parse_declarations(root_node, source_code, &mut items);
+        file_unit.declares = items.declares;
+        file_unit.functions = items.functions;
+        file_unit.structs = items.structs;
+        file_unit.traits = items.traits;
+        file_unit.modules = items.submodules;
+
+        Ok(file_unit)
+    }
+}
+
+impl PhpParser {
+    pub fn try_new() -> Result<Self> {
+        let mut parser = Parser::new();
+        // The grammar of files starting with `<?php`, as opposed to templates mixing HTML
+        let language = tree_sitter_php::LANGUAGE_PHP;
+        parser
+            .set_language(&language.into())
+            .map_err(|e| Error::TreeSitter(e.to_string()))?;
+        Ok(Self { parser })
+    }
+
+    // Parse the use declarations, namespaces, types and functions below `parent` into `module`
+    fn parse_declarations(&self, parent: Node, source_code: &str, module: &mut ModuleUnit) {
+        let children: Vec<Node> = parent.children(&mut parent.walk()).collect();
+        let mut i = 0;
+        while i < children.len() {
+            let child = children[i];
+            i += 1;
+            if child.kind() != "namespace_definition" {
+                self.parse_declaration(child, source_code, module);
+                continue;
+            }
+
+            match child.child_by_field_name("body") {
+                // `namespace { ... }` declares its items in the global namespace
+                Some(body) if child.child_by_field_name("name").is_none() => {
+                    self.parse_declarations(body, source_code, module);
+                }
+                Some(body) => {
+                    let mut namespace = self.parse_namespace(child, source_code);
+                    self.parse_declarations(body, source_code, &mut namespace);
+                    module.submodules.push(namespace);
+                }
+                None => {
+                    // `namespace Foo;` applies to the declarations up to the next namespace
+                    let mut namespace = self.parse_namespace(child, source_code);
+                    let end = children[i..]
+                        .iter()
+                        .position(|n| n.kind() == "namespace_definition")
+                        .map_or(children.len(), |offset| i + offset);
+                    for sibling in &children[i..end] {
+                        self.parse_declaration(*sibling, source_code, &mut namespace);
+                    }
+                    let end_byte = children[end - 1].end_byte();
+                    namespace.source = Some(source_code[child.start_byte()..end_byte].to_string());
+                    module.submodules.push(namespace);
+                    i = end;
+                }
+            }
+        }
+    }
+
+    // Parse a use declaration, type or function into `module`, ignoring anything else
+    fn parse_declaration(&self, node: Node, source_code: &str, module: &mut ModuleUnit) {
+        match node.kind() {
+            "namespace_use_declaration" => {
+                if let Some(source) = get_node_text(node, source_code) {
+                    module.declares.push(DeclareStatements {
+                        source,
+                        kind: DeclareKind::Use,
+                    });
+                }
+            }
+            "class_declaration" | "enum_declaration" => {
+                module.structs.push(self.parse_class(node, source_code));
+            }
+            "interface_declaration" | "trait_declaration" => {
+                module.traits.push(self.parse_trait(node, source_code));
+            }
+            "function_definition" => {
+                module
+                    .functions
+                    .push(self.parse_function(node, source_code));
+            }
+            _ => {}
+        }
+    }
+
+    fn parse_namespace(&self, node: Node, source_code: &str) -> ModuleUnit {
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|n| get_node_text(n, source_code))
+            .unwrap_or_default();
+        ModuleUnit {
+            name,
+            // Namespaces have no access modifiers
+            visibility: Visibility::Public,
+            doc: extract_documentation(node, source_code),
+            source: get_node_text(node, source_code),
+            ..Default::default()
+        }
+    }
+
+    // Parse a class or enum. Types have no access modifiers and are always public.
+    fn parse_class(&self, node: Node, source_code: &str) -> StructUnit {
+        let body = node.child_by_field_name("body");
+        let (fields, methods) = body.map_or_else(Default::default, |body| {
+            self.parse_members(body, source_code)
+        });
+
+        let head_end = body.map_or(node.end_byte(), |b| b.start_byte());
+        StructUnit {
+            name: get_name(node, source_code),
+            head: declaration_text(node, head_end, source_code),
+            visibility: Visibility::Public,
+            doc: extract_documentation(node, source_code),
+            source: Some(declaration_source(node, source_code)),
+            attributes: get_attributes(node, source_code),
+            fields,
+            methods,
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+        }
+    }
+
+    // Parse an interface or trait, their properties and constants become associated items
+    fn parse_trait(&self, node: Node, source_code: &str) -> TraitUnit {
+        let body = node.child_by_field_name("body");
+        let (associated_items, methods) = body.map_or_else(Default::default, |body| {
+            self.parse_members(body, source_code)
+        });
+
+        let head_end = body.map_or(node.end_byte(), |b| b.start_byte());
+        TraitUnit {
+            name: get_name(node, source_code),
+            visibility: Visibility::Public,
+            doc: extract_documentation(node, source_code),
+            source: Some(declaration_source(node, source_code)),
+            attributes: get_attributes(node, source_code),
+            head: declaration_text(node, head_end, source_code),
+            associated_items,
+            is_unsafe: false,
+            methods,
+            deprecation: None,
+        }
+    }
+
+    // The properties, constants and enum cases, and the methods of a type body
+    fn parse_members(&self, body: Node, source_code: &str) -> (Vec<FieldUnit>, Vec<FunctionUnit>) {
+        let mut fields = Vec::new();
+        let mut methods = Vec::new();
+        let mut cursor = body.walk();
+        for member in body.children(&mut cursor) {
+            match member.kind() {
+                "property_declaration" => {
+                    fields.extend(parse_field(member, "property_element", source_code));
+                }
+                "const_declaration" => {
+                    fields.extend(parse_field(member, "const_element", source_code));
+                }
+                "enum_case" => fields.push(FieldUnit {
+                    name: get_name(member, source_code),
+                    doc: extract_documentation(member, source_code),
+                    attributes: get_attributes(member, source_code),
+                    source: Some(declaration_text(member, member.end_byte(), source_code)),
+                    discriminant: None,
+                    visibility: Visibility::Public,
+                }),
+                "method_declaration" => methods.push(self.parse_function(member, source_code)),
+                _ => {}
+            }
+        }
+        (fields, methods)
+    }
+
+    // Parse a function or method. Members without an access modifier are public.
+    fn parse_function(&self, node: Node, source_code: &str) -> FunctionUnit {
+        let source = Some(declaration_source(node, source_code));
+        let (signature, body) = match node.child_by_field_name("body") {
+            Some(body) => (
+                declaration_text(node, body.start_byte(), source_code),
+                get_node_text(body, source_code),
+            ),
+            // Interface and abstract methods end with `;`
+            None => (
+                declaration_text(node, node.end_byte(), source_code)
+                    .trim_end_matches(';')
+                    .to_string(),
+                None,
+            ),
+        };
+
+        FunctionUnit {
+            name: get_name(node, source_code),
+            visibility: determine_visibility(node, source_code),
+            doc: extract_documentation(node, source_code),
+            source,
+            signature: Some(signature),
+            return_type: None,
+            params: Vec::new(),
+            body,
+            attributes: get_attributes(node, source_code),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
+        }
+    }
+}
+
+// A property or constant declaration may declare several elements, e.g. `private $a, $b;`,
+// each becomes a field declared on its own. Properties are named without their `$`.
+fn parse_field(node: Node, element_kind: &str, source_code: &str) -> Vec<FieldUnit> {
+    let doc = extract_documentation(node, source_code);
+    let attributes = get_attributes(node, source_code);
+    let elements: Vec<Node> = node
+        .children(&mut node.walk())
+        .filter(|child| child.kind() == element_kind)
+        .collect();
+    // The modifiers and type shared by the elements, e.g. `private array`
+    let prefix = elements.first().map_or_else(String::new, |first| {
+        declaration_text(node, first.start_byte(), source_code)
+    });
+
+    elements
+        .iter()
+        .filter_map(|element| {
+            let name = element
+                .child_by_field_name("name")
+                .or_else(|| element.named_child(0))
+                .and_then(|name| get_node_text(name, source_code))?;
+            let element = get_node_text(*element, source_code)?;
+            Some(FieldUnit {
+                name: name.trim_start_matches('$').to_string(),
+                doc: doc.clone(),
+                attributes: attributes.clone(),
+                source: Some(format!("{} {};", prefix, element)),
+                discriminant: None,
+                visibility: Visibility::Public,
+            })
+        })
+        .collect()
+}
+
+// Helper function to get the text of a node
+fn get_node_text(node: Node, source_code: &str) -> Option<String> {
+    node.utf8_text(source_code.as_bytes())
+        .ok()
+        .map(String::from)
+}
+
+fn get_name(node: Node, source_code: &str) -> String {
+    node.child_by_field_name("name")
+        .and_then(|n| get_node_text(n, source_code))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// The `#[...]` attribute groups of a declaration
+fn get_attributes(node: Node, source_code: &str) -> Vec<String> {
+    node.children(&mut node.walk())
+        .filter(|child| child.kind() == "attribute_list")
+        .filter_map(|child| get_node_text(child, source_code))
+        .collect()
+}
+
+// Where a declaration starts after its attributes
+fn declaration_start(node: Node) -> usize {
+    node.children(&mut node.walk())
+        .find(|child| child.kind() != "attribute_list")
+        .map_or(node.start_byte(), |child| child.start_byte())
+}
+
+// The text of a declaration without its attributes up to `end`, on a single line
+fn declaration_text(node: Node, end: usize, source_code: &str) -> String {
+    let start = declaration_start(node);
+    source_code[start..end.max(start)]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The source of a declaration without its attributes, which are kept apart
+fn declaration_source(node: Node, source_code: &str) -> String {
+    let start = declaration_start(node);
+    source_code[start..node.end_byte().max(start)].to_string()
+}
+
+// Map the access modifier of a member to a visibility, members are public by default
+fn determine_visibility(node: Node, source_code: &str) -> Visibility {
+    let modifier = node
+        .children(&mut node.walk())
+        .find(|child| child.kind() == "visibility_modifier")
+        .and_then(|child| get_node_text(child, source_code));
+    match modifier.as_deref() {
+        Some("private") => Visibility::Private,
+        Some("protected") => Visibility::Protected,
+        _ => Visibility::Public,
+    }
+}
+
+// Extract the `/** ... */` PHPDoc block right before a node, without its comment markers
+fn extract_documentation(node: Node, source_code: &str) -> Option<String> {
+    let comment = node.prev_sibling().filter(|sibling| {
+        sibling.kind() == "comment" && sibling.end_position().row + 1 >= node.start_position().row
+    })?;
+    let text = get_node_text(comment, source_code)?;
+    let text = text.strip_prefix("/**")?.strip_suffix("*/")?;
+
+    let doc = text
+        .lines()
+        .map(|line| line.trim().trim_start_matches('*').trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (!doc.is_empty()).then_some(doc)
+}
+
+impl Deref for PhpParser {
+    type Target = Parser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.parser
+    }
+}
+
+impl DerefMut for PhpParser {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn parse_fixture(file_name: &str) -> Result<FileUnit> {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+            .expect("CARGO_MANIFEST_DIR should be set during tests");
+        let path = PathBuf::from(manifest_dir).join("fixtures").join(file_name);
+        let mut parser = PhpParser::try_new()?;
+        parser.parse_file(&path)
+    }
+
+    fn parse_source(source: &str) -> FileUnit {
+        let mut parser = PhpParser::try_new().expect("Failed to create PHP parser");
+        parser
+            .parse_str(source, Path::new("sample.php"))
+            .expect("Failed to parse PHP source")
+    }
+
+    #[test]
+    fn test_parse_class_with_documented_method() {
+        let file_unit = parse_source(
+            r#"<?php
+/**
+ * Represents a customer invoice.
+ */
+class Invoice
+{
+    private array $lines = [];
+
+    /**
+     * Adds a line.
+     *
+     * @param string $line The line text.
+     */
+    public function addLine(string $line): void
+    {
+        $this->lines[] = $line;
+    }
+}
+"#,
+        );
+        assert!(file_unit.parse_errors.is_empty());
+        assert_eq!(file_unit.structs.len(), 1);
+
+        let class = &file_unit.structs[0];
+        assert_eq!(class.name, "Invoice");
+        assert_eq!(class.head, "class Invoice");
+        assert_eq!(class.visibility, Visibility::Public);
+        assert_eq!(class.doc.as_deref(), Some("Represents a customer invoice."));
+
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.fields[0].name, "lines");
+        assert_eq!(
+            class.fields[0].source.as_deref(),
+            Some("private array $lines = [];")
+        );
+
+        assert_eq!(class.methods.len(), 1);
+        let method = &class.methods[0];
+        assert_eq!(method.name, "addLine");
+        assert_eq!(method.visibility, Visibility::Public);
+        assert_eq!(
+            method.signature.as_deref(),
+            Some("public function addLine(string $line): void")
+        );
+        assert_eq!(
+            method.doc.as_deref(),
+            Some("Adds a line.\n@param string $line The line text.")
+        );
+        assert!(method.body.as_deref().unwrap().contains("$this->lines[]"));
+    }
+
+    #[test]
+    fn test_access_modifiers() {
+        let file_unit = parse_source(
+            r#"<?php
+class Service
+{
+    function implicit() {}
+    private function hidden() {}
+    protected function derived() {}
+    public static function open() {}
+}
+"#,
+        );
+        let visibilities: Vec<_> = file_unit.structs[0]
+            .methods
+            .iter()
+            .map(|m| &m.visibility)
+            .collect();
+        assert_eq!(
+            visibilities,
+            [
+                &Visibility::Public,
+                &Visibility::Private,
+                &Visibility::Protected,
+                &Visibility::Public,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_php_fixture() {
+        let file_unit = parse_fixture("sample.php").expect("Failed to parse PHP file");
+        assert!(file_unit.parse_errors.is_empty());
+        assert!(file_unit.structs.is_empty());
+        assert_eq!(file_unit.modules.len(), 1);
+
+        let namespace = &file_unit.modules[0];
+        assert_eq!(namespace.name, "Acme\\Billing");
+        assert_eq!(namespace.declares.len(), 2);
+
+        let names: Vec<_> = namespace.structs.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Invoice", "Status"]);
+
+        let invoice = &namespace.structs[0];
+        assert_eq!(
+            invoice.head,
+            "abstract class Invoice extends Document implements Payable"
+        );
+        assert_eq!(invoice.attributes, ["#[Entity]"]);
+        let fields: Vec<_> = invoice.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, ["number", "lines", "notes", "total", "PREFIX"]);
+        assert_eq!(
+            invoice.fields[0].doc.as_deref(),
+            Some("The invoice number.")
+        );
+        assert_eq!(
+            invoice.fields[2].source.as_deref(),
+            Some("private array $notes = [];")
+        );
+        assert_eq!(
+            invoice.fields[4].source.as_deref(),
+            Some("const PREFIX = 'INV';")
+        );
+
+        let methods: Vec<_> = invoice.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(methods, ["addLine", "normalize", "legacy", "render"]);
+        assert_eq!(invoice.methods[1].visibility, Visibility::Private);
+        assert_eq!(
+            invoice.methods[3].signature.as_deref(),
+            Some("abstract protected function render(): string")
+        );
+        assert!(invoice.methods[3].body.is_none());
+
+        let status = &namespace.structs[1];
+        assert_eq!(status.head, "enum Status: string");
+        let cases: Vec<_> = status
+            .fields
+            .iter()
+            .filter_map(|f| f.source.as_deref())
+            .collect();
+        assert_eq!(cases, ["case Draft = 'draft';", "case Paid = 'paid';"]);
+
+        let names: Vec<_> = namespace.traits.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Payable", "HasTimestamps"]);
+        assert_eq!(namespace.traits[0].head, "interface Payable");
+        assert_eq!(
+            namespace.traits[0].methods[0].signature.as_deref(),
+            Some("public function pay(Money $amount): bool")
+        );
+        assert_eq!(namespace.traits[1].associated_items[0].name, "createdAt");
+
+        assert_eq!(namespace.functions.len(), 1);
+        let function = &namespace.functions[0];
+        assert_eq!(function.name, "invoice_number");
+        assert_eq!(function.doc.as_deref(), Some("Formats an invoice number."));
+    }
+
+    #[test]
+    fn test_format_namespace_summary() {
+        use crate::{BankStrategy, Formatter, LanguageType};
+
+        let file_unit = parse_fixture("sample.php").unwrap();
+        let summary = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Php)
+            .unwrap();
+        assert!(summary.starts_with("namespace Acme\\Billing {\n    use Acme\\Support\\Money;\n"));
+        assert!(summary.contains("    function invoice_number(int $number): string { ... }"));
+        assert!(!summary.contains("mod Acme"));
+
+        let no_tests = file_unit
+            .format(&BankStrategy::NoTests, LanguageType::Php)
+            .unwrap();
+        assert!(no_tests.starts_with("namespace Acme\\Billing {\n"));
+    }
+
+    #[test]
+    fn test_namespace_blocks() {
+        let file_unit = parse_source(
+            r#"<?php
+namespace Acme\Shipping {
+    class Parcel {}
+}
+
+namespace {
+    function helper() {}
+}
+"#,
+        );
+        assert_eq!(file_unit.modules.len(), 1);
+        assert_eq!(file_unit.modules[0].name, "Acme\\Shipping");
+        assert_eq!(file_unit.modules[0].structs[0].name, "Parcel");
+        assert_eq!(file_unit.functions[0].name, "helper");
+    }
+}
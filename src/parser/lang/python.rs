@@ -1,3 +1,4 @@
+use super::{collect_parse_errors, parse_tree, timeout_micros};
 use crate::{
     Error, FieldUnit, FileUnit, FunctionUnit, LanguageParser, ModuleUnit, PythonParser, Result,
     StructUnit, Visibility,
@@ -5,6 +6,7 @@ use crate::{
 use std::fs;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::time::Duration;
 use tree_sitter::{Node, Parser};
 
 // Helper function to get the text of a node
@@ -14,7 +16,26 @@ fn get_node_text(node: Node, source_code: &str) -> Option<String> {
         .map(String::from)
 }
 
+// The content of a string delimited by `quotes` on both ends, e.g. a `"""` docstring
+fn strip_quotes<'a>(text: &'a str, quotes: &str) -> Option<&'a str> {
+    text.strip_prefix(quotes)?.strip_suffix(quotes)
+}
+
 // Helper function to get the text of the first child node of a specific kind
+// Whether a decorator turns a method into a property, e.g. `@property`
+fn is_property_decorator(decorator: &str) -> bool {
+    matches!(
+        decorator.trim(),
+        "@property" | "@cached_property" | "@functools.cached_property"
+    )
+}
+
+// Whether a decorator defines the setter or deleter of a property, e.g. `@name.setter`
+fn is_accessor_decorator(decorator: &str) -> bool {
+    let decorator = decorator.trim();
+    decorator.ends_with(".setter") || decorator.ends_with(".deleter")
+}
+
 fn get_child_node_text<'a>(node: Node<'a>, kind: &str, source_code: &'a str) -> Option<String> {
     node.children(&mut node.walk())
         .find(|child| child.kind() == kind)
@@ -96,12 +117,12 @@ impl PythonParser {
     fn clean_docstring(&self, node: Node, source_code: &str) -> Option<String> {
         let doc = get_node_text(node, source_code)?;
         // Clean up the docstring - handle both single and triple quotes
-        let doc = if doc.starts_with("\"\"\"") && doc.ends_with("\"\"\"") {
+        let doc = if let Some(inner) = strip_quotes(&doc, "\"\"\"") {
             // Handle triple quotes
-            doc[3..doc.len() - 3].trim()
-        } else if doc.starts_with("'''") && doc.ends_with("'''") {
+            inner.trim()
+        } else if let Some(inner) = strip_quotes(&doc, "'''") {
             // Handle triple single quotes
-            doc[3..doc.len() - 3].trim()
+            inner.trim()
         } else {
             // Handle single quotes
             doc.trim_matches('"').trim_matches('\'').trim()
@@ -150,10 +171,17 @@ impl PythonParser {
         let mut signature = None;
         let mut body = None;
 
-        if let Some(src) = &source {
-            if let Some(body_start_idx) = src.find(':') {
-                signature = Some(src[0..body_start_idx].trim().to_string());
-                body = Some(src[body_start_idx + 1..].trim().to_string());
+        // Split at the `:` before the body node, an earlier one may start a parameter
+        // annotation. The body keeps the `:` and the indentation of its first line.
+        if let Some(body_node) = function_node.child_by_field_name("body") {
+            let head = &source_code[function_node.start_byte()..body_node.start_byte()];
+            if let Some(colon) = head.rfind(':') {
+                signature = Some(head[..colon].trim().to_string());
+                body = Some(
+                    source_code[function_node.start_byte() + colon..function_node.end_byte()]
+                        .trim_end()
+                        .to_string(),
+                );
             }
         }
 
@@ -163,8 +191,16 @@ impl PythonParser {
             doc: documentation,
             source,
             signature,
+            return_type: None,
+            params: Vec::new(),
             body,
             attributes,
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
+            is_const: false,
+            is_unsafe: false,
+            is_associated_fn: false,
         })
     }
 
@@ -193,8 +229,10 @@ impl PythonParser {
         // TODO: parse class head
         let head = format!("class {}", name);
 
-        // Extract methods from class body
+        // Extract methods from class body, `@property` getters are accessed like
+        // attributes and are listed with the fields instead
         let mut methods = Vec::new();
+        let mut properties = Vec::new();
         let mut cursor = class_node.walk();
         for child in class_node.children(&mut cursor) {
             if child.kind() == "block" {
@@ -202,7 +240,19 @@ impl PythonParser {
                 for method_node in child.children(&mut block_cursor) {
                     match method_node.kind() {
                         "function_definition" | "decorated_definition" => {
-                            if let Ok(method) = self.parse_function(method_node, source_code) {
+                            let Ok(method) = self.parse_function(method_node, source_code) else {
+                                continue;
+                            };
+                            if method.attributes.iter().any(|d| is_property_decorator(d)) {
+                                properties.push(FieldUnit {
+                                    name: method.name,
+                                    doc: method.doc,
+                                    attributes: method.attributes,
+                                    source: method.signature,
+                                    discriminant: None,
+                                    visibility: Visibility::Public,
+                                });
+                            } else if !method.attributes.iter().any(|d| is_accessor_decorator(d)) {
                                 methods.push(method);
                             }
                         }
@@ -221,6 +271,9 @@ impl PythonParser {
             attributes,
             fields: Vec::new(),
             methods: methods.clone(),
+            deprecation: None,
+            is_must_use: false,
+            must_use_note: None,
         };
 
         // Extract fields from __init__ method if present
@@ -244,6 +297,8 @@ impl PythonParser {
                                     doc: None,
                                     attributes: Vec::new(),
                                     source: Some(trimmed_line.to_string()),
+                                    discriminant: None,
+                                    visibility: Visibility::Public,
                                 };
                                 // Avoid duplicates if field is assigned multiple times
                                 if !class_unit.fields.iter().any(|f| f.name == field.name) {
@@ -256,6 +311,12 @@ impl PythonParser {
             }
         }
 
+        for property in properties {
+            if !class_unit.fields.iter().any(|f| f.name == property.name) {
+                class_unit.fields.push(property);
+            }
+        }
+
         Ok(class_unit)
     }
 
@@ -284,6 +345,10 @@ impl PythonParser {
             traits: Vec::new(),
             impls: Vec::new(),
             submodules: Vec::new(),
+            macros: Vec::new(),
+            consts: Vec::new(),
+            type_aliases: Vec::new(),
+            deprecation: None,
         })
     }
 }
@@ -291,13 +356,23 @@ impl PythonParser {
 impl LanguageParser for PythonParser {
     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
         let source_code = fs::read_to_string(file_path).map_err(Error::Io)?;
-        let tree = self
-            .parse(source_code.as_bytes(), None)
-            .ok_or_else(|| Error::TreeSitter("Failed to parse Python file".to_string()))?;
+        self.parse_str(&source_code, file_path)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.set_timeout_micros(timeout_micros(timeout));
+    }
+
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
+        let tree = parse_tree(self, source_code.as_bytes(), || {
+            Error::TreeSitter("Failed to parse Python file".to_string())
+        })?;
 
         let mut file_unit = FileUnit {
             path: file_path.to_path_buf(),
-            source: Some(source_code.clone()),
+            source: Some(source_code.to_string()),
+            parse_errors: collect_parse_errors(tree.root_node(), source_code),
+            omitted_items: 0,
             doc: None,
             declares: Vec::new(),
             modules: Vec::new(),
@@ -305,6 +380,9 @@ impl LanguageParser for PythonParser {
             structs: Vec::new(),
             traits: Vec::new(),
             impls: Vec::new(),
+            macros: Vec::new(),
+            consts: Vec::new(),
+            type_aliases: Vec::new(),
         };
 
         let root_node = tree.root_node();
@@ -320,7 +398,7 @@ impl LanguageParser for PythonParser {
                         .children(&mut first_expr.walk())
                         .find(|c| c.kind() == "string")
                     {
-                        if let Some(doc) = get_node_text(string, &source_code) {
+                        if let Some(doc) = get_node_text(string, source_code) {
                             // Clean up the docstring - handle both single and triple quotes
                             let doc = doc
                                 .trim_start_matches(r#"""""#)
@@ -344,7 +422,7 @@ impl LanguageParser for PythonParser {
             let mut cursor = root_node.walk();
             for node in root_node.children(&mut cursor) {
                 if node.kind() == "import_statement" || node.kind() == "import_from_statement" {
-                    if let Some(import_text) = get_node_text(node, &source_code) {
+                    if let Some(import_text) = get_node_text(node, source_code) {
                         file_unit.declares.push(crate::DeclareStatements {
                             source: import_text,
                             kind: crate::DeclareKind::Import,
@@ -359,11 +437,11 @@ impl LanguageParser for PythonParser {
         for node in root_node.children(&mut cursor) {
             match node.kind() {
                 "function_definition" => {
-                    let func = self.parse_function(node, &source_code)?;
+                    let func = self.parse_function(node, source_code)?;
                     file_unit.functions.push(func);
                 }
                 "class_definition" => {
-                    let class = self.parse_class(node, &source_code)?;
+                    let class = self.parse_class(node, source_code)?;
                     file_unit.structs.push(class);
                 }
                 "decorated_definition" => {
@@ -374,11 +452,11 @@ impl LanguageParser for PythonParser {
                     }) {
                         match def_node.kind() {
                             "function_definition" => {
-                                let func = self.parse_function(node, &source_code)?;
+                                let func = self.parse_function(node, source_code)?;
                                 file_unit.functions.push(func);
                             }
                             "class_definition" => {
-                                let class = self.parse_class(node, &source_code)?;
+                                let class = self.parse_class(node, source_code)?;
                                 file_unit.structs.push(class);
                             }
                             _ => {}
@@ -410,6 +488,7 @@ impl DerefMut for PythonParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{BankStrategy, Formatter, LanguageType};
     use std::path::PathBuf;
 
     fn create_test_file(content: &str) -> Result<(tempfile::TempDir, PathBuf)> {
@@ -585,4 +664,49 @@ class MyClass:
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_property() -> Result<()> {
+        let content = r#"
+class Temperature:
+    def __init__(self, celsius: float):
+        self._celsius = celsius
+
+    @property
+    def celsius(self) -> float:
+        """The temperature in degrees Celsius."""
+        return self._celsius
+
+    @celsius.setter
+    def celsius(self, value: float):
+        self._celsius = value
+
+    def to_fahrenheit(self) -> float:
+        return self._celsius * 9 / 5 + 32
+"#;
+        let (_dir, file_path) = create_test_file(content)?;
+        let mut parser = PythonParser::try_new()?;
+        let file_unit = parser.parse_file(&file_path)?;
+
+        let class = &file_unit.structs[0];
+        let names: Vec<&str> = class.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["__init__", "to_fahrenheit"]);
+
+        let property = class.fields.iter().find(|f| f.name == "celsius").unwrap();
+        assert_eq!(property.attributes, vec!["@property"]);
+        assert_eq!(
+            property.doc.as_deref(),
+            Some("The temperature in degrees Celsius.")
+        );
+        assert_eq!(
+            property.source.as_deref(),
+            Some("def celsius(self) -> float")
+        );
+
+        let formatted = class.format(&BankStrategy::Summary, LanguageType::Python)?;
+        assert!(formatted.contains("    @property\n    def celsius(self) -> float\n"));
+        assert!(formatted.contains("def to_fahrenheit(self) -> float"));
+
+        Ok(())
+    }
 }
@@ -1,12 +1,29 @@
+use super::{collect_parse_errors, parse_tree, timeout_micros};
+use crate::parser::attributes::parse_attribute;
 use crate::{
-    Error, FieldUnit, FileUnit, FunctionUnit, ImplUnit, LanguageParser, LanguageType, ModuleUnit,
-    Result, RustParser, StructUnit, TraitUnit, Visibility,
+    ConstUnit, Deprecation, Error, FieldUnit, FileUnit, FunctionUnit, ImplUnit, LanguageParser,
+    LanguageType, MacroUnit, ModuleUnit, ParamUnit, Result, RustParser, StructUnit, TraitUnit,
+    TypeAliasUnit, Visibility,
 };
+use regex::Regex;
 use std::fs;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::sync::LazyLock;
+use std::time::Duration;
 use tree_sitter::{Node, Parser};
 
+// Matches the head of a macros 2.0 definition, e.g. `pub(crate) macro name`, capturing
+// the `macro` keyword so that it can be checked against the syntax tree
+static MACRO_2_0_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*((?:pub(?:\([^)]*\))?[ \t]+)?(macro)[ \t]+([A-Za-z_][A-Za-z0-9_]*))")
+        .unwrap()
+});
+
+/// Function-like macros whose invocations define public items, recorded as
+/// `DeclareKind::Other("macro_item")` declarations by [`RustParser`].
+pub const DEFAULT_ITEM_MACROS: &[&str] = &["bitflags", "lazy_static"];
+
 // Helper function to extract attributes looking backwards from a node
 fn extract_attributes(node: Node, source_code: &str) -> Vec<String> {
     let mut attributes = Vec::new();
@@ -34,6 +51,14 @@ fn extract_attributes(node: Node, source_code: &str) -> Vec<String> {
     attributes
 }
 
+// The name of an invoked macro without its path, e.g. `thread_local` for `std::thread_local!`
+fn macro_name(node: Node, source_code: &str) -> Option<String> {
+    let name = node
+        .child_by_field_name("macro")
+        .and_then(|macro_node| get_node_text(macro_node, source_code))?;
+    Some(name.rsplit("::").next().unwrap_or(&name).to_string())
+}
+
 // Helper function to get the text of the first child node of a specific kind
 fn get_child_node_text<'a>(node: Node<'a>, kind: &str, source_code: &'a str) -> Option<String> {
     // First try to find it directly as a child
@@ -80,6 +105,289 @@ fn get_node_text(node: Node, source_code: &str) -> Option<String> {
         .map(String::from)
 }
 
+// Helper function to collect the `//!` and `/*! */` docs of a file. They are read from the
+// comments and inner attributes heading the file, in any order, e.g. after `#![deny(warnings)]`
+fn extract_inner_documentation(root_node: Node, source_code: &str) -> Option<String> {
+    let mut doc_comments = Vec::new();
+    for node in root_node.children(&mut root_node.walk()) {
+        if !matches!(
+            node.kind(),
+            "line_comment" | "block_comment" | "inner_attribute_item"
+        ) {
+            break;
+        }
+        let Some(comment) = get_node_text(node, source_code) else {
+            continue;
+        };
+        if let Some(line) = comment.strip_prefix("//!") {
+            doc_comments.push(line.trim().to_string());
+        } else if let Some(block) = comment.strip_prefix("/*!") {
+            doc_comments.extend(block_doc_lines(block));
+        }
+    }
+
+    if doc_comments.is_empty() {
+        None
+    } else {
+        Some(doc_comments.join("\n"))
+    }
+}
+
+// The non-empty lines of a block doc comment following its `/**` or `/*!` opening, without
+// the closing `*/` and the leading `*` of each line. Text may start on the opening line.
+fn block_doc_lines(block: &str) -> Vec<String> {
+    let block = block.strip_suffix("*/").unwrap_or(block);
+    block
+        .lines()
+        .map(|line| line.trim().trim_start_matches('*').trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+// Helper function to read `#[deprecated]`, `#[deprecated = "note"]` or
+// `#[deprecated(since = "..", note = "note")]` from an item's attributes
+fn deprecation_from_attributes(attributes: &[String]) -> Option<Deprecation> {
+    let attr = attributes
+        .iter()
+        .filter_map(|attr| parse_attribute(attr))
+        .find(|attr| attr.path == "deprecated")?;
+    let mut deprecation = Deprecation::default();
+    match attr.args.as_slice() {
+        // `#[deprecated = "note"]`: the whole literal is the note, even with a `=` in it
+        [value] if value.trim_start().starts_with('"') => {
+            deprecation.note = string_literal(value);
+        }
+        args => {
+            for (key, value) in args.iter().filter_map(|arg| arg.split_once('=')) {
+                match key.trim() {
+                    "since" => deprecation.since = string_literal(value),
+                    "note" => deprecation.note = string_literal(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Some(deprecation)
+}
+
+// The content of a string literal argument, e.g. `use "run"` for `"use \"run\""`
+fn string_literal(value: &str) -> Option<String> {
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some(value.replace("\\\"", "\""))
+}
+
+// Helper function to read `#[must_use]` or `#[must_use = "note"]` from an item's attributes
+fn must_use_from_attributes(attributes: &[String]) -> (bool, Option<String>) {
+    let Some(attr) = attributes
+        .iter()
+        .filter_map(|attr| parse_attribute(attr))
+        .find(|attr| attr.path == "must_use")
+    else {
+        return (false, None);
+    };
+    let note = attr.args.first().and_then(|note| string_literal(note));
+    (true, note)
+}
+
+// Helper function to extract the parameters of a function. Receivers are typed as
+// written with `Self`, e.g. `&'a mut Self` for `&'a mut self` and `Self` for `mut self`,
+// and typed receivers such as `self: Box<Self>` count as receivers too
+fn params(node: Node, source_code: &str) -> Vec<ParamUnit> {
+    let Some(parameters) = node.child_by_field_name("parameters") else {
+        return Vec::new();
+    };
+    parameters
+        .named_children(&mut parameters.walk())
+        .filter_map(|parameter| match parameter.kind() {
+            "self_parameter" => {
+                let text = get_node_text(parameter, source_code)?;
+                let receiver = text.strip_prefix("mut").unwrap_or(&text).trim();
+                Some(ParamUnit {
+                    name: Some("self".to_string()),
+                    ty: format!("{}Self", receiver.strip_suffix("self")?),
+                    is_self: true,
+                })
+            }
+            "parameter" => {
+                // The `_` of `_: i32` is an anonymous node, not a pattern
+                let name = parameter
+                    .child_by_field_name("pattern")
+                    .and_then(|pattern| get_node_text(pattern, source_code))
+                    .filter(|name| name != "_");
+                let ty = get_node_text(parameter.child_by_field_name("type")?, source_code)?;
+                Some(ParamUnit {
+                    is_self: name.as_deref() == Some("self"),
+                    name,
+                    ty,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Helper function to map a visibility modifier such as `pub(crate)` to a Visibility
+// `pub(self)` and `pub(in self)` are private, malformed modifiers such as `pub()` are
+// treated as private as well
+pub(crate) fn visibility_from_modifier(vis_text: &str) -> Visibility {
+    let vis_text = vis_text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if vis_text == "pub" {
+        return Visibility::Public;
+    }
+    if vis_text == "crate" {
+        return Visibility::Crate;
+    }
+    let Some(path) = vis_text
+        .strip_prefix("pub")
+        .map(str::trim_start)
+        .and_then(|rest| rest.strip_prefix('('))
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map(str::trim)
+    else {
+        return Visibility::Private;
+    };
+    match path.strip_prefix("in ").map_or(path, str::trim) {
+        "crate" => Visibility::Crate,
+        "self" | "" => Visibility::Private,
+        _ => Visibility::Restricted(format!("pub({})", path)),
+    }
+}
+
+// tree-sitter-rust does not understand macros 2.0 (`pub macro name(..) { .. }`) and its
+// error recovery mangles the surrounding items. Extract those definitions and return a copy
+// of the source with them (and their docs/attributes) blanked out, keeping byte offsets
+// intact so the remaining items can be parsed as usual. Only the definitions whose `macro`
+// keyword is a token of `root_node`, the tree of the unmasked source, are extracted, so
+// that `macro` lines in string literals and comments are left alone.
+fn extract_macros_2_0(source_code: &str, root_node: Node) -> (String, Vec<(usize, MacroUnit)>) {
+    let mut masked = source_code.as_bytes().to_vec();
+    let mut macros = Vec::new();
+
+    let mut keywords = Vec::new();
+    collect_macro_keywords(root_node, source_code, &mut keywords);
+    for caps in MACRO_2_0_REGEX.captures_iter(source_code) {
+        let (Some(head_match), Some(keyword), Some(name_match)) =
+            (caps.get(1), caps.get(2), caps.get(3))
+        else {
+            continue;
+        };
+        if !keywords.contains(&keyword.start()) {
+            continue;
+        }
+        let start = head_match.start();
+        let Some(end) = find_block_end(source_code, name_match.end()) else {
+            continue;
+        };
+        let source = &source_code[start..end];
+        let head = source
+            .split('{')
+            .next()
+            .unwrap_or(source)
+            .trim()
+            .to_string();
+        let visibility = head
+            .split_whitespace()
+            .next()
+            .map(visibility_from_modifier)
+            .unwrap_or(Visibility::Private);
+
+        // Collect the doc comments and attributes on the lines right above the macro
+        let mut doc_lines = Vec::new();
+        let mut attributes = Vec::new();
+        let mut item_start = source_code[..start].rfind('\n').map_or(0, |i| i + 1);
+        while item_start > 0 {
+            let line_start = source_code[..item_start - 1]
+                .rfind('\n')
+                .map_or(0, |i| i + 1);
+            let line = source_code[line_start..item_start - 1].trim();
+            if let Some(doc) = line.strip_prefix("///") {
+                doc_lines.insert(0, doc.trim().to_string());
+            } else if line.starts_with("#[") {
+                attributes.insert(0, line.to_string());
+            } else {
+                break;
+            }
+            item_start = line_start;
+        }
+
+        for byte in &mut masked[item_start..end] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+
+        let deprecation = deprecation_from_attributes(&attributes);
+        macros.push((
+            start,
+            MacroUnit {
+                name: name_match.as_str().to_string(),
+                attributes,
+                visibility,
+                doc: (!doc_lines.is_empty()).then(|| doc_lines.join("\n")),
+                head,
+                is_macro_rules: false,
+                source: Some(source.to_string()),
+                deprecation,
+            },
+        ));
+    }
+
+    // Only ASCII bytes were replaced, so the masked source is still valid UTF-8
+    let masked = String::from_utf8(masked).unwrap_or_else(|_| source_code.to_string());
+    (masked, macros)
+}
+
+// Collect the offsets of the `macro` keywords below `node`. `macro` is reserved, the
+// grammar only sees it as an identifier where a macros 2.0 definition starts
+fn collect_macro_keywords(node: Node, source_code: &str, keywords: &mut Vec<usize>) {
+    if node.kind() == "identifier" && get_node_text(node, source_code).as_deref() == Some("macro") {
+        keywords.push(node.start_byte());
+    }
+    for child in node.children(&mut node.walk()) {
+        collect_macro_keywords(child, source_code, keywords);
+    }
+}
+
+// Find the end (exclusive) of the first brace-delimited block starting at or after `from`
+fn find_block_end(source_code: &str, from: usize) -> Option<usize> {
+    let open = from + source_code[from..].find('{')?;
+    let mut depth = 0usize;
+    for (offset, ch) in source_code[open..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + offset + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Collect the macros 2.0 defined directly in `container`, i.e. not inside one of its
+// nested `mod` items
+fn macros_in_scope(container: Node, macros: &[(usize, MacroUnit)]) -> Vec<MacroUnit> {
+    // The root node may not span trailing whitespace, where a masked macro could sit
+    let scope = match container.parent() {
+        Some(_) => container.byte_range(),
+        None => 0..usize::MAX,
+    };
+    let nested: Vec<std::ops::Range<usize>> = container
+        .children(&mut container.walk())
+        .filter(|child| child.kind() == "mod_item")
+        .map(|child| child.byte_range())
+        .collect();
+    macros
+        .iter()
+        .filter(|(offset, _)| scope.contains(offset))
+        .filter(|(offset, _)| !nested.iter().any(|range| range.contains(offset)))
+        .map(|(_, macro_unit)| macro_unit.clone())
+        .collect()
+}
+
 impl RustParser {
     pub fn try_new() -> Result<Self> {
         let mut parser = Parser::new();
@@ -87,7 +395,33 @@ impl RustParser {
         parser
             .set_language(&language.into())
             .map_err(|e| Error::TreeSitter(e.to_string()))?;
-        Ok(Self { parser })
+        Ok(Self {
+            parser,
+            item_macros: DEFAULT_ITEM_MACROS.iter().map(|m| m.to_string()).collect(),
+        })
+    }
+
+    /// Replace the names of the function-like macros whose invocations define items,
+    /// see [`DEFAULT_ITEM_MACROS`] for the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{DeclareKind, LanguageParser, RustParser};
+    /// use std::path::Path;
+    ///
+    /// let mut parser = RustParser::try_new()?.with_item_macros(["newtype"]);
+    /// let file_unit = parser.parse_str("newtype! { pub struct Meters(f64); }", Path::new("lib.rs"))?;
+    /// assert_eq!(file_unit.declares[0].kind, DeclareKind::Other("macro_item".to_string()));
+    /// # Ok::<(), codebank::Error>(())
+    /// ```
+    pub fn with_item_macros<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.item_macros = names.into_iter().map(Into::into).collect();
+        self
     }
 
     // Helper function to parse the head (declaration line) of an item
@@ -99,12 +433,20 @@ impl RustParser {
         visibility: &Visibility,
         name: &str,
     ) -> String {
-        if let Some(src) = get_node_text(node, source_code) {
-            if let Some(body_start_idx) = src.find('{') {
+        // Slice up to the braced body, braces may come before it in const generic defaults
+        let body = node
+            .child_by_field_name("body")
+            .filter(|body| body.kind() != "ordered_field_declaration_list");
+        if let Some(body) = body {
+            source_code[node.start_byte()..body.start_byte()]
+                .trim()
+                .to_string()
+        } else if let Some(src) = get_node_text(node, source_code) {
+            if src.trim_end().ends_with(';') {
+                // Handle unit and tuple items like `struct Unit;` or `struct Pair(u8, [u8; 2]);`
+                src.trim().to_string()
+            } else if let Some(body_start_idx) = src.find('{') {
                 src[0..body_start_idx].trim().to_string()
-            } else if let Some(semi_idx) = src.find(';') {
-                // Handle unit items like `struct Unit;`
-                src[0..=semi_idx].trim().to_string()
             } else {
                 // Fallback, might occur for malformed code or items without bodies/semicolons
                 format!(
@@ -141,20 +483,13 @@ impl RustParser {
                     } // else: it's a non-doc line comment, ignore and continue searching backward
                 }
             } else if kind == "block_comment" {
-                if let Some(comment) = get_node_text(prev, source_code) {
-                    if comment.starts_with("/**") {
-                        let lines: Vec<&str> = comment.lines().collect();
-                        if lines.len() > 1 {
-                            // Insert lines in reverse order to maintain original order
-                            for line in lines[1..lines.len() - 1].iter().rev() {
-                                let cleaned = line.trim_start_matches('*').trim().to_string();
-                                if !cleaned.is_empty() {
-                                    doc_comments.insert(0, cleaned);
-                                }
-                            }
-                        }
-                    } // else: it's a non-doc block comment, ignore and continue searching backward
-                }
+                if let Some(block) = get_node_text(prev, source_code)
+                    .as_deref()
+                    .and_then(|comment| comment.strip_prefix("/**"))
+                    .filter(|block| !block.starts_with(['*', '/']))
+                {
+                    doc_comments.splice(0..0, block_doc_lines(block));
+                } // else: it's a non-doc block comment, ignore and continue searching backward
             } else if kind != "attribute_item" {
                 // Stop if it's not a comment or attribute
                 break;
@@ -176,13 +511,12 @@ impl RustParser {
             .children(&mut node.walk())
             .find(|child| child.kind() == "visibility_modifier")
         {
+            // The grammar recovers from `pub()` as `pub` followed by an error
+            let malformed = vis_mod.next_sibling().is_some_and(|next| next.is_error());
             if let Some(vis_text) = get_node_text(vis_mod, source_code) {
-                return match vis_text.as_str() {
-                    "pub" => Visibility::Public,
-                    "pub(crate)" => Visibility::Crate,
-                    s if s.starts_with("pub(") => Visibility::Restricted(s.to_string()),
-                    _ => Visibility::Private, // Should not happen based on grammar?
-                };
+                if !malformed {
+                    return visibility_from_modifier(&vis_text);
+                }
             }
         }
         Visibility::Private
@@ -200,28 +534,186 @@ impl RustParser {
         let mut signature = None;
         let mut body = None;
 
-        if let Some(src) = &source {
-            if let Some(body_start_idx) = src.find('{') {
-                signature = Some(src[0..body_start_idx].trim().to_string());
-                body = Some(src[body_start_idx..].trim().to_string());
-            } else if let Some(sig_end_idx) = src.find(';') {
-                signature = Some(src[0..=sig_end_idx].trim().to_string());
+        // Split at the body node rather than the first brace, which may belong to a
+        // const generic argument such as `-> Buffer<{ 2 * N }>`
+        if let Some(body_node) = node.child_by_field_name("body") {
+            signature = Some(
+                source_code[node.start_byte()..body_node.start_byte()]
+                    .trim()
+                    .to_string(),
+            );
+            body = get_node_text(body_node, source_code).map(|text| text.trim().to_string());
+        } else if let Some(src) = source.as_deref().map(str::trim) {
+            // Declarations end with their `;`, e.g. `fn header() -> [u8; 4];`
+            if src.ends_with(';') {
+                signature = Some(src.to_string());
             }
         }
 
+        // `const` and `unsafe` are anonymous tokens among the `function_modifiers`
+        let has_modifier = |kind: &str| {
+            node.children(&mut node.walk())
+                .filter(|child| child.kind() == "function_modifiers")
+                .any(|modifiers| {
+                    modifiers
+                        .children(&mut modifiers.walk())
+                        .any(|modifier| modifier.kind() == kind)
+                })
+        };
+        let is_const = has_modifier("const");
+        let is_unsafe = has_modifier("unsafe");
+        let return_type = node
+            .child_by_field_name("return_type")
+            .and_then(|return_type| get_node_text(return_type, source_code));
+
+        // Functions of impl and trait bodies without a receiver are associated functions
+        let in_type_body = node
+            .parent()
+            .filter(|parent| parent.kind() == "declaration_list")
+            .and_then(|list| list.parent())
+            .is_some_and(|owner| matches!(owner.kind(), "impl_item" | "trait_item"));
+        let params = params(node, source_code);
+        let is_associated_fn = in_type_body && !params.iter().any(|param| param.is_self);
+
+        let deprecation = deprecation_from_attributes(&attributes);
+        let (is_must_use, must_use_note) = must_use_from_attributes(&attributes);
         Ok(FunctionUnit {
             name,
             visibility,
             doc: documentation,
             source,
             signature,
+            return_type,
+            params,
             body,
             attributes,
+            deprecation,
+            is_must_use,
+            must_use_note,
+            is_const,
+            is_unsafe,
+            is_associated_fn,
+        })
+    }
+
+    // Parse a `macro_rules!` definition; it is public only when `#[macro_export]`ed
+    fn parse_macro_rules(&self, node: Node, source_code: &str) -> Result<MacroUnit> {
+        let documentation = self.extract_documentation(node, source_code);
+        let attributes = extract_attributes(node, source_code);
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|name| get_node_text(name, source_code))
+            .unwrap_or_else(|| "unknown".to_string());
+        let visibility = if attributes.iter().any(|attr| attr.contains("macro_export")) {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        };
+
+        let deprecation = deprecation_from_attributes(&attributes);
+        Ok(MacroUnit {
+            head: format!("macro_rules! {}", name),
+            name,
+            attributes,
+            visibility,
+            doc: documentation,
+            is_macro_rules: true,
+            source: get_node_text(node, source_code),
+            deprecation,
+        })
+    }
+
+    // Parse a `const` or `static` item
+    fn parse_const(&self, node: Node, source_code: &str) -> Result<ConstUnit> {
+        let is_mut = node
+            .children(&mut node.walk())
+            .any(|child| child.kind() == "mutable_specifier");
+        let field_text = |field: &str| {
+            node.child_by_field_name(field)
+                .and_then(|child| get_node_text(child, source_code))
+        };
+        let attributes = extract_attributes(node, source_code);
+        let deprecation = deprecation_from_attributes(&attributes);
+        Ok(ConstUnit {
+            name: field_text("name").unwrap_or_else(|| "unknown".to_string()),
+            attributes,
+            visibility: self.determine_visibility(node, source_code),
+            doc: self.extract_documentation(node, source_code),
+            deprecation,
+            is_static: node.kind() == "static_item",
+            is_mut,
+            is_thread_local: false,
+            type_annotation: field_text("type"),
+            value: field_text("value"),
+            source: get_node_text(node, source_code),
+        })
+    }
+
+    // Parse the statics declared by a `thread_local!` invocation. Its body is parsed on its
+    // own, as the grammar leaves it as a token tree
+    fn parse_thread_local(&self, node: Node, source_code: &str) -> Vec<ConstUnit> {
+        let Some(body) = node
+            .children(&mut node.walk())
+            .find(|child| child.kind() == "token_tree")
+            .and_then(|token_tree| get_node_text(token_tree, source_code))
+        else {
+            return Vec::new();
+        };
+        // Without its delimiters, and with the `;` the last static may leave out
+        let mut body = body[1..body.len().saturating_sub(1).max(1)]
+            .trim_end()
+            .to_string();
+        if !body.ends_with(';') {
+            body.push(';');
+        }
+
+        let mut parser = Parser::new();
+        let Some(tree) = parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .ok()
+            .and_then(|_| parser.parse(&body, None))
+        else {
+            return Vec::new();
+        };
+        let root_node = tree.root_node();
+        root_node
+            .children(&mut root_node.walk())
+            .filter(|child| child.kind() == "static_item")
+            .filter_map(|child| self.parse_const(child, &body).ok())
+            .map(|const_unit| ConstUnit {
+                is_thread_local: true,
+                ..const_unit
+            })
+            .collect()
+    }
+
+    // Parse a `type` alias, the where clause is only kept in its source
+    fn parse_type_alias(&self, node: Node, source_code: &str) -> Result<TypeAliasUnit> {
+        let field_text = |field: &str| {
+            node.child_by_field_name(field)
+                .and_then(|child| get_node_text(child, source_code))
+        };
+        let attributes = extract_attributes(node, source_code);
+        let deprecation = deprecation_from_attributes(&attributes);
+        Ok(TypeAliasUnit {
+            name: field_text("name").unwrap_or_else(|| "unknown".to_string()),
+            attributes,
+            visibility: self.determine_visibility(node, source_code),
+            doc: self.extract_documentation(node, source_code),
+            deprecation,
+            generics: field_text("type_parameters"),
+            aliased_type: field_text("type"),
+            source: get_node_text(node, source_code),
         })
     }
 
     // Parse module and extract its details
-    fn parse_module(&self, node: Node, source_code: &str) -> Result<ModuleUnit> {
+    fn parse_module(
+        &self,
+        node: Node,
+        source_code: &str,
+        macros: &[(usize, MacroUnit)],
+    ) -> Result<ModuleUnit> {
         let name = get_child_node_text(node, "identifier", source_code)
             .unwrap_or_else(|| "unknown".to_string());
         let visibility = self.determine_visibility(node, source_code);
@@ -229,10 +721,12 @@ impl RustParser {
         let attributes = extract_attributes(node, source_code);
         let source = get_node_text(node, source_code);
 
+        let deprecation = deprecation_from_attributes(&attributes);
         let mut module = ModuleUnit {
             name,
             visibility,
             doc: document,
+            deprecation,
             source,
             attributes,
             ..Default::default()
@@ -273,10 +767,15 @@ impl RustParser {
                         }
                     }
                     "mod_item" => {
-                        if let Ok(submodule) = self.parse_module(item, source_code) {
+                        if let Ok(submodule) = self.parse_module(item, source_code, macros) {
                             module.submodules.push(submodule);
                         }
                     }
+                    "macro_definition" => {
+                        if let Ok(macro_unit) = self.parse_macro_rules(item, source_code) {
+                            module.macros.push(macro_unit);
+                        }
+                    }
                     "use_declaration" => {
                         if let Some(declare_text) = get_node_text(item, source_code) {
                             module.declares.push(crate::DeclareStatements {
@@ -285,16 +784,63 @@ impl RustParser {
                             });
                         }
                     }
+                    "type_item" => {
+                        if let Ok(type_alias) = self.parse_type_alias(item, source_code) {
+                            module.type_aliases.push(type_alias);
+                        }
+                    }
+                    "const_item" | "static_item" => {
+                        if let Ok(const_unit) = self.parse_const(item, source_code) {
+                            module.consts.push(const_unit);
+                        }
+                    }
+                    "macro_invocation"
+                        if macro_name(item, source_code).as_deref() == Some("thread_local") =>
+                    {
+                        module
+                            .consts
+                            .extend(self.parse_thread_local(item, source_code));
+                    }
+                    "macro_invocation" => {
+                        if let Some(declare) = self.parse_special_item(item, source_code) {
+                            module.declares.push(declare);
+                        }
+                    }
                     _ => {
                         // Ignore other kinds of items for now
                     }
                 }
             }
+            module.macros.extend(macros_in_scope(block_node, macros));
         }
 
         Ok(module)
     }
 
+    // Parse invocations of item defining macros (e.g. `bitflags!`) into declarations, as
+    // they are part of the API but don't map to any unit. Other macro invocations are
+    // ignored.
+    fn parse_special_item(
+        &self,
+        node: Node,
+        source_code: &str,
+    ) -> Option<crate::DeclareStatements> {
+        let name = macro_name(node, source_code)?;
+        if !self.item_macros.contains(&name) {
+            return None;
+        }
+
+        let mut source = get_node_text(node, source_code)?;
+        // `name!(...);` leaves its semicolon to a separate empty statement
+        if !source.ends_with('}') {
+            source.push(';');
+        }
+        Some(crate::DeclareStatements {
+            source,
+            kind: crate::DeclareKind::Other("macro_item".to_string()),
+        })
+    }
+
     // Parse an enum as a struct (for simplified model)
     fn parse_enum_as_struct(&self, node: Node, source_code: &str) -> Result<StructUnit> {
         let name = get_child_node_text(node, "identifier", source_code)
@@ -336,11 +882,17 @@ impl RustParser {
                         doc: variant_documentation,
                         attributes: variant_attributes,
                         source: final_variant_source, // Use the trimmed source
+                        discriminant: variant_node
+                            .child_by_field_name("value")
+                            .and_then(|value| get_node_text(value, source_code)),
+                        visibility: Visibility::Public,
                     });
                 }
             }
         }
 
+        let deprecation = deprecation_from_attributes(&attributes);
+        let (is_must_use, must_use_note) = must_use_from_attributes(&attributes);
         let struct_unit = StructUnit {
             name,
             head,
@@ -350,6 +902,9 @@ impl RustParser {
             attributes,
             fields, // Populated with variants
             methods: Vec::new(),
+            deprecation,
+            is_must_use,
+            must_use_note,
         };
 
         Ok(struct_unit)
@@ -388,11 +943,15 @@ impl RustParser {
                         doc: field_documentation,
                         attributes: field_attributes,
                         source: field_source,
+                        discriminant: None,
+                        visibility: Visibility::Public,
                     });
                 }
             }
         }
 
+        let deprecation = deprecation_from_attributes(&attributes);
+        let (is_must_use, must_use_note) = must_use_from_attributes(&attributes);
         // NOTE: Ensure StructUnit in src/parser/mod.rs has the `fields` field added.
         let struct_unit = StructUnit {
             name,
@@ -403,6 +962,9 @@ impl RustParser {
             attributes,
             fields,
             methods: Vec::new(), // Methods are parsed in impl blocks, not here
+            deprecation,
+            is_must_use,
+            must_use_note,
         };
 
         Ok(struct_unit)
@@ -416,7 +978,12 @@ impl RustParser {
         let documentation = self.extract_documentation(node, source_code);
         let attributes = extract_attributes(node, source_code);
         let source = get_node_text(node, source_code);
+        let head = self.parse_item_head(node, source_code, "trait", &visibility, &name);
+        let is_unsafe = node
+            .children(&mut node.walk())
+            .any(|child| child.kind() == "unsafe");
         let mut methods = Vec::new();
+        let mut associated_items = Vec::new();
 
         // Look for trait items (methods, associated types, consts)
         if let Some(block_node) = node
@@ -431,18 +998,44 @@ impl RustParser {
                         method.visibility = Visibility::Public;
                         methods.push(method);
                     }
+                } else if let Some(associated_item) = self.parse_associated_item(item, source_code)
+                {
+                    associated_items.push(associated_item);
                 }
-                // TODO: Potentially parse associated_type_declaration, constant_item in the future
             }
         }
 
+        let deprecation = deprecation_from_attributes(&attributes);
         Ok(TraitUnit {
             name,
             visibility,
             doc: documentation,
             source,
             attributes,
+            head,
+            associated_items,
+            is_unsafe,
             methods,
+            deprecation,
+        })
+    }
+
+    // Parse an associated type or constant of a trait or impl, `None` for other items
+    fn parse_associated_item(&self, node: Node, source_code: &str) -> Option<FieldUnit> {
+        if !matches!(node.kind(), "associated_type" | "type_item" | "const_item") {
+            return None;
+        }
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|name| get_node_text(name, source_code))
+            .unwrap_or_default();
+        Some(FieldUnit {
+            name,
+            doc: self.extract_documentation(node, source_code),
+            attributes: extract_attributes(node, source_code),
+            source: get_node_text(node, source_code),
+            discriminant: None,
+            visibility: Visibility::Public,
         })
     }
 
@@ -452,27 +1045,32 @@ impl RustParser {
         let attributes = extract_attributes(node, source_code);
         let source = get_node_text(node, source_code);
         let mut methods = Vec::new();
+        let mut associated_items = Vec::new();
 
-        // Parse impl head (declaration line)
-        let head = if let Some(src) = &source {
-            if let Some(body_start_idx) = src.find('{') {
-                src[0..body_start_idx].trim().to_string()
-            } else if let Some(semi_idx) = src.find(';') {
-                src[0..=semi_idx].trim().to_string()
-            } else {
-                "impl".to_string() // Fallback
+        let body = node
+            .children(&mut node.walk())
+            .find(|child| child.kind() == "declaration_list");
+
+        // Parse impl head (declaration line). Slice up to the body so `unsafe`, `!Trait`,
+        // where clauses and braces inside const generic arguments are kept as written,
+        // then collapse the whitespace of multi-line heads into single spaces.
+        let head = if let Some(body) = body {
+            &source_code[node.start_byte()..body.start_byte()]
+        } else if let Some(src) = &source {
+            match src.find('{') {
+                Some(body_start_idx) => &src[0..body_start_idx],
+                None => src.find(';').map_or("impl", |semi_idx| &src[0..=semi_idx]),
             }
         } else {
-            "impl".to_string() // Fallback
+            "impl" // Fallback
         };
+        let head = head.split_whitespace().collect::<Vec<_>>().join(" ");
 
-        // Check if head indicates a trait implementation
-        let is_trait_impl = head.contains(" for ");
+        // Trait impls are recognized by the grammar, `for<'a>` bounds in where clauses
+        // would fool a textual ` for ` check
+        let is_trait_impl = node.child_by_field_name("trait").is_some();
 
-        if let Some(block_node) = node
-            .children(&mut node.walk())
-            .find(|child| child.kind() == "declaration_list")
-        {
+        if let Some(block_node) = body {
             for item in block_node.children(&mut block_node.walk()) {
                 if item.kind() == "function_item" {
                     if let Ok(mut method) = self.parse_function(item, source_code) {
@@ -482,8 +1080,10 @@ impl RustParser {
                         }
                         methods.push(method);
                     }
+                } else if let Some(associated_item) = self.parse_associated_item(item, source_code)
+                {
+                    associated_items.push(associated_item);
                 }
-                // TODO: Parse associated types, consts within impls
             }
         }
 
@@ -492,6 +1092,7 @@ impl RustParser {
             head, // Use parsed head
             source,
             attributes,
+            associated_items,
             methods,
         })
     }
@@ -499,18 +1100,33 @@ impl RustParser {
 
 impl LanguageParser for RustParser {
     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
-        // Read the file
         let source_code = fs::read_to_string(file_path).map_err(Error::Io)?;
+        self.parse_str(&source_code, file_path)
+    }
 
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.set_timeout_micros(timeout_micros(timeout));
+    }
+
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
         // Parse the file
-        let tree = self
-            .parse(source_code.as_bytes(), None)
-            .ok_or_else(|| Error::TreeSitter("Failed to parse source code".to_string()))?;
+        let mut tree = parse_tree(self, source_code.as_bytes(), || {
+            Error::TreeSitter("Failed to parse source code".to_string())
+        })?;
+
+        // Macros 2.0 are not supported by the grammar, extract them and parse the rest again
+        let (masked_source, macros) = extract_macros_2_0(source_code, tree.root_node());
+        if !macros.is_empty() {
+            tree = parse_tree(self, masked_source.as_bytes(), || {
+                Error::TreeSitter("Failed to parse source code".to_string())
+            })?;
+        }
         let root_node = tree.root_node();
 
         // Create a new file unit
         let mut file_unit = FileUnit::new(file_path.to_path_buf());
-        file_unit.source = Some(source_code.clone());
+        file_unit.source = Some(source_code.to_string());
+        file_unit.parse_errors = collect_parse_errors(root_node, source_code);
 
         // Process the module document comment at the top of the file
         // Find the first non-comment, non-attribute node to pass to extract_documentation
@@ -522,14 +1138,16 @@ impl LanguageParser for RustParser {
                 && kind != "inner_attribute_item"
         });
 
-        if let Some(first_node) = first_item_node {
-            file_unit.doc = self.extract_documentation(first_node, &source_code);
+        if let Some(doc) = extract_inner_documentation(root_node, source_code) {
+            file_unit.doc = Some(doc);
+        } else if let Some(first_node) = first_item_node {
+            file_unit.doc = self.extract_documentation(first_node, source_code);
         } else {
             // If the file potentially only contains comments/attributes, try extracting from the last one
             if let Some(last_node) = root_node.children(&mut root_node.walk()).last() {
                 file_unit.doc = self.extract_documentation(
                     last_node.next_sibling().unwrap_or(last_node),
-                    &source_code,
+                    source_code,
                 );
             }
         }
@@ -538,38 +1156,43 @@ impl LanguageParser for RustParser {
         for child in root_node.children(&mut root_node.walk()) {
             match child.kind() {
                 "function_item" => {
-                    if let Ok(func) = self.parse_function(child, &source_code) {
+                    if let Ok(func) = self.parse_function(child, source_code) {
                         file_unit.functions.push(func);
                     }
                 }
                 "struct_item" => {
-                    if let Ok(struct_item) = self.parse_struct(child, &source_code) {
+                    if let Ok(struct_item) = self.parse_struct(child, source_code) {
                         file_unit.structs.push(struct_item);
                     }
                 }
                 "enum_item" => {
                     // Handle enum as a struct in our simplified model
-                    if let Ok(enum_as_struct) = self.parse_enum_as_struct(child, &source_code) {
+                    if let Ok(enum_as_struct) = self.parse_enum_as_struct(child, source_code) {
                         file_unit.structs.push(enum_as_struct);
                     }
                 }
                 "trait_item" => {
-                    if let Ok(trait_item) = self.parse_trait(child, &source_code) {
+                    if let Ok(trait_item) = self.parse_trait(child, source_code) {
                         file_unit.traits.push(trait_item);
                     }
                 }
                 "impl_item" => {
-                    if let Ok(impl_item) = self.parse_impl(child, &source_code) {
+                    if let Ok(impl_item) = self.parse_impl(child, source_code) {
                         file_unit.impls.push(impl_item);
                     }
                 }
                 "mod_item" => {
-                    if let Ok(module) = self.parse_module(child, &source_code) {
+                    if let Ok(module) = self.parse_module(child, source_code, &macros) {
                         file_unit.modules.push(module);
                     }
                 }
+                "macro_definition" => {
+                    if let Ok(macro_unit) = self.parse_macro_rules(child, source_code) {
+                        file_unit.macros.push(macro_unit);
+                    }
+                }
                 "use_declaration" => {
-                    if let Some(declare_text) = get_node_text(child, &source_code) {
+                    if let Some(declare_text) = get_node_text(child, source_code) {
                         file_unit.declares.push(crate::DeclareStatements {
                             source: declare_text,
                             kind: crate::DeclareKind::Use,
@@ -577,7 +1200,7 @@ impl LanguageParser for RustParser {
                     }
                 }
                 "extern_crate_declaration" => {
-                    if let Some(declare_text) = get_node_text(child, &source_code) {
+                    if let Some(declare_text) = get_node_text(child, source_code) {
                         file_unit.declares.push(crate::DeclareStatements {
                             source: declare_text,
                             kind: crate::DeclareKind::Other("extern_crate".to_string()),
@@ -585,18 +1208,41 @@ impl LanguageParser for RustParser {
                     }
                 }
                 "mod_declaration" => {
-                    if let Some(declare_text) = get_node_text(child, &source_code) {
+                    if let Some(declare_text) = get_node_text(child, source_code) {
                         file_unit.declares.push(crate::DeclareStatements {
                             source: declare_text,
                             kind: crate::DeclareKind::Mod,
                         });
                     }
                 }
+                "type_item" => {
+                    if let Ok(type_alias) = self.parse_type_alias(child, source_code) {
+                        file_unit.type_aliases.push(type_alias);
+                    }
+                }
+                "const_item" | "static_item" => {
+                    if let Ok(const_unit) = self.parse_const(child, source_code) {
+                        file_unit.consts.push(const_unit);
+                    }
+                }
+                "macro_invocation"
+                    if macro_name(child, source_code).as_deref() == Some("thread_local") =>
+                {
+                    file_unit
+                        .consts
+                        .extend(self.parse_thread_local(child, source_code));
+                }
+                "macro_invocation" => {
+                    if let Some(declare) = self.parse_special_item(child, source_code) {
+                        file_unit.declares.push(declare);
+                    }
+                }
                 _ => {
                     // Ignore other top-level constructs
                 }
             }
         }
+        file_unit.macros.extend(macros_in_scope(root_node, &macros));
 
         Ok(file_unit)
     }
@@ -619,6 +1265,7 @@ impl DerefMut for RustParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::DeclareStatements;
     use std::path::PathBuf;
 
     fn parse_fixture(file_name: &str) -> Result<FileUnit> {
@@ -629,6 +1276,225 @@ mod tests {
         parser.parse_file(&path)
     }
 
+    /// Render a parsed fixture as a deterministic outline for snapshot tests.
+    ///
+    /// Sources and bodies are left out so the snapshot only changes when the parsed
+    /// structure does. Review changes with `cargo insta review`.
+    fn snapshot_parse(fixture: &str) -> String {
+        let file_unit = parse_fixture(fixture).unwrap();
+        let mut out = format!("file {}\n", fixture);
+        push_doc(&mut out, 1, &file_unit.doc);
+        push_declares(&mut out, 1, &file_unit.declares);
+        push_consts(&mut out, 1, &file_unit.consts);
+        push_type_aliases(&mut out, 1, &file_unit.type_aliases);
+        push_items(
+            &mut out,
+            1,
+            &file_unit.functions,
+            &file_unit.structs,
+            &file_unit.traits,
+            &file_unit.impls,
+            &file_unit.macros,
+        );
+        push_modules(&mut out, 1, &file_unit.modules);
+        for (span, message) in &file_unit.parse_errors {
+            push_line(&mut out, 1, &format!("parse error {}: {}", span, message));
+        }
+        out
+    }
+
+    fn push_line(out: &mut String, depth: usize, line: &str) {
+        out.push_str(&"  ".repeat(depth));
+        // Keep one unit per line, multi-line heads and signatures are escaped
+        out.push_str(&line.replace('\n', "\\n"));
+        out.push('\n');
+    }
+
+    fn push_doc(out: &mut String, depth: usize, doc: &Option<String>) {
+        if let Some(doc) = doc {
+            push_line(out, depth, &format!("doc: {:?}", doc));
+        }
+    }
+
+    fn push_meta(
+        out: &mut String,
+        depth: usize,
+        attributes: &[String],
+        deprecation: Option<&Deprecation>,
+    ) {
+        if !attributes.is_empty() {
+            push_line(out, depth, &format!("attributes: {:?}", attributes));
+        }
+        if let Some(deprecation) = deprecation {
+            push_line(out, depth, &format!("deprecated: {:?}", deprecation));
+        }
+    }
+
+    fn push_declares(out: &mut String, depth: usize, declares: &[DeclareStatements]) {
+        for declare in declares {
+            push_line(
+                out,
+                depth,
+                &format!("declare {:?}: {}", declare.kind, declare.source),
+            );
+        }
+    }
+
+    fn push_consts(out: &mut String, depth: usize, consts: &[ConstUnit]) {
+        for item in consts {
+            let keyword = match (item.is_static, item.is_mut) {
+                (false, _) => "const",
+                (true, false) => "static",
+                (true, true) => "static mut",
+            };
+            push_line(
+                out,
+                depth,
+                &format!(
+                    "{} {} ({:?}): {}",
+                    keyword,
+                    item.name,
+                    item.visibility,
+                    item.source.as_deref().unwrap_or_default()
+                ),
+            );
+            push_doc(out, depth + 1, &item.doc);
+            push_meta(out, depth + 1, &item.attributes, None);
+        }
+    }
+
+    fn push_type_aliases(out: &mut String, depth: usize, type_aliases: &[TypeAliasUnit]) {
+        for item in type_aliases {
+            push_line(
+                out,
+                depth,
+                &format!(
+                    "type {} ({:?}): {}",
+                    item.name,
+                    item.visibility,
+                    item.source.as_deref().unwrap_or_default()
+                ),
+            );
+            push_doc(out, depth + 1, &item.doc);
+            push_meta(out, depth + 1, &item.attributes, None);
+        }
+    }
+
+    fn push_functions(out: &mut String, depth: usize, functions: &[FunctionUnit]) {
+        for function in functions {
+            push_line(
+                out,
+                depth,
+                &format!(
+                    "fn {} ({:?}): {}",
+                    function.name,
+                    function.visibility,
+                    function.signature.as_deref().unwrap_or_default()
+                ),
+            );
+            push_doc(out, depth + 1, &function.doc);
+            push_meta(
+                out,
+                depth + 1,
+                &function.attributes,
+                function.deprecation.as_ref(),
+            );
+        }
+    }
+
+    fn push_items(
+        out: &mut String,
+        depth: usize,
+        functions: &[FunctionUnit],
+        structs: &[StructUnit],
+        traits: &[TraitUnit],
+        impls: &[ImplUnit],
+        macros: &[MacroUnit],
+    ) {
+        push_functions(out, depth, functions);
+        for item in structs {
+            push_line(
+                out,
+                depth,
+                &format!(
+                    "struct {} ({:?}): {}",
+                    item.name, item.visibility, item.head
+                ),
+            );
+            push_doc(out, depth + 1, &item.doc);
+            push_meta(out, depth + 1, &item.attributes, item.deprecation.as_ref());
+            for field in &item.fields {
+                push_line(
+                    out,
+                    depth + 1,
+                    &format!(
+                        "field {}: {}",
+                        field.name,
+                        field.source.as_deref().unwrap_or_default()
+                    ),
+                );
+                push_doc(out, depth + 2, &field.doc);
+                push_meta(out, depth + 2, &field.attributes, None);
+            }
+            push_functions(out, depth + 1, &item.methods);
+        }
+        for item in traits {
+            push_line(
+                out,
+                depth,
+                &format!("trait {} ({:?}): {}", item.name, item.visibility, item.head),
+            );
+            push_doc(out, depth + 1, &item.doc);
+            push_meta(out, depth + 1, &item.attributes, item.deprecation.as_ref());
+            push_functions(out, depth + 1, &item.methods);
+        }
+        for item in impls {
+            push_line(out, depth, &format!("impl: {}", item.head));
+            push_doc(out, depth + 1, &item.doc);
+            push_meta(out, depth + 1, &item.attributes, None);
+            push_functions(out, depth + 1, &item.methods);
+        }
+        for item in macros {
+            push_line(
+                out,
+                depth,
+                &format!("macro {} ({:?}): {}", item.name, item.visibility, item.head),
+            );
+            push_doc(out, depth + 1, &item.doc);
+            push_meta(out, depth + 1, &item.attributes, None);
+        }
+    }
+
+    fn push_modules(out: &mut String, depth: usize, modules: &[ModuleUnit]) {
+        for module in modules {
+            push_line(
+                out,
+                depth,
+                &format!("mod {} ({:?})", module.name, module.visibility),
+            );
+            push_doc(out, depth + 1, &module.doc);
+            push_meta(
+                out,
+                depth + 1,
+                &module.attributes,
+                module.deprecation.as_ref(),
+            );
+            push_declares(out, depth + 1, &module.declares);
+            push_consts(out, depth + 1, &module.consts);
+            push_type_aliases(out, depth + 1, &module.type_aliases);
+            push_items(
+                out,
+                depth + 1,
+                &module.functions,
+                &module.structs,
+                &module.traits,
+                &module.impls,
+                &module.macros,
+            );
+            push_modules(out, depth + 1, &module.submodules);
+        }
+    }
+
     #[test]
     fn test_parse_file_level_items() {
         let file_unit = parse_fixture("sample.rs").unwrap();
@@ -862,98 +1728,1303 @@ mod tests {
 
     #[test]
     fn test_parse_enum_with_variants() {
-        let file_unit = parse_fixture("sample_enum.rs").unwrap();
+        insta::assert_snapshot!(snapshot_parse("sample_enum.rs"));
+    }
 
-        // Find PublicEnum
-        let public_enum = file_unit
-            .structs // Enums are parsed as structs
-            .iter()
-            .find(|s| s.name == "PublicEnum")
-            .expect("PublicEnum not found");
+    #[test]
+    fn test_raw_identifiers() {
+        use crate::{BankStrategy, Formatter};
 
-        assert_eq!(public_enum.visibility, Visibility::Public);
-        assert!(public_enum.doc.is_some());
-        assert!(
-            public_enum
-                .doc
-                .as_ref()
-                .unwrap()
-                .contains("public enum with documentation")
-        );
-        assert_eq!(public_enum.attributes.len(), 1);
-        assert_eq!(public_enum.attributes[0], "#[derive(Debug)]");
-        assert_eq!(public_enum.head, "pub enum PublicEnum");
+        let file_unit = parse_fixture("sample_raw_identifiers.rs").unwrap();
 
-        // Check if variants were parsed as fields
-        assert!(
-            !public_enum.fields.is_empty(),
-            "Variants should be parsed as fields for PublicEnum"
+        let function = &file_unit.functions[0];
+        assert_eq!(function.name, "r#async");
+        assert_eq!(
+            function.signature.as_deref(),
+            Some("pub fn r#async(r#in: u32) -> u32")
         );
-        assert_eq!(public_enum.fields.len(), 3, "Expected 3 variants");
+        let type_unit = &file_unit.structs[0];
+        assert_eq!(type_unit.name, "r#type");
+        assert_eq!(type_unit.head, "pub struct r#type");
+        assert_eq!(type_unit.fields[0].name, "r#ref");
+        assert_eq!(file_unit.impls[0].head, "impl r#type");
+        assert_eq!(file_unit.impls[0].methods[0].name, "r#match");
+        assert_eq!(file_unit.traits[0].name, "r#trait");
+        assert_eq!(file_unit.traits[0].methods[0].name, "r#move");
+        assert_eq!(file_unit.modules[0].name, "r#mod");
+        assert_eq!(file_unit.modules[0].functions[0].name, "r#yield");
+
+        let formatted = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("pub fn r#async(r#in: u32) -> u32 { ... }"));
+        assert!(formatted.contains("pub r#ref: String,"));
+        assert!(formatted.contains("pub fn r#match(&self, r#type: &str) -> bool { ... }"));
+        assert!(formatted.contains("pub mod r#mod {"));
+    }
+
+    #[test]
+    fn test_enum_discriminants() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_discriminants.rs").unwrap();
 
-        // Check details of the first variant (Variant1)
-        let variant1 = public_enum
+        let status = &file_unit.structs[0];
+        let discriminants: Vec<_> = status
             .fields
             .iter()
-            .find(|f| f.name == "Variant1")
-            .expect("Variant1 not found");
+            .map(|f| (f.name.as_str(), f.discriminant.as_deref()))
+            .collect();
+        assert_eq!(
+            discriminants,
+            [
+                ("Active", Some("1")),
+                ("Inactive", Some("2")),
+                ("Banned", Some("1 << 4"))
+            ]
+        );
+        let frame_type = &file_unit.structs[1];
+        assert_eq!(frame_type.fields[1].discriminant.as_deref(), Some("0x1"));
+        assert_eq!(frame_type.fields[2].discriminant, None);
+
+        for strategy in [BankStrategy::Default, BankStrategy::Summary] {
+            let formatted = status.format(&strategy, LanguageType::Rust).unwrap();
+            assert!(formatted.contains("Active = 1,"), "{:?}", strategy);
+            assert!(formatted.contains("Banned = 1 << 4,"), "{:?}", strategy);
+        }
+    }
 
-        assert!(variant1.doc.is_some());
-        assert!(
-            variant1
-                .doc
-                .as_ref()
-                .unwrap()
-                .contains("Variant documentation")
+    #[test]
+    fn test_sample_fixture_snapshot() {
+        insta::assert_snapshot!(snapshot_parse("sample.rs"));
+    }
+
+    #[test]
+    fn test_parse_macro_definitions() {
+        let file_unit = parse_fixture("sample_macros.rs").unwrap();
+
+        let find = |name: &str| file_unit.macros.iter().find(|m| m.name == name).unwrap();
+
+        let exported = find("exported_macro");
+        assert!(exported.is_macro_rules);
+        assert_eq!(exported.visibility, Visibility::Public);
+        assert_eq!(exported.head, "macro_rules! exported_macro");
+        assert_eq!(
+            exported.doc.as_deref(),
+            Some("An exported declarative macro")
         );
-        assert!(variant1.attributes.is_empty());
-        // Source should NOT have trailing comma
-        assert_eq!(variant1.source.as_ref().unwrap(), "Variant1");
 
-        // Check details of the second variant (Variant2)
-        let variant2 = public_enum
-            .fields
-            .iter()
-            .find(|f| f.name == "Variant2")
-            .expect("Variant2 not found");
+        let local = find("local_macro");
+        assert!(local.is_macro_rules);
+        assert_eq!(local.visibility, Visibility::Private);
 
-        assert!(
-            variant2
-                .doc
-                .as_ref()
-                .unwrap()
-                .contains("Another variant documentation")
+        let public = find("public_macro");
+        assert!(!public.is_macro_rules);
+        assert_eq!(public.visibility, Visibility::Public);
+        assert_eq!(public.head, "pub macro public_macro($x:expr)");
+        assert_eq!(
+            public.doc.as_deref(),
+            Some("A public macros 2.0 definition")
         );
-        assert!(!variant2.attributes.is_empty());
-        assert_eq!(variant2.attributes[0], "#[allow(dead_code)]");
-        // Source should NOT have trailing comma
-        assert_eq!(variant2.source.as_ref().unwrap(), "Variant2(String)");
 
-        // Check details of the third variant (Variant3)
-        let variant3 = public_enum
-            .fields
-            .iter()
-            .find(|f| f.name == "Variant3")
-            .expect("Variant3 not found");
+        let private = find("private_macro");
+        assert!(!private.is_macro_rules);
+        assert_eq!(private.visibility, Visibility::Private);
 
-        assert!(
-            variant3
-                .doc
-                .as_ref()
-                .unwrap()
-                .contains("Yet another variant documentation")
+        // Items following a macros 2.0 definition are still parsed with their own docs
+        assert_eq!(file_unit.functions.len(), 1);
+        assert_eq!(
+            file_unit.functions[0].doc.as_deref(),
+            Some("A function following the macros")
         );
-        assert!(variant3.attributes.is_empty());
-        // Source should NOT have trailing comma
-        assert_eq!(variant3.source.as_ref().unwrap(), "Variant3 { field: i32 }");
+    }
 
-        // Check that PrivateEnum was also parsed (as a struct)
-        let private_enum = file_unit
-            .structs
+    #[test]
+    fn test_parse_macros_in_module() {
+        let file_unit = parse_fixture("sample_macros.rs").unwrap();
+        assert_eq!(file_unit.macros.len(), 4);
+
+        let nested = file_unit
+            .modules
+            .iter()
+            .find(|m| m.name == "nested")
+            .unwrap();
+        assert_eq!(nested.macros.len(), 2);
+
+        let crate_macro = nested
+            .macros
+            .iter()
+            .find(|m| m.name == "crate_macro")
+            .unwrap();
+        assert_eq!(crate_macro.visibility, Visibility::Crate);
+
+        let nested_exported = nested
+            .macros
             .iter()
-            .find(|s| s.name == "PrivateEnum")
-            .expect("PrivateEnum not found");
-        assert_eq!(private_enum.visibility, Visibility::Private);
-        assert_eq!(private_enum.fields.len(), 1); // Should have one variant
+            .find(|m| m.name == "nested_exported")
+            .unwrap();
+        assert!(nested_exported.is_macro_rules);
+        assert_eq!(nested_exported.visibility, Visibility::Public);
+    }
+
+    #[test]
+    fn test_macro_keyword_in_strings_and_comments() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_macro_text.rs").unwrap();
+        let names: Vec<_> = file_unit.macros.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["real"]);
+        let names: Vec<_> = file_unit
+            .functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, ["help", "after_comment"]);
+
+        let summary = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(summary.contains("pub fn help() -> &'static str { ... }"));
+        assert!(summary.contains("pub fn after_comment() { ... }"));
+        assert!(summary.contains("pub macro real($x:expr) { ... }"));
+    }
+
+    #[test]
+    fn test_collect_code_units_by_kind() {
+        use crate::{CodeUnit, UnitKind};
+        use std::collections::HashMap;
+
+        fn collect<'a>(module: &'a ModuleUnit, units: &mut Vec<&'a dyn CodeUnit>) {
+            units.push(module);
+            units.extend(module.functions.iter().map(|u| u as &dyn CodeUnit));
+            units.extend(module.structs.iter().map(|u| u as &dyn CodeUnit));
+            units.extend(module.traits.iter().map(|u| u as &dyn CodeUnit));
+            units.extend(module.impls.iter().map(|u| u as &dyn CodeUnit));
+            units.extend(module.macros.iter().map(|u| u as &dyn CodeUnit));
+            units.extend(module.consts.iter().map(|u| u as &dyn CodeUnit));
+            units.extend(module.type_aliases.iter().map(|u| u as &dyn CodeUnit));
+            for submodule in &module.submodules {
+                collect(submodule, units);
+            }
+        }
+
+        let file_unit = parse_fixture("sample.rs").unwrap();
+        let mut units: Vec<&dyn CodeUnit> = Vec::new();
+        units.extend(file_unit.functions.iter().map(|u| u as &dyn CodeUnit));
+        units.extend(file_unit.structs.iter().map(|u| u as &dyn CodeUnit));
+        units.extend(file_unit.traits.iter().map(|u| u as &dyn CodeUnit));
+        units.extend(file_unit.impls.iter().map(|u| u as &dyn CodeUnit));
+        units.extend(file_unit.macros.iter().map(|u| u as &dyn CodeUnit));
+        units.extend(file_unit.consts.iter().map(|u| u as &dyn CodeUnit));
+        units.extend(file_unit.type_aliases.iter().map(|u| u as &dyn CodeUnit));
+        for module in &file_unit.modules {
+            collect(module, &mut units);
+        }
+
+        let mut counts: HashMap<UnitKind, usize> = HashMap::new();
+        for unit in &units {
+            *counts.entry(unit.kind()).or_default() += 1;
+        }
+        assert_eq!(counts[&UnitKind::Module], 3);
+        assert_eq!(counts[&UnitKind::Function], 3);
+        assert_eq!(counts[&UnitKind::Struct], 4);
+        assert_eq!(counts[&UnitKind::Enum], 2);
+        assert_eq!(counts[&UnitKind::Trait], 3);
+        assert_eq!(counts[&UnitKind::Impl], 6);
+        assert_eq!(counts[&UnitKind::Macro], 1);
+        assert_eq!(counts[&UnitKind::Const], 2);
+        assert_eq!(counts[&UnitKind::TypeAlias], 1);
+
+        let public_enum = units
+            .iter()
+            .find(|unit| unit.name() == "PublicEnum")
+            .unwrap();
+        assert_eq!(public_enum.kind(), UnitKind::Enum);
+        assert_eq!(public_enum.visibility(), &Visibility::Public);
+    }
+
+    #[test]
+    fn test_parse_deprecated_items() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_deprecated.rs").unwrap();
+
+        let start = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "start")
+            .unwrap();
+        assert_eq!(
+            start.deprecation,
+            Some(Deprecation {
+                since: Some("0.2.0".to_string()),
+                note: Some("use `run` instead".to_string()),
+            })
+        );
+
+        let stop = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "stop")
+            .unwrap();
+        assert_eq!(
+            stop.deprecation,
+            Some(Deprecation {
+                since: None,
+                note: Some("use `run` and drop the handle instead".to_string()),
+            })
+        );
+
+        let settings = &file_unit.structs[0];
+        assert_eq!(
+            settings.deprecation,
+            Some(Deprecation {
+                since: None,
+                note: Some("use `Config` instead".to_string()),
+            })
+        );
+
+        let legacy = &file_unit.traits[0];
+        assert_eq!(legacy.deprecation, Some(Deprecation::default()));
+
+        let max_threads = &file_unit.consts[0];
+        assert_eq!(
+            max_threads.deprecation,
+            Some(Deprecation {
+                since: Some("0.3.0".to_string()),
+                note: Some("use `MAX_JOBS` instead".to_string()),
+            })
+        );
+
+        let run = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "run")
+            .unwrap();
+        assert!(run.deprecation.is_none());
+
+        let formatted = start
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.starts_with("/// ⚠️ DEPRECATED since 0.2.0: use `run` instead\n"));
+        let formatted = stop
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(
+            formatted.starts_with("/// ⚠️ DEPRECATED: use `run` and drop the handle instead\n")
+        );
+        let formatted = legacy
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.starts_with("/// ⚠️ DEPRECATED\n"));
+        let formatted = max_threads
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.starts_with("/// ⚠️ DEPRECATED since 0.3.0: use `MAX_JOBS` instead\n"));
+
+        let options = &file_unit.structs[1];
+        assert_eq!(
+            options.deprecation,
+            Some(Deprecation {
+                since: None,
+                note: Some("use `Config { verbose = true }` instead".to_string()),
+            })
+        );
+
+        let handle = &file_unit.type_aliases[0];
+        assert_eq!(handle.name, "Handle");
+        assert_eq!(handle.deprecation, Some(Deprecation::default()));
+        let formatted = handle
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.starts_with("/// ⚠️ DEPRECATED\n#[deprecated]\npub type Handle = u64;"));
+    }
+
+    #[test]
+    fn test_modern_syntax_signature_split() {
+        let file_unit = parse_fixture("sample_modern_syntax.rs").unwrap();
+        let function = |name: &str| file_unit.functions.iter().find(|f| f.name == name).unwrap();
+
+        let first_word = function("first_word");
+        assert_eq!(
+            first_word.signature.as_deref(),
+            Some("pub fn first_word(text: &str) -> &str")
+        );
+        assert!(first_word.body.as_deref().unwrap().ends_with("word\n}"));
+
+        let both = function("both");
+        assert_eq!(
+            both.signature.as_deref(),
+            Some("pub fn both(a: Option<u8>, b: Option<u8>) -> u8")
+        );
+
+        let chunk = function("chunk");
+        assert_eq!(
+            chunk.signature.as_deref(),
+            Some("pub fn chunk() -> Buffer<{ 2 * 1024 }>")
+        );
+        assert!(
+            chunk
+                .body
+                .as_deref()
+                .unwrap()
+                .starts_with("{\n    Buffer {")
+        );
+
+        let fetch_all = function("fetch_all");
+        assert_eq!(
+            fetch_all.signature.as_deref(),
+            Some("pub async fn fetch_all(urls: Vec<String>) -> usize")
+        );
+        assert!(
+            fetch_all
+                .body
+                .as_deref()
+                .unwrap()
+                .ends_with("task.await\n}")
+        );
+
+        let buffer = &file_unit.structs[0];
+        assert_eq!(
+            buffer.head,
+            "pub struct Buffer<const N: usize = { 4 * 1024 }>"
+        );
+        assert_eq!(buffer.fields.len(), 1);
+        assert_eq!(
+            file_unit.structs[1].head,
+            "pub struct Pair(pub u8, pub [u8; 4]);"
+        );
+
+        let header = &file_unit.traits[0].methods[0];
+        assert_eq!(header.signature.as_deref(), Some("fn header() -> [u8; 4];"));
+        assert!(header.body.is_none());
+    }
+
+    #[test]
+    fn test_generic_field_types() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_with_fields.rs").unwrap();
+        let generic_fields = file_unit
+            .structs
+            .iter()
+            .find(|s| s.name == "GenericFields")
+            .unwrap();
+        assert_eq!(generic_fields.fields.len(), 4);
+
+        let formatted = generic_fields
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        for field in [
+            "    pub entries: HashMap<String, Vec<u8>>,\n",
+            "    pub handler: Box<dyn Fn(u32) -> Result<(), Error> + Send + 'static>,\n",
+            "    pub pairs: Vec<(u8, u16)>,\n",
+            "    pub reader: fn(&[u8]) -> impl Iterator<Item = (usize, u8)>,\n",
+        ] {
+            assert!(formatted.contains(field), "{field:?} in {formatted}");
+        }
+    }
+
+    #[test]
+    fn test_multi_line_trait_head() {
+        use crate::{BankStrategy, Formatter};
+
+        let source = r#"
+/// A multi-line trait declaration
+pub trait Repository<T>:
+    Send
+    + Sync
+where
+    T: Clone,
+{
+    fn get(&self, id: u64) -> Option<T>;
+}
+"#;
+        let mut parser = RustParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("repo.rs")).unwrap();
+
+        let repository = &file_unit.traits[0];
+        assert_eq!(
+            repository.head,
+            "pub trait Repository<T>:\n    Send\n    + Sync\nwhere\n    T: Clone,"
+        );
+
+        let formatted = repository
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains(
+            "pub trait Repository<T>:\n    Send\n    + Sync\nwhere\n    T: Clone, { ... }"
+        ));
+    }
+
+    #[test]
+    fn test_unsafe_and_negative_impl_heads() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_auto_traits.rs").unwrap();
+        let heads: Vec<&str> = file_unit
+            .impls
+            .iter()
+            .map(|imp| imp.head.as_str())
+            .collect();
+        assert_eq!(
+            heads,
+            vec![
+                "unsafe impl Send for Foo",
+                "impl !Sync for Bar",
+                "unsafe impl Marker for Bar",
+                "unsafe impl Sync for Buffer<{ 4 * 1024 }>"
+            ]
+        );
+
+        let formatted = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("unsafe impl Send for Foo"));
+        assert!(formatted.contains("impl !Sync for Bar"));
+    }
+
+    #[test]
+    fn test_impl_heads_with_where_clauses() {
+        let file_unit = parse_fixture("sample_where_clauses.rs").unwrap();
+
+        let summarize = &file_unit.impls[0];
+        assert_eq!(
+            summarize.head,
+            "impl<T> Summarize for Wrapper<T> where T: Iterator + Clone, T::Item: Clone + Debug,"
+        );
+        assert_eq!(summarize.trait_name(), Some("Summarize"));
+        assert_eq!(summarize.methods[0].visibility, Visibility::Public);
+
+        let inherent = &file_unit.impls[1];
+        assert_eq!(
+            inherent.head,
+            "impl<T> Wrapper<T> where for<'a> T: Visit<'a>,"
+        );
+        assert_eq!(inherent.trait_name(), None);
+        assert_eq!(inherent.methods[0].visibility, Visibility::Public);
+    }
+
+    #[test]
+    fn test_impl_heads_with_generic_arguments() {
+        let file_unit = parse_fixture("sample_generic_impls.rs").unwrap();
+        let heads: Vec<_> = file_unit
+            .impls
+            .iter()
+            .map(|impl_unit| (impl_unit.head.as_str(), impl_unit.trait_name()))
+            .collect();
+        assert_eq!(
+            heads,
+            [
+                (
+                    "impl GenericTrait<String> for Foo<u32>",
+                    Some("GenericTrait")
+                ),
+                (
+                    "impl<T: Clone> GenericTrait<Vec<T>> for Pair<T, HashMap<String, Vec<T>>>",
+                    Some("GenericTrait")
+                ),
+                (
+                    "impl<const N: usize> GenericTrait<[u8; N]> for Foo<[u8; N]>",
+                    Some("GenericTrait")
+                ),
+                ("impl Foo<Option<&'static str>>", None),
+                (
+                    "impl<'a, B> std::ops::Deref for Pair<&'a str, B>",
+                    Some("Deref")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_impl_heads_with_trait_objects() {
+        use crate::parser::exclude::impl_type_name;
+
+        let file_unit = parse_fixture("sample_dyn_impls.rs").unwrap();
+        let heads: Vec<_> = file_unit
+            .impls
+            .iter()
+            .map(|impl_unit| {
+                (
+                    impl_unit.head.as_str(),
+                    impl_unit.trait_name(),
+                    impl_type_name(&impl_unit.head),
+                )
+            })
+            .collect();
+        assert_eq!(
+            heads,
+            [
+                (
+                    "impl Describe for dyn Other + Send",
+                    Some("Describe"),
+                    "Other"
+                ),
+                (
+                    "impl<T: ?Sized> Describe for Box<T> where T: Describe,",
+                    Some("Describe"),
+                    "Box"
+                ),
+                (
+                    "impl fmt::Debug for dyn Other + Send + Sync + 'static",
+                    Some("Debug"),
+                    "Other"
+                ),
+                ("impl dyn Other", None, "Other"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_associated_items() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_associated_items.rs").unwrap();
+        let shape = &file_unit.traits[0];
+        let items: Vec<_> = shape
+            .associated_items
+            .iter()
+            .map(|item| (item.name.as_str(), item.doc.as_deref()))
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("Output", Some("The type of the computed area")),
+                ("SIDES", Some("The number of sides")),
+            ]
+        );
+        assert_eq!(shape.methods.len(), 1);
+
+        let formatted = shape
+            .format(&BankStrategy::NoTests, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains(
+            "    /// The type of the computed area\n    type Output;\n    /// The number of sides\n    const SIDES: u32 = 0;\n"
+        ));
+
+        let trait_impl = &file_unit.impls[0];
+        assert_eq!(trait_impl.associated_items.len(), 2);
+        let formatted = trait_impl
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains(
+            "    /// Areas of squares are floats\n    type Output = f64;\n    /// Squares have four sides\n    const SIDES: u32 = 4;\n"
+        ));
+
+        // Inherent impls only show their public associated items in Summary
+        let formatted = file_unit.impls[1]
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(
+            formatted
+                .contains("    /// The unit square\n    pub const UNIT: Square = Square(1.0);\n")
+        );
+        assert!(!formatted.contains("SCALE"));
+    }
+
+    #[test]
+    fn test_generic_associated_types() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_associated_items.rs").unwrap();
+        let lending = &file_unit.traits[1];
+        let item = &lending.associated_items[0];
+        assert_eq!(item.name, "Item");
+        assert_eq!(
+            item.source.as_deref(),
+            Some("type Item<'a>\n    where\n        Self: 'a;")
+        );
+        let formatted = lending
+            .format(&BankStrategy::NoTests, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("    /// The items, borrowed for `'a`\n    type Item<'a>\n"));
+
+        let windows = &file_unit.impls[2];
+        assert_eq!(windows.head, "impl LendingIterator for Windows");
+        let formatted = windows
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("    type Item<'a> = &'a [u8] where Self: 'a;\n"));
+    }
+
+    #[test]
+    fn test_impl_heads_for_primitive_types() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_primitive_impls.rs").unwrap();
+        let heads: Vec<_> = file_unit.impls.iter().map(|i| i.head.as_str()).collect();
+        assert_eq!(
+            heads,
+            [
+                "impl MyTrait for u32",
+                "impl MyTrait for ()",
+                "impl<T> MyTrait for [T]",
+                "impl<A, B> MyTrait for (A, B)",
+                "impl MyTrait for &str",
+            ]
+        );
+
+        let formatted = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        for head in heads {
+            assert!(formatted.contains(&format!(
+                "{} {{\n    fn id(&self) -> u8 {{ ... }}\n}}",
+                head
+            )));
+        }
+        assert!(!formatted.contains("UnknownType"));
+    }
+
+    #[test]
+    fn test_impl_heads_for_unnamed_types() {
+        // Types without a type identifier, the head is taken from the source as written
+        let source = r#"
+impl Trait for [u8; 4] {}
+impl<T> Trait for (T, T) {}
+impl Trait for fn(u8) -> u8 {}
+impl<'a, T> Trait for &'a mut T {}
+impl Trait for *const u8 {}
+impl Trait for my_type!() {}
+impl Trait
+    for
+    [u8; 4]
+{
+}
+"#;
+        let mut parser = RustParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("impls.rs")).unwrap();
+        let heads: Vec<_> = file_unit.impls.iter().map(|i| i.head.as_str()).collect();
+        assert_eq!(
+            heads,
+            [
+                "impl Trait for [u8; 4]",
+                "impl<T> Trait for (T, T)",
+                "impl Trait for fn(u8) -> u8",
+                "impl<'a, T> Trait for &'a mut T",
+                "impl Trait for *const u8",
+                "impl Trait for my_type!()",
+                "impl Trait for [u8; 4]",
+            ]
+        );
+        assert!(
+            file_unit
+                .impls
+                .iter()
+                .all(|i| i.trait_name() == Some("Trait"))
+        );
+    }
+
+    #[test]
+    fn test_return_types() {
+        let source = r#"
+pub fn load(path: &Path) -> Result<Vec<u8>, io::Error> {
+    fs::read(path)
+}
+
+pub fn reset() {}
+
+pub trait Store {
+    fn get(&self, key: &str) -> Option<&[u8]>;
+}
+"#;
+        let mut parser = RustParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("store.rs")).unwrap();
+        assert_eq!(
+            file_unit.functions[0].return_type.as_deref(),
+            Some("Result<Vec<u8>, io::Error>")
+        );
+        assert_eq!(file_unit.functions[1].return_type, None);
+        assert_eq!(
+            file_unit.traits[0].methods[0].return_type.as_deref(),
+            Some("Option<&[u8]>")
+        );
+    }
+
+    #[test]
+    fn test_nested_impls_are_not_collected() {
+        let file_unit = parse_fixture("sample_nested_impls.rs").unwrap();
+        // Impls in const initializers and function bodies are implementation details
+        assert!(file_unit.impls.is_empty());
+        assert_eq!(file_unit.structs.len(), 1);
+        assert_eq!(file_unit.functions[0].name, "register");
+
+        let api = &file_unit.modules[0];
+        let heads: Vec<_> = api.impls.iter().map(|i| i.head.as_str()).collect();
+        assert_eq!(heads, ["impl Handle"]);
+        assert_eq!(api.structs.len(), 1);
+    }
+
+    #[test]
+    fn test_async_fn_summary() {
+        use crate::{BankStrategy, Formatter};
+
+        let source = r#"
+/// Fetch the data
+pub async fn fetch() -> Data {
+    let data = async move { load().await };
+    data.await
+}
+
+pub struct Client;
+
+impl Client {
+    pub async fn get(&self) -> Result<Data, Error> {
+        async { Ok(Data) }.await
+    }
+}
+"#;
+        let mut parser = RustParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("client.rs")).unwrap();
+        assert_eq!(
+            file_unit.functions[0].signature.as_deref(),
+            Some("pub async fn fetch() -> Data")
+        );
+
+        let formatted = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("/// Fetch the data\npub async fn fetch() -> Data { ... }\n"));
+        assert!(formatted.contains("    pub async fn get(&self) -> Result<Data, Error> { ... }\n"));
+        assert!(!formatted.contains("async move"));
+        assert!(!formatted.contains(".await"));
+    }
+
+    #[test]
+    fn test_params() {
+        let source = r#"
+pub struct Counter;
+
+impl Counter {
+    pub fn add(&self, name: &str, count: usize) {}
+
+    pub fn into_pair(mut self, (a, b): (u8, u8), _: i32) {}
+
+    pub fn pinned(self: Pin<&mut Self>) {}
+}
+"#;
+        let param = |name: Option<&str>, ty: &str, is_self: bool| ParamUnit {
+            name: name.map(str::to_string),
+            ty: ty.to_string(),
+            is_self,
+        };
+        let mut parser = RustParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("counter.rs")).unwrap();
+        let methods = &file_unit.impls[0].methods;
+        assert_eq!(
+            methods[0].params,
+            [
+                param(Some("self"), "&Self", true),
+                param(Some("name"), "&str", false),
+                param(Some("count"), "usize", false),
+            ]
+        );
+        assert_eq!(
+            methods[1].params,
+            [
+                param(Some("self"), "Self", true),
+                param(Some("(a, b)"), "(u8, u8)", false),
+                param(None, "i32", false),
+            ]
+        );
+        assert_eq!(
+            methods[2].params,
+            [param(Some("self"), "Pin<&mut Self>", true)]
+        );
+        assert!(methods.iter().all(|method| !method.is_associated_fn));
+        // The signature stays the canonical form
+        assert_eq!(
+            methods[0].signature.as_deref(),
+            Some("pub fn add(&self, name: &str, count: usize)")
+        );
+    }
+
+    #[test]
+    fn test_file_docs_after_inner_attributes() {
+        let file_unit = parse_fixture("sample_inner_attributes.rs").unwrap();
+        assert_eq!(
+            file_unit.doc.as_deref(),
+            Some("Crate docs\nspanning two lines.\nBlock docs after an inner attribute.")
+        );
+        assert_eq!(file_unit.functions.len(), 1);
+        assert_eq!(
+            file_unit.functions[0].doc.as_deref(),
+            Some("Returns the answer.")
+        );
+    }
+
+    #[test]
+    fn test_mixed_doc_comment_styles() {
+        let file_unit = parse_fixture("sample_mixed_docs.rs").unwrap();
+        let docs: Vec<_> = file_unit
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.doc.as_deref()))
+            .collect();
+        assert_eq!(
+            docs,
+            [
+                ("f", Some("block doc\nline doc")),
+                ("g", Some("line doc first\nthen a block doc")),
+                (
+                    "h",
+                    Some(
+                        "Text on the opening line,\nand on the next one.\nLine doc after an attribute."
+                    )
+                ),
+                ("i", Some("Only this line is a doc.")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_must_use_note() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample.rs").unwrap();
+        let function = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "public_function")
+            .unwrap();
+        assert!(function.is_must_use);
+        assert_eq!(
+            function.must_use_note.as_deref(),
+            Some("Return value should be used")
+        );
+        let formatted = function
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.starts_with(
+            "// must use: Return value should be used\n/// This is a public function with documentation\n"
+        ));
+
+        let private = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "private_function")
+            .unwrap();
+        assert!(!private.is_must_use);
+        assert_eq!(
+            must_use_from_attributes(&["#[must_use]".to_string()]),
+            (true, None)
+        );
+    }
+
+    #[test]
+    fn test_method_receivers() {
+        let file_unit = parse_fixture("sample_receivers.rs").unwrap();
+        let methods: Vec<_> = file_unit.impls[0]
+            .methods
+            .iter()
+            .map(|m| (m.name.as_str(), m.is_associated_fn))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                ("new", true),
+                ("by_ref", false),
+                ("by_mut", false),
+                ("by_value", false),
+                ("by_mut_value", false),
+                ("with_lifetime", false),
+                ("boxed", false),
+                ("pinned", false),
+                ("mut_boxed", false),
+            ]
+        );
+        let signature = |name: &str| {
+            file_unit.impls[0]
+                .methods
+                .iter()
+                .find(|m| m.name == name)
+                .and_then(|m| m.signature.as_deref())
+                .unwrap()
+        };
+        assert_eq!(signature("pinned"), "pub fn pinned(self: Pin<&mut Self>)");
+        assert_eq!(signature("boxed"), "pub fn boxed(self: Box<Self>)");
+        assert_eq!(
+            signature("with_lifetime"),
+            "pub fn with_lifetime<'a>(&'a self) -> &'a Self"
+        );
+
+        let trait_methods: Vec<_> = file_unit.traits[0]
+            .methods
+            .iter()
+            .map(|m| (m.name.as_str(), m.is_associated_fn, m.signature.as_deref()))
+            .collect();
+        assert_eq!(
+            trait_methods,
+            [
+                (
+                    "create",
+                    true,
+                    Some("fn create() -> Self\n    where\n        Self: Sized;")
+                ),
+                (
+                    "poll",
+                    false,
+                    Some("fn poll(self: Pin<&mut Self>) -> bool;")
+                ),
+                (
+                    "finish",
+                    false,
+                    Some("fn finish(self)\n    where\n        Self: Sized,")
+                ),
+            ]
+        );
+
+        assert!(!file_unit.functions[0].is_associated_fn);
+    }
+
+    #[test]
+    fn test_unsafe_items() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_unsafe.rs").unwrap();
+        let traits: Vec<_> = file_unit
+            .traits
+            .iter()
+            .map(|t| (t.name.as_str(), t.is_unsafe))
+            .collect();
+        assert_eq!(traits, [("Zeroable", true), ("Reset", false)]);
+        let functions: Vec<_> = file_unit
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.is_unsafe))
+            .collect();
+        assert_eq!(
+            functions,
+            [("read", true), ("zeroed", true), ("first", false)]
+        );
+
+        let formatted = file_unit.traits[0]
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.starts_with("/// ⚠️ unsafe\n/// Types whose all-zero"));
+        assert!(formatted.ends_with("pub unsafe trait Zeroable { ... }"));
+
+        let formatted = file_unit.functions[0]
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.starts_with("/// ⚠️ unsafe\n/// Read a value"));
+        let formatted = file_unit.functions[2]
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(!formatted.contains("⚠️"));
+
+        // The marker is only added to summaries, other strategies show the source
+        let formatted = file_unit.functions[0]
+            .format(&BankStrategy::NoTests, LanguageType::Rust)
+            .unwrap();
+        assert!(!formatted.contains("⚠️"));
+    }
+
+    #[test]
+    fn test_const_fn() {
+        let file_unit = parse_fixture("sample_const_fn.rs").unwrap();
+
+        let functions: Vec<_> = file_unit
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.is_const))
+            .collect();
+        assert_eq!(
+            functions,
+            [("max_size", true), ("runtime_size", false), ("read", true)]
+        );
+        assert_eq!(
+            file_unit.functions[0].signature.as_deref(),
+            Some("pub const fn max_size() -> usize")
+        );
+        assert_eq!(
+            file_unit.functions[2].signature.as_deref(),
+            Some("pub const unsafe fn read(ptr: *const u8) -> u8")
+        );
+
+        let methods = &file_unit.impls[0].methods;
+        assert!(methods[0].is_const);
+        assert_eq!(
+            methods[0].signature.as_deref(),
+            Some("pub const fn new() -> Self")
+        );
+        assert!(!methods[1].is_const);
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        let source = "/// Parsed before the error\npub fn valid() -> u32 {\n    1\n}\n\npub fn broken(x: u32 {\n    x\n}\n";
+        let mut parser = RustParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("broken.rs")).unwrap();
+
+        assert_eq!(file_unit.functions[0].name, "valid");
+        assert_eq!(
+            file_unit.functions[0].doc.as_deref(),
+            Some("Parsed before the error")
+        );
+
+        assert!(!file_unit.parse_errors.is_empty());
+        let (span, message) = &file_unit.parse_errors[0];
+        assert_eq!(span.start_line, 6);
+        assert_eq!(message, "missing `)`");
+
+        let file_unit = parse_fixture("sample.rs").unwrap();
+        assert!(file_unit.parse_errors.is_empty());
+    }
+
+    #[test]
+    fn test_parse_item_defining_macros() {
+        use crate::{BankStrategy, DeclareKind, Formatter};
+
+        let file_unit = parse_fixture("sample_item_macros.rs").unwrap();
+        let items: Vec<_> = file_unit
+            .declares
+            .iter()
+            .filter(|decl| decl.kind == DeclareKind::Other("macro_item".to_string()))
+            .collect();
+        assert_eq!(items.len(), 2);
+        assert!(items[0].source.starts_with("bitflags! {"));
+        assert!(items[0].source.contains("pub struct Permissions: u32"));
+        assert!(items[1].source.starts_with("lazy_static! {"));
+        assert!(
+            !file_unit
+                .declares
+                .iter()
+                .any(|decl| decl.source.contains("println!"))
+        );
+
+        let module = &file_unit.modules[0];
+        assert_eq!(
+            module.declares[0].source,
+            "bitflags::bitflags!(pub struct Mode: u8 { const FAST = 1; });"
+        );
+
+        let formatted = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(formatted.contains("pub struct Permissions: u32 {"));
+        assert!(formatted.contains("pub static ref DEFAULTS"));
+    }
+
+    #[test]
+    fn test_visibility_edge_cases() {
+        assert_eq!(visibility_from_modifier("pub(self)"), Visibility::Private);
+        assert_eq!(
+            visibility_from_modifier("pub(in self)"),
+            Visibility::Private
+        );
+        assert_eq!(visibility_from_modifier("pub()"), Visibility::Private);
+        assert_eq!(visibility_from_modifier("pub(crate"), Visibility::Private);
+        assert_eq!(visibility_from_modifier("pub(in crate)"), Visibility::Crate);
+        assert_eq!(visibility_from_modifier("pub( crate )"), Visibility::Crate);
+        assert_eq!(
+            visibility_from_modifier("pub(super)"),
+            Visibility::Restricted("pub(super)".to_string())
+        );
+        assert_eq!(
+            visibility_from_modifier("pub(in crate::parser)"),
+            Visibility::Restricted("pub(in crate::parser)".to_string())
+        );
+
+        let source = "pub(self) fn f() {}\npub(in self) fn g() {}\npub(super) fn h() {}\n";
+        let mut parser = RustParser::try_new().unwrap();
+        let file_unit = parser.parse_str(source, Path::new("vis.rs")).unwrap();
+        let visibilities: Vec<_> = file_unit
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.visibility.clone()))
+            .collect();
+        assert_eq!(
+            visibilities,
+            vec![
+                ("f", Visibility::Private),
+                ("g", Visibility::Private),
+                ("h", Visibility::Restricted("pub(super)".to_string())),
+            ]
+        );
+
+        // Malformed modifiers must not panic, whatever the grammar recovers
+        let file_unit = parser
+            .parse_str("pub() fn broken() {}\n", Path::new("broken.rs"))
+            .unwrap();
+        assert!(
+            file_unit
+                .functions
+                .iter()
+                .all(|f| f.visibility == Visibility::Private)
+        );
+    }
+
+    #[test]
+    fn test_parse_static_mut_and_thread_local() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_statics.rs").unwrap();
+        let names: Vec<_> = file_unit.consts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["GREETING", "COUNTER", "PRIVATE_COUNTER", "DEPTH", "BUFFER"]
+        );
+        assert!(
+            file_unit
+                .declares
+                .iter()
+                .all(|d| !d.source.contains("static"))
+        );
+
+        let counter = &file_unit.consts[1];
+        assert!(counter.is_static && counter.is_mut && !counter.is_thread_local);
+        assert_eq!(counter.visibility, Visibility::Public);
+        assert_eq!(counter.doc.as_deref(), Some("Global counter"));
+        assert_eq!(counter.type_annotation.as_deref(), Some("usize"));
+        assert_eq!(file_unit.consts[2].visibility, Visibility::Private);
+
+        let depth = &file_unit.consts[3];
+        assert!(depth.is_thread_local && !depth.is_mut);
+        assert_eq!(depth.type_annotation.as_deref(), Some("Cell<u32>"));
+        assert_eq!(file_unit.consts[4].visibility, Visibility::Private);
+
+        let registry = &file_unit.modules[0];
+        assert_eq!(registry.consts.len(), 1);
+        assert_eq!(registry.consts[0].name, "NAMES");
+        assert!(registry.consts[0].is_thread_local);
+
+        let summary = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(summary.contains("/// Global counter\npub static mut COUNTER: usize;\n"));
+        assert!(summary.contains("thread_local! {\n    pub static DEPTH: Cell<u32>;\n}"));
+        assert!(
+            summary
+                .contains("    thread_local! {\n        pub static NAMES: RefCell<Vec<String>>;")
+        );
+        assert!(!summary.contains("PRIVATE_COUNTER"));
+        assert!(!summary.contains("BUFFER"));
+
+        let no_tests = file_unit
+            .format(&BankStrategy::NoTests, LanguageType::Rust)
+            .unwrap();
+        assert!(no_tests.contains("static mut PRIVATE_COUNTER: u64 = 0;"));
+        assert!(no_tests.contains(
+            "thread_local! {\n    static BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::new());\n}"
+        ));
+    }
+
+    #[test]
+    fn test_parse_consts_and_statics() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_consts.rs").unwrap();
+        let names: Vec<_> = file_unit.consts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["MAX_RETRIES", "GREETING", "BUFFER_SIZE", "TABLE", "COUNTER"]
+        );
+        assert!(file_unit.consts[4].is_mut);
+
+        let max_retries = &file_unit.consts[0];
+        assert_eq!(max_retries.visibility, Visibility::Public);
+        assert_eq!(
+            max_retries.doc.as_deref(),
+            Some("The maximum number of retries")
+        );
+        assert_eq!(max_retries.type_annotation.as_deref(), Some("u32"));
+        assert_eq!(max_retries.value.as_deref(), Some("3"));
+        assert!(!max_retries.is_static);
+
+        let greeting = &file_unit.consts[1];
+        assert!(greeting.is_static);
+        assert_eq!(greeting.attributes, ["#[allow(dead_code)]"]);
+        assert_eq!(greeting.doc.as_deref(), Some("The default greeting"));
+        assert_eq!(file_unit.consts[2].visibility, Visibility::Private);
+        assert_eq!(file_unit.consts[3].visibility, Visibility::Crate);
+
+        let limits = &file_unit.modules[0];
+        let names: Vec<_> = limits.consts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["MAX_PAYLOAD", "INTERNAL"]);
+
+        let summary = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(summary.contains(
+            "/// The default greeting\n#[allow(dead_code)]\npub static GREETING: &str;\n"
+        ));
+        assert!(summary.contains("pub const MAX_RETRIES: u32;\n"));
+        assert!(summary.contains("pub static mut COUNTER: usize;\n"));
+        assert!(summary.contains("    pub const MAX_PAYLOAD: usize;\n"));
+        assert!(!summary.contains("BUFFER_SIZE"));
+        assert!(!summary.contains("TABLE"));
+        assert!(!summary.contains("INTERNAL"));
+
+        let no_tests = file_unit
+            .format(&BankStrategy::NoTests, LanguageType::Rust)
+            .unwrap();
+        assert!(no_tests.contains("pub const MAX_RETRIES: u32 = 3;"));
+        assert!(no_tests.contains("const BUFFER_SIZE: usize = 4 * 1024;"));
+        assert!(no_tests.contains("    static INTERNAL: u8 = 0;"));
+    }
+
+    #[test]
+    fn test_parse_type_aliases() {
+        use crate::{BankStrategy, Formatter};
+
+        let file_unit = parse_fixture("sample_type_aliases.rs").unwrap();
+        let names: Vec<_> = file_unit
+            .type_aliases
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, ["Result", "Callback", "Registry", "Cache"]);
+
+        let result = &file_unit.type_aliases[0];
+        assert_eq!(result.visibility, Visibility::Public);
+        assert_eq!(
+            result.doc.as_deref(),
+            Some("The result of the crate's operations")
+        );
+        assert_eq!(result.generics.as_deref(), Some("<T, E = Error>"));
+        assert_eq!(
+            result.aliased_type.as_deref(),
+            Some("std::result::Result<T, E>")
+        );
+
+        let callback = &file_unit.type_aliases[1];
+        assert_eq!(callback.attributes, ["#[allow(type_alias_bounds)]"]);
+        assert_eq!(callback.aliased_type.as_deref(), Some("Box<F>"));
+        let registry = &file_unit.type_aliases[2];
+        assert_eq!(registry.generics.as_deref(), Some("<K, V>"));
+        assert_eq!(registry.aliased_type.as_deref(), Some("HashMap<K, V>"));
+        assert_eq!(file_unit.type_aliases[3].visibility, Visibility::Private);
+
+        let ids = &file_unit.modules[0];
+        let names: Vec<_> = ids.type_aliases.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["UserId", "RawId"]);
+        assert_eq!(ids.type_aliases[1].visibility, Visibility::Crate);
+
+        // Summary keeps the whole alias of public items, where clauses included
+        let summary = file_unit
+            .format(&BankStrategy::Summary, LanguageType::Rust)
+            .unwrap();
+        assert!(summary.contains(
+            "/// The result of the crate's operations\npub type Result<T, E = Error> = std::result::Result<T, E>;\n"
+        ));
+        assert!(summary.contains(
+            "#[allow(type_alias_bounds)]\npub type Callback<F>\nwhere\n    F: Fn(u32) -> bool,\n= Box<F>;\n"
+        ));
+        assert!(
+            summary.contains(
+                "pub type Registry<K, V> = HashMap<K, V> where K: std::hash::Hash + Eq;\n"
+            )
+        );
+        assert!(summary.contains("    pub type UserId = u64;\n"));
+        assert!(!summary.contains("Cache"));
+        assert!(!summary.contains("RawId"));
+
+        for strategy in [BankStrategy::NoTests, BankStrategy::Default] {
+            let formatted = file_unit.format(&strategy, LanguageType::Rust).unwrap();
+            assert!(formatted.contains("type Cache = HashMap<String, Vec<u8>>;"));
+            assert!(formatted.contains("pub(crate) type RawId = [u8; 16];"));
+        }
     }
 }
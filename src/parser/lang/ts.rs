@@ -1,11 +1,13 @@
+use super::{collect_parse_errors, parse_tree, timeout_micros};
 use crate::{
-    DeclareKind, DeclareStatements, Error, FieldUnit, FileUnit, FunctionUnit, LanguageParser,
-    Result, StructUnit, TypeScriptParser, Visibility,
+    DeclareKind, DeclareStatements, Deprecation, Error, FieldUnit, FileUnit, FunctionUnit,
+    LanguageParser, ParamUnit, Result, StructUnit, TypeScriptParser, Visibility,
 };
 use std::{
     fs,
     ops::{Deref, DerefMut},
     path::Path,
+    time::Duration,
 };
 use tree_sitter::{Node, Parser};
 
@@ -94,6 +96,7 @@ impl TypeScriptParser {
             // Extract function signature
             let mut signature = String::from("function ");
             signature.push_str(&name);
+            signature.push_str(type_parameters_text(node, source));
 
             // Add parameters
             if let Some(params_node) = node.child_by_field_name("parameters") {
@@ -105,14 +108,23 @@ impl TypeScriptParser {
                 signature.push_str(return_type.utf8_text(source).unwrap_or(""));
             }
 
+            let deprecation = deprecation_from_doc(documentation.as_deref());
             file_unit.functions.push(FunctionUnit {
                 name,
                 source: Some(func_source),
                 visibility,
                 doc: documentation,
                 signature: Some(signature),
+                return_type: return_type_text(node, source),
+                params: params(node, source),
                 body: None,
                 attributes: vec![],
+                deprecation,
+                is_must_use: false,
+                must_use_note: None,
+                is_const: false,
+                is_unsafe: false,
+                is_associated_fn: false,
             });
         }
     }
@@ -140,6 +152,8 @@ impl TypeScriptParser {
 
             // Find the function value (arrow function or function expression)
             let mut signature = None;
+            let mut return_type = None;
+            let mut parameters = Vec::new();
 
             if let Some(value_node) = var_node.child_by_field_name("value") {
                 if value_node.kind() == "arrow_function"
@@ -150,6 +164,7 @@ impl TypeScriptParser {
                     // For arrow functions, use the variable name and add parameters
                     if value_node.kind() == "arrow_function" {
                         sig.push_str(&name);
+                        sig.push_str(type_parameters_text(value_node, source));
 
                         // Add parameters
                         if let Some(params_node) = value_node.child_by_field_name("parameters") {
@@ -166,6 +181,7 @@ impl TypeScriptParser {
                         // For function expressions, format as "function name(params)"
                         sig.push_str("function ");
                         sig.push_str(&name);
+                        sig.push_str(type_parameters_text(value_node, source));
 
                         // Add parameters
                         if let Some(params_node) = value_node.child_by_field_name("parameters") {
@@ -179,17 +195,28 @@ impl TypeScriptParser {
                     }
 
                     signature = Some(sig);
+                    return_type = return_type_text(value_node, source);
+                    parameters = params(value_node, source);
                 }
             }
 
+            let deprecation = deprecation_from_doc(documentation.as_deref());
             file_unit.functions.push(FunctionUnit {
                 name,
                 source: Some(func_source),
                 visibility,
                 doc: documentation,
                 signature,
+                return_type,
+                params: parameters,
                 body: None,
                 attributes: vec![],
+                deprecation,
+                is_must_use: false,
+                must_use_note: None,
+                is_const: false,
+                is_unsafe: false,
+                is_associated_fn: false,
             });
         }
     }
@@ -262,8 +289,9 @@ impl TypeScriptParser {
                                         }
                                     }
 
-                                    // Add method name
+                                    // Add method name and its generic clause
                                     signature.push_str(&method_name);
+                                    signature.push_str(type_parameters_text(method_node, source));
                                 }
 
                                 // Add parameters
@@ -289,8 +317,16 @@ impl TypeScriptParser {
                                     visibility: method_visibility,
                                     doc: None, // Could extract doc comments for methods too
                                     signature: Some(signature),
+                                    return_type: return_type_text(method_node, source),
+                                    params: params(method_node, source),
                                     body: None,
                                     attributes: vec![],
+                                    deprecation: None,
+                                    is_must_use: false,
+                                    must_use_note: None,
+                                    is_const: false,
+                                    is_unsafe: false,
+                                    is_associated_fn: false,
                                 });
                             }
                         }
@@ -307,6 +343,8 @@ impl TypeScriptParser {
                                 fields.push(FieldUnit {
                                     name: field_name,
                                     source: Some(field_source),
+                                    discriminant: None,
+                                    visibility: Visibility::Public,
                                     doc: field_doc,
                                     attributes: vec![],
                                 });
@@ -316,15 +354,19 @@ impl TypeScriptParser {
                 }
             }
 
+            let deprecation = deprecation_from_doc(documentation.as_deref());
             file_unit.structs.push(StructUnit {
                 name: name.clone(),
                 source: Some(class_source),
-                head: format!("class {}", name),
+                head: format!("class {}{}", name, type_parameters_text(node, source)),
                 visibility,
                 doc: documentation,
                 fields,
                 methods,
                 attributes: vec![],
+                deprecation,
+                is_must_use: false,
+                must_use_note: None,
             });
         }
     }
@@ -372,6 +414,7 @@ impl TypeScriptParser {
 
                                 // Interface methods are public by default in TypeScript
                                 signature.push_str(&method_name);
+                                signature.push_str(type_parameters_text(method_node, source));
 
                                 // Add parameters
                                 if let Some(params_node) =
@@ -396,8 +439,16 @@ impl TypeScriptParser {
                                     visibility: Visibility::Public,
                                     doc: None,
                                     signature: Some(signature),
+                                    return_type: return_type_text(method_node, source),
+                                    params: params(method_node, source),
                                     body: None,
                                     attributes: vec![],
+                                    deprecation: None,
+                                    is_must_use: false,
+                                    must_use_note: None,
+                                    is_const: false,
+                                    is_unsafe: false,
+                                    is_associated_fn: false,
                                 });
                             }
                         } else if method_node.kind() == "property_signature" {
@@ -411,6 +462,8 @@ impl TypeScriptParser {
                                 fields.push(FieldUnit {
                                     name: field_name,
                                     source: Some(field_source),
+                                    discriminant: None,
+                                    visibility: Visibility::Public,
                                     doc: field_doc,
                                     attributes: vec![],
                                 });
@@ -420,15 +473,19 @@ impl TypeScriptParser {
                 }
             }
 
+            let deprecation = deprecation_from_doc(documentation.as_deref());
             file_unit.structs.push(StructUnit {
                 name: name.clone(),
                 source: Some(interface_source),
-                head: format!("interface {}", name),
+                head: format!("interface {}{}", name, type_parameters_text(node, source)),
                 visibility,
                 doc: documentation,
                 fields,
                 methods,
                 attributes: vec![],
+                deprecation,
+                is_must_use: false,
+                must_use_note: None,
             });
         }
     }
@@ -453,15 +510,19 @@ impl TypeScriptParser {
             // Check for documentation
             let documentation = find_documentation_for_node(node, source);
 
+            let deprecation = deprecation_from_doc(documentation.as_deref());
             file_unit.structs.push(StructUnit {
                 name: name.clone(),
                 source: Some(type_source),
-                head: format!("type {}", name),
+                head: format!("type {}{}", name, type_parameters_text(node, source)),
                 visibility,
                 doc: documentation,
                 methods: vec![],
                 fields: Vec::new(),
                 attributes: vec![],
+                deprecation,
+                is_must_use: false,
+                must_use_note: None,
             });
         }
     }
@@ -480,6 +541,7 @@ impl TypeScriptParser {
             // Check for documentation
             let documentation = find_documentation_for_node(node, source);
 
+            let deprecation = deprecation_from_doc(documentation.as_deref());
             file_unit.structs.push(StructUnit {
                 name: name.clone(),
                 source: Some(enum_source),
@@ -489,6 +551,9 @@ impl TypeScriptParser {
                 methods: vec![],
                 fields: Vec::new(),
                 attributes: vec![],
+                deprecation,
+                is_must_use: false,
+                must_use_note: None,
             });
         }
     }
@@ -496,6 +561,74 @@ impl TypeScriptParser {
 
 // --- Helper Functions ---
 
+// Helper to read a JSDoc `@deprecated` tag and its optional note from a doc comment
+fn deprecation_from_doc(doc: Option<&str>) -> Option<Deprecation> {
+    let line = doc
+        .into_iter()
+        .flat_map(str::lines)
+        .map(str::trim)
+        .find(|line| line.starts_with("@deprecated"))?;
+    let note = line.trim_start_matches("@deprecated").trim();
+    Some(Deprecation {
+        since: None,
+        note: (!note.is_empty()).then(|| note.to_string()),
+    })
+}
+
+// Helper to get the generic clause of a declaration, e.g. `<T extends Base = Default>`
+fn type_parameters_text<'a>(node: Node, source: &'a [u8]) -> &'a str {
+    node.child_by_field_name("type_parameters")
+        .and_then(|params| params.utf8_text(source).ok())
+        .unwrap_or("")
+}
+
+// The return type of a function or method without its `:`, e.g. `Promise<User>`
+fn return_type_text(node: Node, source: &[u8]) -> Option<String> {
+    let annotation = node
+        .child_by_field_name("return_type")?
+        .utf8_text(source)
+        .ok()?;
+    Some(annotation.trim_start_matches(':').trim().to_string())
+}
+
+// The parameters of a function or method, e.g. `name: string` or `this: Window`. A
+// parameter without annotation has an empty type, including the bare one of `x => x`
+fn params(node: Node, source: &[u8]) -> Vec<ParamUnit> {
+    let text = |node: Node| node.utf8_text(source).unwrap_or("").to_string();
+    if let Some(parameter) = node.child_by_field_name("parameter") {
+        return vec![ParamUnit {
+            name: Some(text(parameter)),
+            ty: String::new(),
+            is_self: false,
+        }];
+    }
+    let Some(parameters) = node.child_by_field_name("parameters") else {
+        return Vec::new();
+    };
+    parameters
+        .named_children(&mut parameters.walk())
+        .filter(|parameter| {
+            matches!(
+                parameter.kind(),
+                "required_parameter" | "optional_parameter"
+            )
+        })
+        .map(|parameter| {
+            let name = parameter.child_by_field_name("pattern").map(text);
+            let ty = parameter.child_by_field_name("type").map(text);
+            ParamUnit {
+                is_self: name.as_deref() == Some("this"),
+                name,
+                ty: ty
+                    .unwrap_or_default()
+                    .trim_start_matches(':')
+                    .trim()
+                    .to_string(),
+            }
+        })
+        .collect()
+}
+
 // Helper to find documentation for a node
 fn find_documentation_for_node(node: Node, source: &[u8]) -> Option<String> {
     let mut current_node = node;
@@ -595,6 +728,31 @@ fn extract_doc_comment(node: Node, source: &[u8]) -> Option<String> {
     }
 }
 
+/// Collects the local names listed in an `export { ... }` clause.
+///
+/// For `export { a as b }` the local name `a` is recorded, since that is the
+/// name the declaration is known by in this file. Names are only recorded once.
+fn collect_exported_names(node: Node, source: &[u8], names: &mut Vec<String>) {
+    let Some(clause) = node
+        .children(&mut node.walk())
+        .find(|child| child.kind() == "export_clause")
+    else {
+        return;
+    };
+
+    for specifier in clause.children(&mut clause.walk()) {
+        if specifier.kind() != "export_specifier" {
+            continue;
+        }
+        if let Some(name_node) = specifier.child_by_field_name("name") {
+            let name = name_node.utf8_text(source).unwrap_or("").trim().to_string();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+}
+
 /// Finds the next non-comment, non-extra sibling node.
 #[allow(dead_code)]
 fn find_next_sibling_node(node: Node) -> Option<Node> {
@@ -652,9 +810,17 @@ impl DerefMut for TypeScriptParser {
 impl LanguageParser for TypeScriptParser {
     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
         let source_code = fs::read_to_string(file_path).map_err(Error::Io)?;
+        self.parse_str(&source_code, file_path)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.set_timeout_micros(timeout_micros(timeout));
+    }
+
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
         let source_bytes = source_code.as_bytes();
 
-        let tree = self.parser.parse(&source_code, None).ok_or_else(|| {
+        let tree = parse_tree(&mut self.parser, source_bytes, || {
             Error::Parse(format!(
                 "Tree-sitter failed to parse the file: {}",
                 file_path.display()
@@ -663,7 +829,8 @@ impl LanguageParser for TypeScriptParser {
 
         let mut file_unit = FileUnit {
             path: file_path.to_path_buf(),
-            source: Some(source_code.clone()),
+            source: Some(source_code.to_string()),
+            parse_errors: collect_parse_errors(tree.root_node(), source_code),
             ..Default::default()
         };
 
@@ -686,31 +853,20 @@ impl LanguageParser for TypeScriptParser {
             if let Some(node) = root_node.child(i) {
                 if node.kind() == "export_statement" {
                     // Direct exports should already be handled by parent check later, so focus on export blocks
-                    let node_text = node.utf8_text(source_bytes).unwrap_or("");
-
-                    // Handle named exports format: export { Name1, Name2 }
-                    if node_text.contains("{") && node_text.contains("}") {
-                        // Basic parsing of export statement text to extract names
-                        // For more complex cases, a proper structured parsing approach would be better
-                        if let Some(content) = node_text.split('{').nth(1) {
-                            if let Some(items) = content.split('}').next() {
-                                for item in items.split(',') {
-                                    let name = item.trim();
-                                    if !name.is_empty() {
-                                        exported_names.push(name.to_string());
-                                    }
-                                }
-                            }
-                        }
+                    // Handle named exports format: export { Name1, Name2 as Alias }
+                    // Re-exports with a `from` source refer to another module's items,
+                    // so they must not mark local declarations as exported.
+                    if node.child_by_field_name("source").is_none() {
+                        collect_exported_names(node, source_bytes, &mut exported_names);
                     }
 
-                    // Handle export default Name
-                    if node_text.starts_with("export default") {
-                        let parts: Vec<&str> = node_text.split_whitespace().collect();
-                        if parts.len() >= 3 {
-                            let default_name = parts[2].trim_end_matches(';').to_string();
-                            default_export_name = Some(default_name);
-                        }
+                    // Handle export default Name (declarations are handled by process_export)
+                    if let Some(value_node) = node
+                        .child_by_field_name("value")
+                        .filter(|value| value.kind() == "identifier")
+                    {
+                        default_export_name =
+                            Some(value_node.utf8_text(source_bytes).unwrap_or("").to_string());
                     }
                 }
             }
@@ -988,6 +1144,60 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_return_types() -> Result<()> {
+        let ts_code = r#"
+        export async function getUser(id: string): Promise<User> {
+            return fetchUser(id);
+        }
+
+        export const toName = (user: User): string => user.name;
+
+        function log(message: string) {}
+
+        export class UserService {
+            findAll(): User[] {
+                return [];
+            }
+        }
+
+        export interface UserStore {
+            get(id: string): User | undefined;
+        }
+        "#;
+
+        let file_unit = parse_ts_str(ts_code)?;
+
+        let return_types: Vec<_> = file_unit
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.return_type.as_deref()))
+            .collect();
+        assert_eq!(
+            return_types,
+            [
+                ("getUser", Some("Promise<User>")),
+                ("toName", Some("string")),
+                ("log", None),
+            ]
+        );
+        assert_eq!(
+            file_unit.functions[0].signature.as_deref(),
+            Some("function getUser(id: string): Promise<User>")
+        );
+        assert_eq!(
+            file_unit.structs[0].methods[0].return_type.as_deref(),
+            Some("User[]")
+        );
+        // Interfaces are parsed as structs
+        assert_eq!(
+            file_unit.structs[1].methods[0].return_type.as_deref(),
+            Some("User | undefined")
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_interface() -> Result<()> {
         let ts_code = r#"
@@ -1550,6 +1760,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_declared_then_exported_function() -> Result<()> {
+        let ts_code = r#"
+        function f() {
+            return 1;
+        }
+
+        export { f };
+        "#;
+
+        let file_unit = parse_ts_str(ts_code)?;
+
+        assert_eq!(file_unit.functions.len(), 1);
+        assert_eq!(file_unit.functions[0].name, "f");
+        assert_eq!(file_unit.functions[0].visibility, Visibility::Public);
+        assert_eq!(file_unit.declares.len(), 1); // The export statement itself
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aliased_and_reexported_names() -> Result<()> {
+        let ts_code = r#"
+        function local() {}
+        function shadowed() {}
+        class Widget {}
+
+        export { local as renamed, Widget };
+        export { shadowed } from './elsewhere';
+        "#;
+
+        let file_unit = parse_ts_str(ts_code)?;
+
+        assert_eq!(file_unit.functions.len(), 2);
+        assert_eq!(file_unit.structs.len(), 1);
+
+        let local = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "local")
+            .unwrap();
+        assert_eq!(local.visibility, Visibility::Public);
+
+        // Re-exporting from another module doesn't export the local declaration
+        let shadowed = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "shadowed")
+            .unwrap();
+        assert_eq!(shadowed.visibility, Visibility::Private);
+
+        assert_eq!(file_unit.structs[0].visibility, Visibility::Public);
+
+        Ok(())
+    }
+
     #[test]
     fn test_class_with_fields() -> Result<()> {
         let ts_code = r#"
@@ -1684,4 +1950,211 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_generic_clauses_in_heads_and_signatures() -> Result<()> {
+        use crate::{BankStrategy, Formatter, LanguageType};
+
+        let ts_code = r#"
+        export class Repo<T extends Entity = User> {
+          find<K extends keyof T>(key: K): T[K] {
+            return this.items[key];
+          }
+        }
+
+        export interface Store<T = string> {
+          get<K extends string>(key: K): T;
+        }
+
+        export type Mapper<T, U extends T = T> = (value: T) => U;
+
+        export function identity<T extends object = {}>(value: T): T {
+          return value;
+        }
+
+        export const wrap = <T,>(value: T): T[] => [value];
+        "#;
+
+        let file_unit = parse_ts_str(ts_code)?;
+
+        let repo = file_unit.structs.iter().find(|s| s.name == "Repo").unwrap();
+        assert_eq!(repo.head, "class Repo<T extends Entity = User>");
+        assert_eq!(
+            repo.methods[0].signature.as_deref(),
+            Some("find<K extends keyof T>(key: K): T[K]")
+        );
+
+        let store = file_unit
+            .structs
+            .iter()
+            .find(|s| s.name == "Store")
+            .unwrap();
+        assert_eq!(store.head, "interface Store<T = string>");
+        assert_eq!(
+            store.methods[0].signature.as_deref(),
+            Some("get<K extends string>(key: K): T")
+        );
+
+        let mapper = file_unit
+            .structs
+            .iter()
+            .find(|s| s.name == "Mapper")
+            .unwrap();
+        assert_eq!(mapper.head, "type Mapper<T, U extends T = T>");
+
+        let identity = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "identity")
+            .unwrap();
+        assert_eq!(
+            identity.signature.as_deref(),
+            Some("function identity<T extends object = {}>(value: T): T")
+        );
+
+        let wrap = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "wrap")
+            .unwrap();
+        assert_eq!(wrap.signature.as_deref(), Some("wrap<T,>(value: T): T[]"));
+
+        // The full generic clause survives in the Summary output
+        let summary = repo.format(&BankStrategy::Summary, LanguageType::TypeScript)?;
+        assert!(summary.contains("class Repo<T extends Entity = User>"));
+        assert!(summary.contains("find<K extends keyof T>(key: K): T[K]"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_params() -> Result<()> {
+        let ts_code = r#"
+        export function greet(this: Window, name: string, times?: number, ...rest: string[]) {}
+
+        export const double = x => x * 2;
+
+        export class Counter {
+            add(count: number, label = "total"): void {}
+        }
+        "#;
+
+        let file_unit = parse_ts_str(ts_code)?;
+        let params = |function: &FunctionUnit| -> Vec<_> {
+            function
+                .params
+                .iter()
+                .map(|p| (p.name.clone().unwrap_or_default(), p.ty.clone(), p.is_self))
+                .collect()
+        };
+        let param = |name: &str, ty: &str, is_self| (name.to_string(), ty.to_string(), is_self);
+        assert_eq!(
+            params(&file_unit.functions[0]),
+            [
+                param("this", "Window", true),
+                param("name", "string", false),
+                param("times", "number", false),
+                param("...rest", "string[]", false),
+            ]
+        );
+        assert_eq!(params(&file_unit.functions[1]), [param("x", "", false)]);
+        assert_eq!(
+            params(&file_unit.structs[0].methods[0]),
+            [param("count", "number", false), param("label", "", false)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_methods_in_summary() -> Result<()> {
+        use crate::{BankStrategy, FormatOptions, Formatter, LanguageType};
+
+        let ts_code = r#"
+        export class Greeter {
+          name: string;
+
+          constructor(name: string) {
+            this.name = name;
+          }
+
+          greet(greeting: string): string {
+            return `${greeting}, ${this.name}`;
+          }
+
+          private secret(): void {}
+        }
+        "#;
+
+        let file_unit = parse_ts_str(ts_code)?;
+        let greeter = &file_unit.structs[0];
+
+        // Methods are rendered inside the class body, after the fields
+        let summary = greeter.format(&BankStrategy::Summary, LanguageType::TypeScript)?;
+        assert_eq!(
+            summary,
+            "class Greeter{\n    name: string,\n    constructor(name: string) { ... }\n    greet(greeting: string): string { ... }\n}"
+        );
+
+        // Also when the fields are elided
+        let options = FormatOptions {
+            elide_large_structs: Some(0),
+            ..Default::default()
+        };
+        let summary = greeter.format_with_options(
+            &BankStrategy::Summary,
+            LanguageType::TypeScript,
+            &options,
+        )?;
+        assert!(summary.starts_with("class Greeter{\n    // 1 field\n    constructor("));
+        assert!(summary.ends_with("{ ... }\n}"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_deprecated_items() -> Result<()> {
+        let ts_code = r#"
+        /**
+         * Formats a date.
+         * @deprecated Use formatDateTime instead
+         */
+        export function formatDate(date: Date): string {
+          return date.toString();
+        }
+
+        /** @deprecated */
+        export class OldClient {}
+
+        export function formatDateTime(date: Date): string {
+          return date.toISOString();
+        }
+        "#;
+
+        let file_unit = parse_ts_str(ts_code)?;
+
+        let format_date = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "formatDate")
+            .unwrap();
+        assert_eq!(
+            format_date
+                .deprecation
+                .as_ref()
+                .and_then(|deprecation| deprecation.note.as_deref()),
+            Some("Use formatDateTime instead")
+        );
+
+        let old_client = &file_unit.structs[0];
+        assert_eq!(old_client.deprecation, Some(Deprecation::default()));
+
+        let format_date_time = file_unit
+            .functions
+            .iter()
+            .find(|f| f.name == "formatDateTime")
+            .unwrap();
+        assert!(format_date_time.deprecation.is_none());
+
+        Ok(())
+    }
 }
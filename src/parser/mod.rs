@@ -1,12 +1,28 @@
+pub(crate) mod attributes;
+pub(crate) mod exclude;
 pub mod formatter;
 mod lang;
+mod paths;
+mod registry;
 mod units;
+mod use_tree;
 
 use crate::Result;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-
-pub use formatter::Formatter;
-pub use lang::{CppParser, GoParser, PythonParser, RustParser, TypeScriptParser};
+use std::time::Duration;
+
+pub use attributes::{Attribute, parse_attribute};
+pub use exclude::SKIP_ATTRIBUTE;
+pub use formatter::{FormatOptions, Formatter};
+pub(crate) use lang::visibility_from_modifier;
+pub use lang::{
+    CSharpParser, CppParser, DEFAULT_ITEM_MACROS, GoParser, PhpParser, PythonParser, RustParser,
+    TypeScriptParser,
+};
+pub use paths::{DEFAULT_SHORTEN_PREFIXES, shorten_paths};
+pub use registry::{ParserFactory, ParserRegistry};
+pub use use_tree::{UsePath, parse_use_tree};
 
 /// Represents visibility levels for code elements.
 ///
@@ -34,7 +50,11 @@ pub use lang::{CppParser, GoParser, PythonParser, RustParser, TypeScriptParser};
 /// let vis = Visibility::Restricted("super::module".to_string());
 /// assert!(matches!(vis, Visibility::Restricted(_)));
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+///
+/// When serialized, visibility uses a language-agnostic tagged form such as
+/// `{"kind":"public"}` or `{"kind":"restricted","path":"super::module"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "path", rename_all = "snake_case")]
 pub enum Visibility {
     /// Public visibility (accessible from outside the module)
     #[default]
@@ -75,6 +95,12 @@ pub enum Visibility {
 /// // Check Go files
 /// assert!(matches!(LanguageType::Go, LanguageType::Go));
 ///
+/// // Check C# files
+/// assert!(matches!(LanguageType::CSharp, LanguageType::CSharp));
+///
+/// // Check PHP files
+/// assert!(matches!(LanguageType::Php, LanguageType::Php));
+///
 /// // Handle unknown types
 /// assert!(matches!(LanguageType::Unknown, LanguageType::Unknown));
 /// ```
@@ -90,6 +116,10 @@ pub enum LanguageType {
     Cpp,
     /// Go language
     Go,
+    /// C# language
+    CSharp,
+    /// PHP language
+    Php,
     /// Unknown language (used for unsupported extensions)
     Unknown,
 }
@@ -124,6 +154,84 @@ pub enum LanguageType {
 pub trait LanguageParser {
     /// Parse a file into a FileUnit
     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit>;
+
+    /// Parse in-memory source code into a FileUnit, using `file_path` as its path.
+    ///
+    /// Parsers that only work on files can keep the default, which reports the
+    /// language as unsupported.
+    fn parse_str(&mut self, source_code: &str, file_path: &Path) -> Result<FileUnit> {
+        let _ = source_code;
+        Err(crate::Error::UnsupportedLanguage(format!(
+            "parsing in-memory source is not supported for {}",
+            file_path.display()
+        )))
+    }
+
+    /// Stop parsing a single file after `timeout`, failing the parse with
+    /// [`Error::ParseTimeout`](crate::Error::ParseTimeout), or never when `None`.
+    ///
+    /// Parsers that cannot be interrupted can keep the default, which ignores the timeout.
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        let _ = timeout;
+    }
+}
+
+/// The kind of a parsed code unit.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{CodeUnit, StructUnit, UnitKind};
+///
+/// let unit = StructUnit {
+///     name: "Color".to_string(),
+///     head: "pub enum Color".to_string(),
+///     ..Default::default()
+/// };
+/// assert_eq!(unit.kind(), UnitKind::Enum);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitKind {
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Module,
+    Const,
+    TypeAlias,
+    Macro,
+}
+
+/// Common accessors shared by all parsed code units, allowing generic traversal and filtering.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{CodeUnit, FunctionUnit, UnitKind, Visibility};
+///
+/// let function = FunctionUnit {
+///     name: "run".to_string(),
+///     visibility: Visibility::Public,
+///     doc: Some("Runs the task".to_string()),
+///     ..Default::default()
+/// };
+///
+/// let unit: &dyn CodeUnit = &function;
+/// assert_eq!(unit.name(), "run");
+/// assert_eq!(unit.visibility(), &Visibility::Public);
+/// assert_eq!(unit.doc(), Some("Runs the task"));
+/// assert_eq!(unit.kind(), UnitKind::Function);
+/// ```
+pub trait CodeUnit {
+    /// The name of the unit
+    fn name(&self) -> &str;
+    /// The visibility of the unit
+    fn visibility(&self) -> &Visibility;
+    /// The documentation of the unit, if any
+    fn doc(&self) -> Option<&str>;
+    /// The kind of the unit
+    fn kind(&self) -> UnitKind;
 }
 
 /// Represents a file in the code.
@@ -152,6 +260,7 @@ pub trait LanguageParser {
 ///     body: Some("{ println!(\"Hello\"); }".to_string()),
 ///     source: Some("fn example_function() { println!(\"Hello\"); }".to_string()),
 ///     attributes: vec![],
+///     ..Default::default()
 /// };
 /// file.functions.push(function);
 ///
@@ -159,7 +268,7 @@ pub trait LanguageParser {
 /// assert!(file.doc.is_some());
 /// assert!(!file.functions.is_empty());
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct FileUnit {
     /// The path to the file
     pub path: PathBuf,
@@ -185,8 +294,55 @@ pub struct FileUnit {
     /// Top-level implementation blocks
     pub impls: Vec<ImplUnit>,
 
+    /// Top-level macro definitions
+    pub macros: Vec<MacroUnit>,
+
+    /// Top-level constants and statics
+    pub consts: Vec<ConstUnit>,
+
+    /// Top-level type aliases
+    pub type_aliases: Vec<TypeAliasUnit>,
+
     /// Source code of the entire file
     pub source: Option<String>,
+
+    /// Syntax errors found while parsing, items in these regions may be missing
+    pub parse_errors: Vec<(Span, String)>,
+
+    /// The number of items removed by [`FileUnit::truncate_items`], noted in summaries
+    pub omitted_items: usize,
+}
+
+/// A region of a source file, lines and columns are 1-based.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::Span;
+///
+/// let span = Span { start_line: 3, start_column: 5, end_line: 3, end_column: 9 };
+/// assert_eq!(span.to_string(), "3:5-3:9");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Span {
+    /// Line of the first character
+    pub start_line: usize,
+    /// Column of the first character
+    pub start_column: usize,
+    /// Line of the end of the region
+    pub end_line: usize,
+    /// Column of the end of the region
+    pub end_column: usize,
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            self.start_line, self.start_column, self.end_line, self.end_column
+        )
+    }
 }
 
 /// Represents declarations in source code.
@@ -213,7 +369,7 @@ pub struct FileUnit {
 /// };
 /// assert!(matches!(module.kind, DeclareKind::Mod));
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct DeclareStatements {
     /// The source code of the declaration
     pub source: String,
@@ -244,7 +400,8 @@ pub struct DeclareStatements {
 /// let kind = DeclareKind::Other("macro_rules".to_string());
 /// assert!(matches!(kind, DeclareKind::Other(_)));
 /// ```
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DeclareKind {
     #[default]
     Import,
@@ -253,8 +410,18 @@ pub enum DeclareKind {
     Other(String),
 }
 
+/// The deprecation of an item, e.g. from `#[deprecated(since = "0.2.0", note = "use run")]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    /// The version the item was deprecated in
+    pub since: Option<String>,
+
+    /// The deprecation note, e.g. what to use instead
+    pub note: Option<String>,
+}
+
 /// Represents a module in the code
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct ModuleUnit {
     /// The name of the module
     pub name: String,
@@ -265,6 +432,10 @@ pub struct ModuleUnit {
     /// The document for the module
     pub doc: Option<String>,
 
+    /// The deprecation of the module, if it is marked deprecated, e.g. `#[deprecated]` or a
+    /// `@deprecated` tag
+    pub deprecation: Option<Deprecation>,
+
     /// The declares in the module, e.g. imports, use statements, mod statements, c includes, python/js imports, etc.
     pub declares: Vec<DeclareStatements>,
 
@@ -286,12 +457,21 @@ pub struct ModuleUnit {
     /// Sub-modules defined in the module
     pub submodules: Vec<ModuleUnit>,
 
+    /// Macros defined in the module
+    pub macros: Vec<MacroUnit>,
+
+    /// Constants and statics defined in the module
+    pub consts: Vec<ConstUnit>,
+
+    /// Type aliases defined in the module
+    pub type_aliases: Vec<TypeAliasUnit>,
+
     /// Source code of the module declaration
     pub source: Option<String>,
 }
 
 /// Represents a function or method in the code
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct FunctionUnit {
     /// The name of the function
     pub name: String,
@@ -305,9 +485,40 @@ pub struct FunctionUnit {
     /// The documentation for the function
     pub doc: Option<String>,
 
+    /// The deprecation of the function, if it is marked deprecated, e.g. `#[deprecated]` or a
+    /// `@deprecated` tag
+    pub deprecation: Option<Deprecation>,
+
+    /// Whether the result of the function must be used, e.g. `#[must_use]`
+    pub is_must_use: bool,
+
+    /// The reason given with `#[must_use = "..."]`, if any
+    pub must_use_note: Option<String>,
+
+    /// Whether the function can be evaluated at compile time, e.g. a Rust `const fn`
+    pub is_const: bool,
+
+    /// Whether the function is declared `unsafe` and has a safety contract for callers
+    pub is_unsafe: bool,
+
+    /// Whether the function belongs to a type or trait but takes no receiver, e.g.
+    /// `fn new() -> Self`, as opposed to methods taking `self` in any form, including
+    /// `self: Pin<&mut Self>`. Only the Rust parser sets it.
+    pub is_associated_fn: bool,
+
     /// The function signature (without body)
     pub signature: Option<String>,
 
+    /// The declared return type, e.g. `Promise<User>` or `Result<()>`, without the `:` or
+    /// `->` introducing it. Only the Rust and TypeScript parsers extract it, and the C++
+    /// parser for trailing return types such as `auto add(int a, int b) -> int`.
+    pub return_type: Option<String>,
+
+    /// The parameters of the function, the receiver included, e.g. `&self`, `name: &str`.
+    /// Only the Rust and TypeScript parsers extract them, the signature stays the canonical
+    /// form of the parameters.
+    pub params: Vec<ParamUnit>,
+
     /// The function body
     pub body: Option<String>,
 
@@ -315,8 +526,20 @@ pub struct FunctionUnit {
     pub source: Option<String>,
 }
 
+/// A parameter of a function, as written in its signature
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ParamUnit {
+    /// The name of the parameter, or its pattern such as `(a, b)`, `None` for `_` and
+    /// unnamed parameters
+    pub name: Option<String>,
+    /// The declared type, e.g. `&str`, empty for untyped parameters such as JavaScript ones
+    pub ty: String,
+    /// Whether the parameter is the receiver, e.g. `&mut self` or a TypeScript `this`
+    pub is_self: bool,
+}
+
 /// Represents a struct or class in the code
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct StructUnit {
     /// The name of the struct
     pub name: String,
@@ -330,6 +553,16 @@ pub struct StructUnit {
     /// The documentation for the struct
     pub doc: Option<String>,
 
+    /// The deprecation of the struct, if it is marked deprecated, e.g. `#[deprecated]` or a
+    /// `@deprecated` tag
+    pub deprecation: Option<Deprecation>,
+
+    /// Whether the struct must be used, e.g. `#[must_use]`
+    pub is_must_use: bool,
+
+    /// The reason given with `#[must_use = "..."]`, if any
+    pub must_use_note: Option<String>,
+
     /// struct head, e.g. struct Type, class Type, etc.
     pub head: String,
 
@@ -344,7 +577,7 @@ pub struct StructUnit {
 }
 
 /// Represents a field in a struct
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct FieldUnit {
     /// The name of the field
     pub name: String,
@@ -354,10 +587,15 @@ pub struct FieldUnit {
     pub attributes: Vec<String>,
     /// the source code of the field
     pub source: Option<String>,
+    /// the explicit value of an enum variant, e.g. `1` for `Active = 1`
+    pub discriminant: Option<String>,
+    /// the visibility of the field, only tracked by the Go parser from the case of its
+    /// name, other parsers leave it public and keep modifiers such as `pub` in the source
+    pub visibility: Visibility,
 }
 
 /// Represents a trait or interface in the code
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct TraitUnit {
     /// The name of the trait
     pub name: String,
@@ -371,6 +609,19 @@ pub struct TraitUnit {
     /// The documentation for the trait
     pub doc: Option<String>,
 
+    /// The deprecation of the trait, if it is marked deprecated, e.g. `#[deprecated]` or a
+    /// `@deprecated` tag
+    pub deprecation: Option<Deprecation>,
+
+    /// The head of the trait, e.g. `pub trait Name<T>: Bound where T: Clone`
+    pub head: String,
+
+    /// Associated types and constants, e.g. `type Output;`, kept as written
+    pub associated_items: Vec<FieldUnit>,
+
+    /// Whether the trait is declared `unsafe` and has a safety contract for implementors
+    pub is_unsafe: bool,
+
     /// The methods declared in the trait
     pub methods: Vec<FunctionUnit>,
 
@@ -379,7 +630,7 @@ pub struct TraitUnit {
 }
 
 /// Represents an implementation block in the code, not all languages need this
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ImplUnit {
     /// Attributes applied to the trait
     pub attributes: Vec<String>,
@@ -390,6 +641,9 @@ pub struct ImplUnit {
     /// impl head, e.g. impl Trait for Type or impl Type
     pub head: String,
 
+    /// Associated types and constants, e.g. `type Output = u32;`, kept as written
+    pub associated_items: Vec<FieldUnit>,
+
     /// The methods implemented in this block
     pub methods: Vec<FunctionUnit>,
 
@@ -397,6 +651,101 @@ pub struct ImplUnit {
     pub source: Option<String>,
 }
 
+/// Represents a macro definition in the code, e.g. `macro_rules!` or a macros 2.0 `macro`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct MacroUnit {
+    /// The name of the macro
+    pub name: String,
+
+    /// Attributes applied to the macro
+    pub attributes: Vec<String>,
+
+    /// The visibility of the macro. `macro_rules!` macros are public only when
+    /// marked `#[macro_export]`, macros 2.0 use their visibility modifier
+    pub visibility: Visibility,
+
+    /// The documentation for the macro
+    pub doc: Option<String>,
+
+    /// The deprecation of the macro, if it is marked deprecated, e.g. `#[deprecated]` or a
+    /// `@deprecated` tag
+    pub deprecation: Option<Deprecation>,
+
+    /// macro head, e.g. `macro_rules! name` or `pub macro name($x:expr)`
+    pub head: String,
+
+    /// Whether this is a `macro_rules!` macro rather than a macros 2.0 `macro`
+    pub is_macro_rules: bool,
+
+    /// The source code of the macro
+    pub source: Option<String>,
+}
+
+/// Represents a constant or static item in the code, e.g. `pub const MAX: usize = 16;`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ConstUnit {
+    /// The name of the constant
+    pub name: String,
+
+    /// Attributes applied to the constant
+    pub attributes: Vec<String>,
+
+    /// The visibility of the constant
+    pub visibility: Visibility,
+
+    /// The documentation for the constant
+    pub doc: Option<String>,
+
+    /// The deprecation of the constant, if it is marked deprecated, e.g. `#[deprecated]`
+    pub deprecation: Option<Deprecation>,
+
+    /// Whether this is a `static` rather than a `const` item
+    pub is_static: bool,
+
+    /// Whether this is a `static mut` item
+    pub is_mut: bool,
+
+    /// Whether this is a static declared by a `thread_local!` invocation
+    pub is_thread_local: bool,
+
+    /// The declared type, e.g. `usize`
+    pub type_annotation: Option<String>,
+
+    /// The initializer, e.g. `16`
+    pub value: Option<String>,
+
+    /// The source code of the constant
+    pub source: Option<String>,
+}
+
+/// Represents a type alias in the code, e.g. `pub type Result<T> = std::result::Result<T, Error>;`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct TypeAliasUnit {
+    /// The name of the type alias
+    pub name: String,
+
+    /// Attributes applied to the type alias
+    pub attributes: Vec<String>,
+
+    /// The visibility of the type alias
+    pub visibility: Visibility,
+
+    /// The documentation for the type alias
+    pub doc: Option<String>,
+
+    /// The deprecation of the type alias, if it is marked deprecated, e.g. `#[deprecated]`
+    pub deprecation: Option<Deprecation>,
+
+    /// The generic parameters, e.g. `<T, E = Error>`
+    pub generics: Option<String>,
+
+    /// The aliased type, e.g. `std::result::Result<T, E>`
+    pub aliased_type: Option<String>,
+
+    /// The source code of the type alias
+    pub source: Option<String>,
+}
+
 impl Visibility {
     pub fn as_str(&self, language: LanguageType) -> &str {
         match (self, language) {
@@ -407,20 +756,89 @@ impl Visibility {
             (_, LanguageType::TypeScript) => "",
             (_, LanguageType::Cpp) => "",
             (_, LanguageType::Go) => "",
+            (_, LanguageType::CSharp) => "",
+            (_, LanguageType::Php) => "",
             (_, LanguageType::Unknown) => "",
         }
     }
+
+    /// Whether this visibility is at least as visible as `min`, ordering
+    /// `Private < Restricted, Protected < Crate < Public`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::Visibility;
+    ///
+    /// assert!(Visibility::Public.is_at_least(&Visibility::Crate));
+    /// assert!(Visibility::Crate.is_at_least(&Visibility::Crate));
+    /// assert!(!Visibility::Private.is_at_least(&Visibility::Public));
+    /// ```
+    pub fn is_at_least(&self, min: &Visibility) -> bool {
+        self.rank() >= min.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Visibility::Private => 0,
+            Visibility::Restricted(_) | Visibility::Protected => 1,
+            Visibility::Crate => 2,
+            Visibility::Public => 3,
+        }
+    }
 }
 
 impl LanguageType {
-    pub fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             LanguageType::Rust => "rust",
             LanguageType::Python => "python",
             LanguageType::TypeScript => "ts",
             LanguageType::Cpp => "cpp",
             LanguageType::Go => "go",
+            LanguageType::CSharp => "csharp",
+            LanguageType::Php => "php",
             LanguageType::Unknown => "unknown",
         }
     }
+
+    /// The usual file extension of the language, `None` for `Unknown`
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            LanguageType::Rust => Some("rs"),
+            LanguageType::Python => Some("py"),
+            LanguageType::TypeScript => Some("ts"),
+            LanguageType::Cpp => Some("cpp"),
+            LanguageType::Go => Some("go"),
+            LanguageType::CSharp => Some("cs"),
+            LanguageType::Php => Some("php"),
+            LanguageType::Unknown => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visibility_serde_round_trip() {
+        let cases = [
+            (Visibility::Public, r#"{"kind":"public"}"#),
+            (Visibility::Private, r#"{"kind":"private"}"#),
+            (Visibility::Protected, r#"{"kind":"protected"}"#),
+            (Visibility::Crate, r#"{"kind":"crate"}"#),
+            (
+                Visibility::Restricted("super::module".to_string()),
+                r#"{"kind":"restricted","path":"super::module"}"#,
+            ),
+        ];
+
+        for (visibility, expected) in cases {
+            let json = serde_json::to_string(&visibility).unwrap();
+            assert_eq!(json, expected);
+            let decoded: Visibility = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded, visibility);
+        }
+    }
 }
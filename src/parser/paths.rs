@@ -0,0 +1,147 @@
+use super::{FieldUnit, FileUnit, FunctionUnit, ImplUnit, ModuleUnit, StructUnit, TraitUnit};
+
+/// Module prefixes stripped by [`shorten_paths`] when no custom list is configured.
+///
+/// `std::fmt::` and `std::io::` are deliberately missing: `fmt::Result` and
+/// `io::Result` would both become an ambiguous `Result`.
+pub const DEFAULT_SHORTEN_PREFIXES: &[&str] = &[
+    "std::borrow::",
+    "std::boxed::",
+    "std::cell::",
+    "std::collections::",
+    "std::option::",
+    "std::path::",
+    "std::rc::",
+    "std::result::",
+    "std::string::",
+    "std::sync::",
+    "std::vec::",
+];
+
+/// Strip the given module prefixes from paths in `text`, keeping the final segment.
+///
+/// A prefix is only stripped when it starts a path and is followed by the last
+/// segment of that path, so `std::collections::hash_map::Entry` stays untouched.
+/// String literals are copied verbatim.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{shorten_paths, DEFAULT_SHORTEN_PREFIXES};
+///
+/// let signature = "pub fn index(map: std::collections::HashMap<std::string::String, u32>)";
+/// assert_eq!(
+///     shorten_paths(signature, DEFAULT_SHORTEN_PREFIXES),
+///     "pub fn index(map: HashMap<String, u32>)"
+/// );
+/// assert_eq!(
+///     shorten_paths(r#"fn f(s: &str) { f("std::vec::Vec") }"#, &["std::vec::"]),
+///     r#"fn f(s: &str) { f("std::vec::Vec") }"#
+/// );
+/// ```
+pub fn shorten_paths<S: AsRef<str>>(text: &str, prefixes: &[S]) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut prev = None;
+    let mut i = 0;
+
+    while let Some(c) = text[i..].chars().next() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+        } else if c == '"' {
+            in_string = true;
+        } else if starts_path(prev) {
+            let rest = &text[i..];
+            if let Some(prefix) = prefixes
+                .iter()
+                .map(AsRef::as_ref)
+                .find(|prefix| is_final_segment(rest, prefix))
+            {
+                i += prefix.len();
+                continue;
+            }
+        }
+        output.push(c);
+        prev = Some(c);
+        i += c.len_utf8();
+    }
+    output
+}
+
+impl FileUnit {
+    /// Shorten the paths in the signatures and heads of all units, see [`shorten_paths`]
+    pub fn shorten_paths<S: AsRef<str>>(&mut self, prefixes: &[S]) {
+        shorten_functions(&mut self.functions, prefixes);
+        shorten_structs(&mut self.structs, prefixes);
+        shorten_traits(&mut self.traits, prefixes);
+        shorten_impls(&mut self.impls, prefixes);
+        shorten_modules(&mut self.modules, prefixes);
+    }
+}
+
+// A path may start anywhere an identifier may, except right after `::` or `.`
+fn starts_path(prev: Option<char>) -> bool {
+    prev.is_none_or(|prev| !(prev.is_alphanumeric() || matches!(prev, '_' | ':' | '.')))
+}
+
+fn is_final_segment(rest: &str, prefix: &str) -> bool {
+    let Some(segment) = rest.strip_prefix(prefix) else {
+        return false;
+    };
+    let end = segment
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(segment.len());
+    end > 0 && !segment[end..].starts_with("::")
+}
+
+fn shorten_functions<S: AsRef<str>>(functions: &mut [FunctionUnit], prefixes: &[S]) {
+    for function in functions {
+        if let Some(signature) = &mut function.signature {
+            *signature = shorten_paths(signature, prefixes);
+        }
+    }
+}
+
+fn shorten_fields<S: AsRef<str>>(fields: &mut [FieldUnit], prefixes: &[S]) {
+    for field in fields {
+        if let Some(source) = &mut field.source {
+            *source = shorten_paths(source, prefixes);
+        }
+    }
+}
+
+fn shorten_structs<S: AsRef<str>>(structs: &mut [StructUnit], prefixes: &[S]) {
+    for struct_unit in structs {
+        struct_unit.head = shorten_paths(&struct_unit.head, prefixes);
+        shorten_fields(&mut struct_unit.fields, prefixes);
+        shorten_functions(&mut struct_unit.methods, prefixes);
+    }
+}
+
+fn shorten_traits<S: AsRef<str>>(traits: &mut [TraitUnit], prefixes: &[S]) {
+    for trait_unit in traits {
+        trait_unit.head = shorten_paths(&trait_unit.head, prefixes);
+        shorten_fields(&mut trait_unit.associated_items, prefixes);
+        shorten_functions(&mut trait_unit.methods, prefixes);
+    }
+}
+
+fn shorten_impls<S: AsRef<str>>(impls: &mut [ImplUnit], prefixes: &[S]) {
+    for impl_unit in impls {
+        impl_unit.head = shorten_paths(&impl_unit.head, prefixes);
+        shorten_fields(&mut impl_unit.associated_items, prefixes);
+        shorten_functions(&mut impl_unit.methods, prefixes);
+    }
+}
+
+fn shorten_modules<S: AsRef<str>>(modules: &mut [ModuleUnit], prefixes: &[S]) {
+    for module in modules {
+        shorten_functions(&mut module.functions, prefixes);
+        shorten_structs(&mut module.structs, prefixes);
+        shorten_traits(&mut module.traits, prefixes);
+        shorten_impls(&mut module.impls, prefixes);
+        shorten_modules(&mut module.submodules, prefixes);
+    }
+}
@@ -0,0 +1,169 @@
+use super::{
+    CSharpParser, CppParser, GoParser, LanguageParser, LanguageType, PhpParser, PythonParser,
+    RustParser, TypeScriptParser,
+};
+use crate::Result;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A factory creating a fresh parser instance.
+pub type ParserFactory = Arc<dyn Fn() -> Result<Box<dyn LanguageParser>> + Send + Sync>;
+
+#[derive(Clone)]
+struct Registration {
+    language: LanguageType,
+    factory: ParserFactory,
+}
+
+/// Maps file extensions to the parsers used for them.
+///
+/// [`ParserRegistry::new`] comes with the built-in languages registered. Extra
+/// languages can be plugged in with [`ParserRegistry::register`] and handed to
+/// [`crate::CodeBank::with_registry`]. Parsers are usually built on a tree-sitter grammar,
+/// like the built-in ones, e.g. [`crate::GoParser`]; the parser below is a stub.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::{FileUnit, LanguageParser, LanguageType, ParserRegistry, Result};
+/// use std::path::Path;
+///
+/// struct TextParser;
+///
+/// impl LanguageParser for TextParser {
+///     fn parse_file(&mut self, file_path: &Path) -> Result<FileUnit> {
+///         Ok(FileUnit::new(file_path.to_path_buf()))
+///     }
+/// }
+///
+/// let mut registry = ParserRegistry::new();
+/// registry.register("txt", || Ok(Box::new(TextParser)));
+///
+/// assert!(registry.supports(Path::new("notes.txt")));
+/// assert_eq!(registry.language(Path::new("lib.rs")), LanguageType::Rust);
+/// assert_eq!(registry.language(Path::new("notes.txt")), LanguageType::Unknown);
+/// ```
+#[derive(Clone)]
+pub struct ParserRegistry {
+    parsers: HashMap<String, Registration>,
+}
+
+impl ParserRegistry {
+    /// Create a registry with the built-in languages registered
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register_builtin(&["rs"], LanguageType::Rust, || {
+            Ok(Box::new(RustParser::try_new()?))
+        });
+        registry.register_builtin(&["py"], LanguageType::Python, || {
+            Ok(Box::new(PythonParser::try_new()?))
+        });
+        registry.register_builtin(
+            &["ts", "tsx", "js", "jsx"],
+            LanguageType::TypeScript,
+            || Ok(Box::new(TypeScriptParser::try_new()?)),
+        );
+        registry.register_builtin(&["c", "h", "cpp", "hpp"], LanguageType::Cpp, || {
+            Ok(Box::new(CppParser::try_new()?))
+        });
+        registry.register_builtin(&["go"], LanguageType::Go, || {
+            Ok(Box::new(GoParser::try_new()?))
+        });
+        registry.register_builtin(&["cs"], LanguageType::CSharp, || {
+            Ok(Box::new(CSharpParser::try_new()?))
+        });
+        registry.register_builtin(&["php"], LanguageType::Php, || {
+            Ok(Box::new(PhpParser::try_new()?))
+        });
+        registry
+    }
+
+    /// Create a registry without any parser registered
+    pub fn empty() -> Self {
+        Self {
+            parsers: HashMap::new(),
+        }
+    }
+
+    /// Register a parser factory for files with the extension `ext` (without the dot).
+    ///
+    /// Replacing a built-in extension keeps its language type, so the built-in
+    /// formatting rules still apply. New extensions are formatted as
+    /// [`LanguageType::Unknown`].
+    pub fn register<F>(&mut self, ext: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn() -> Result<Box<dyn LanguageParser>> + Send + Sync + 'static,
+    {
+        let ext = ext.into();
+        let language = self
+            .parsers
+            .get(&ext)
+            .map_or(LanguageType::Unknown, |registration| registration.language);
+        self.parsers.insert(
+            ext,
+            Registration {
+                language,
+                factory: Arc::new(factory),
+            },
+        );
+        self
+    }
+
+    /// Whether a parser is registered for the extension of `path`
+    pub fn supports(&self, path: &Path) -> bool {
+        self.registration(path).is_some()
+    }
+
+    /// The language type of `path`, `Unknown` for unsupported or external languages
+    pub fn language(&self, path: &Path) -> LanguageType {
+        self.registration(path)
+            .map_or(LanguageType::Unknown, |registration| registration.language)
+    }
+
+    /// The parser factory registered for the extension of `path`
+    pub fn factory(&self, path: &Path) -> Option<&ParserFactory> {
+        self.registration(path)
+            .map(|registration| &registration.factory)
+    }
+
+    fn register_builtin<F>(&mut self, exts: &[&str], language: LanguageType, factory: F)
+    where
+        F: Fn() -> Result<Box<dyn LanguageParser>> + Send + Sync + 'static,
+    {
+        let factory: ParserFactory = Arc::new(factory);
+        for ext in exts {
+            self.parsers.insert(
+                ext.to_string(),
+                Registration {
+                    language,
+                    factory: factory.clone(),
+                },
+            );
+        }
+    }
+
+    fn registration(&self, path: &Path) -> Option<&Registration> {
+        path.extension()
+            .and_then(OsStr::to_str)
+            .and_then(|ext| self.parsers.get(ext))
+    }
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ParserRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut extensions: Vec<&String> = self.parsers.keys().collect();
+        extensions.sort();
+        f.debug_struct("ParserRegistry")
+            .field("extensions", &extensions)
+            .finish()
+    }
+}
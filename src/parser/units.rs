@@ -1,4 +1,8 @@
-use super::{FileUnit, ModuleUnit, Visibility};
+use super::{
+    CodeUnit, ConstUnit, FileUnit, FunctionUnit, ImplUnit, MacroUnit, ModuleUnit, Span, StructUnit,
+    TraitUnit, TypeAliasUnit, UnitKind, Visibility,
+};
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 /// Implementation of ModuleUnit.
@@ -57,8 +61,12 @@ impl ModuleUnit {
             traits: Vec::new(),
             impls: Vec::new(),
             submodules: Vec::new(),
+            macros: Vec::new(),
+            consts: Vec::new(),
+            type_aliases: Vec::new(),
             source: None,
             attributes: Vec::new(),
+            deprecation: None,
         }
     }
 }
@@ -82,6 +90,7 @@ impl ModuleUnit {
 /// assert!(file.structs.is_empty());
 /// assert!(file.traits.is_empty());
 /// assert!(file.impls.is_empty());
+/// assert!(file.macros.is_empty());
 /// assert!(file.source.is_none());
 /// ```
 impl FileUnit {
@@ -110,7 +119,540 @@ impl FileUnit {
             structs: Vec::new(),
             traits: Vec::new(),
             impls: Vec::new(),
+            macros: Vec::new(),
+            consts: Vec::new(),
+            type_aliases: Vec::new(),
             source: None,
+            parse_errors: Vec::new(),
+            omitted_items: 0,
         }
     }
 }
+
+impl FileUnit {
+    /// Remove the functions, structs, traits, macros, constants, type aliases, modules and methods less visible
+    /// than `min`, see [`Visibility::is_at_least`]. Impl blocks left without methods are
+    /// removed as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{FileUnit, FunctionUnit, Visibility};
+    /// use std::path::PathBuf;
+    ///
+    /// let mut file = FileUnit::new(PathBuf::from("lib.rs"));
+    /// for (name, visibility) in [("api", Visibility::Public), ("helper", Visibility::Private)] {
+    ///     file.functions.push(FunctionUnit {
+    ///         name: name.to_string(),
+    ///         visibility,
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// file.retain_visibility(&Visibility::Public);
+    /// assert_eq!(file.functions.len(), 1);
+    /// assert_eq!(file.functions[0].name, "api");
+    /// ```
+    pub fn retain_visibility(&mut self, min: &Visibility) {
+        self.functions.retain(|f| f.visibility.is_at_least(min));
+        retain_struct_visibility(&mut self.structs, min);
+        retain_trait_visibility(&mut self.traits, min);
+        retain_impl_visibility(&mut self.impls, min);
+        self.macros.retain(|m| m.visibility.is_at_least(min));
+        self.consts.retain(|c| c.visibility.is_at_least(min));
+        self.type_aliases.retain(|t| t.visibility.is_at_least(min));
+        retain_module_visibility(&mut self.modules, min);
+    }
+}
+
+fn retain_struct_visibility(structs: &mut Vec<StructUnit>, min: &Visibility) {
+    structs.retain(|s| s.visibility.is_at_least(min));
+    for struct_unit in structs {
+        struct_unit
+            .methods
+            .retain(|m| m.visibility.is_at_least(min));
+    }
+}
+
+fn retain_trait_visibility(traits: &mut Vec<TraitUnit>, min: &Visibility) {
+    traits.retain(|t| t.visibility.is_at_least(min));
+}
+
+fn retain_impl_visibility(impls: &mut Vec<ImplUnit>, min: &Visibility) {
+    impls.retain_mut(|impl_unit| {
+        let had_methods = !impl_unit.methods.is_empty();
+        impl_unit.methods.retain(|m| m.visibility.is_at_least(min));
+        !had_methods || !impl_unit.methods.is_empty()
+    });
+}
+
+fn retain_module_visibility(modules: &mut Vec<ModuleUnit>, min: &Visibility) {
+    modules.retain(|m| m.visibility.is_at_least(min));
+    for module in modules {
+        module.functions.retain(|f| f.visibility.is_at_least(min));
+        retain_struct_visibility(&mut module.structs, min);
+        retain_trait_visibility(&mut module.traits, min);
+        retain_impl_visibility(&mut module.impls, min);
+        module.macros.retain(|m| m.visibility.is_at_least(min));
+        module.consts.retain(|c| c.visibility.is_at_least(min));
+        module
+            .type_aliases
+            .retain(|t| t.visibility.is_at_least(min));
+        retain_module_visibility(&mut module.submodules, min);
+    }
+}
+
+impl FileUnit {
+    /// Sort the functions, structs, traits, macros, constants, type aliases and modules of the file and of its
+    /// inline modules by name, so that moving items around in the source does not change
+    /// the output. Impls are sorted with inherent impls first, then by trait name and head.
+    /// Methods keep their source order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{FileUnit, ImplUnit};
+    /// use std::path::PathBuf;
+    ///
+    /// let mut file = FileUnit::new(PathBuf::from("lib.rs"));
+    /// for head in ["impl Display for Point", "impl Debug for Point", "impl Point"] {
+    ///     file.impls.push(ImplUnit {
+    ///         head: head.to_string(),
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// file.sort_items();
+    /// let heads: Vec<_> = file.impls.iter().map(|i| i.head.as_str()).collect();
+    /// assert_eq!(heads, ["impl Point", "impl Debug for Point", "impl Display for Point"]);
+    /// ```
+    pub fn sort_items(&mut self) {
+        self.functions.sort_by(|a, b| a.name.cmp(&b.name));
+        self.structs.sort_by(|a, b| a.name.cmp(&b.name));
+        self.traits.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_impls(&mut self.impls);
+        self.macros.sort_by(|a, b| a.name.cmp(&b.name));
+        self.consts.sort_by(|a, b| a.name.cmp(&b.name));
+        self.type_aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_module_items(&mut self.modules);
+    }
+
+    /// Keep at most `max` top-level items, public items first and then by name, and add
+    /// the number of removed items to [`FileUnit::omitted_items`]. Impls are named by
+    /// their head. The kept items stay in source order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{FileUnit, FunctionUnit, Visibility};
+    /// use std::path::PathBuf;
+    ///
+    /// let mut file = FileUnit::new(PathBuf::from("lib.rs"));
+    /// for (name, visibility) in [("b", Visibility::Public), ("c", Visibility::Private), ("a", Visibility::Public)] {
+    ///     file.functions.push(FunctionUnit {
+    ///         name: name.to_string(),
+    ///         visibility,
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// file.truncate_items(2);
+    /// let names: Vec<_> = file.functions.iter().map(|f| f.name.as_str()).collect();
+    /// assert_eq!(names, ["b", "a"]);
+    /// assert_eq!(file.omitted_items, 1);
+    /// ```
+    pub fn truncate_items(&mut self, max: usize) {
+        let public = |visibility: &Visibility| *visibility == Visibility::Public;
+        let mut items: Vec<(bool, &str, UnitKind, usize)> = Vec::new();
+        items.extend(self.functions.iter().enumerate().map(|(idx, f)| {
+            (
+                public(&f.visibility),
+                f.name.as_str(),
+                UnitKind::Function,
+                idx,
+            )
+        }));
+        items.extend(self.structs.iter().enumerate().map(|(idx, s)| {
+            (
+                public(&s.visibility),
+                s.name.as_str(),
+                UnitKind::Struct,
+                idx,
+            )
+        }));
+        items.extend(
+            self.traits
+                .iter()
+                .enumerate()
+                .map(|(idx, t)| (public(&t.visibility), t.name.as_str(), UnitKind::Trait, idx)),
+        );
+        items.extend(
+            self.impls
+                .iter()
+                .enumerate()
+                .map(|(idx, i)| (true, i.head.as_str(), UnitKind::Impl, idx)),
+        );
+        items.extend(
+            self.macros
+                .iter()
+                .enumerate()
+                .map(|(idx, m)| (public(&m.visibility), m.name.as_str(), UnitKind::Macro, idx)),
+        );
+        items.extend(
+            self.consts
+                .iter()
+                .enumerate()
+                .map(|(idx, c)| (public(&c.visibility), c.name.as_str(), UnitKind::Const, idx)),
+        );
+        items.extend(self.type_aliases.iter().enumerate().map(|(idx, t)| {
+            (
+                public(&t.visibility),
+                t.name.as_str(),
+                UnitKind::TypeAlias,
+                idx,
+            )
+        }));
+        items.extend(self.modules.iter().enumerate().map(|(idx, m)| {
+            (
+                public(&m.visibility),
+                m.name.as_str(),
+                UnitKind::Module,
+                idx,
+            )
+        }));
+        if items.len() <= max {
+            return;
+        }
+
+        // Stable, so that items with the same name are kept in source order
+        items.sort_by(|a, b| (!a.0, a.1).cmp(&(!b.0, b.1)));
+        let omitted = items.len() - max;
+        let kept: HashSet<(UnitKind, usize)> = items[..max]
+            .iter()
+            .map(|&(_, _, kind, idx)| (kind, idx))
+            .collect();
+        retain_kept(&mut self.functions, UnitKind::Function, &kept);
+        retain_kept(&mut self.structs, UnitKind::Struct, &kept);
+        retain_kept(&mut self.traits, UnitKind::Trait, &kept);
+        retain_kept(&mut self.impls, UnitKind::Impl, &kept);
+        retain_kept(&mut self.macros, UnitKind::Macro, &kept);
+        retain_kept(&mut self.consts, UnitKind::Const, &kept);
+        retain_kept(&mut self.type_aliases, UnitKind::TypeAlias, &kept);
+        retain_kept(&mut self.modules, UnitKind::Module, &kept);
+        self.omitted_items += omitted;
+    }
+
+    /// Compare the signature of every function with the source text before its body and
+    /// record each mismatch in [`FileUnit::parse_errors`]. Functions without a body or
+    /// whose source does not end with it are not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::{FileUnit, FunctionUnit};
+    /// use std::path::PathBuf;
+    ///
+    /// let mut file = FileUnit::new(PathBuf::from("lib.rs"));
+    /// file.functions.push(FunctionUnit {
+    ///     name: "run".to_string(),
+    ///     source: Some("pub fn run(n: u32) {}".to_string()),
+    ///     signature: Some("pub fn run()".to_string()),
+    ///     body: Some("{}".to_string()),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// file.check_signatures();
+    /// assert_eq!(file.parse_errors.len(), 1);
+    /// ```
+    pub fn check_signatures(&mut self) {
+        let mut mismatches = Vec::new();
+        check_functions(&self.functions, &mut mismatches);
+        for struct_unit in &self.structs {
+            check_functions(&struct_unit.methods, &mut mismatches);
+        }
+        for trait_unit in &self.traits {
+            check_functions(&trait_unit.methods, &mut mismatches);
+        }
+        for impl_unit in &self.impls {
+            check_functions(&impl_unit.methods, &mut mismatches);
+        }
+        check_module_signatures(&self.modules, &mut mismatches);
+        self.parse_errors.extend(
+            mismatches
+                .into_iter()
+                .map(|message| (Span::default(), message)),
+        );
+    }
+}
+
+fn retain_kept<T>(units: &mut Vec<T>, kind: UnitKind, kept: &HashSet<(UnitKind, usize)>) {
+    let mut idx = 0;
+    units.retain(|_| {
+        idx += 1;
+        kept.contains(&(kind, idx - 1))
+    });
+}
+
+fn sort_impls(impls: &mut [ImplUnit]) {
+    impls.sort_by(|a, b| {
+        (a.trait_name().is_some(), a.trait_name(), &a.head).cmp(&(
+            b.trait_name().is_some(),
+            b.trait_name(),
+            &b.head,
+        ))
+    });
+}
+
+fn sort_module_items(modules: &mut [ModuleUnit]) {
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    for module in modules {
+        module.functions.sort_by(|a, b| a.name.cmp(&b.name));
+        module.structs.sort_by(|a, b| a.name.cmp(&b.name));
+        module.traits.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_impls(&mut module.impls);
+        module.macros.sort_by(|a, b| a.name.cmp(&b.name));
+        module.consts.sort_by(|a, b| a.name.cmp(&b.name));
+        module.type_aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_module_items(&mut module.submodules);
+    }
+}
+
+fn check_functions(functions: &[FunctionUnit], mismatches: &mut Vec<String>) {
+    for function in functions {
+        let (Some(source), Some(body), Some(signature)) =
+            (&function.source, &function.body, &function.signature)
+        else {
+            continue;
+        };
+        let Some(sliced) = source.trim_end().strip_suffix(body.trim()) else {
+            continue;
+        };
+        // Multi-line signatures may be reconstructed with different line breaks
+        let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+        let sliced = normalize(sliced);
+        if normalize(signature) != sliced {
+            mismatches.push(format!(
+                "signature of `{}` differs from its source: `{}` instead of `{}`",
+                function.name, signature, sliced
+            ));
+        }
+    }
+}
+
+fn check_module_signatures(modules: &[ModuleUnit], mismatches: &mut Vec<String>) {
+    for module in modules {
+        check_functions(&module.functions, mismatches);
+        for struct_unit in &module.structs {
+            check_functions(&struct_unit.methods, mismatches);
+        }
+        for trait_unit in &module.traits {
+            check_functions(&trait_unit.methods, mismatches);
+        }
+        for impl_unit in &module.impls {
+            check_functions(&impl_unit.methods, mismatches);
+        }
+        check_module_signatures(&module.submodules, mismatches);
+    }
+}
+
+impl ImplUnit {
+    /// The name of the implemented trait for `impl Trait for Type` blocks, without its
+    /// path or generic arguments. Returns `None` for inherent impls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use codebank::ImplUnit;
+    ///
+    /// let imp = ImplUnit {
+    ///     head: "impl<T: Clone> fmt::Display for Wrapper<T>".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(imp.trait_name(), Some("Display"));
+    ///
+    /// let inherent = ImplUnit {
+    ///     head: "impl Wrapper".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(inherent.trait_name(), None);
+    /// ```
+    pub fn trait_name(&self) -> Option<&str> {
+        let head = self.head.trim_start();
+        let head = head.strip_prefix("unsafe ").unwrap_or(head).trim_start();
+        let mut rest = head.strip_prefix("impl")?;
+
+        // Skip the impl generics, which may contain nested `<>` and ` for ` in bounds
+        if rest.starts_with('<') {
+            let mut depth = 0;
+            let end = rest.char_indices().find_map(|(i, c)| {
+                match c {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(i)
+            })?;
+            rest = &rest[end + 1..];
+        } else if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        let (trait_path, _) = rest.split_once(" for ")?;
+        let trait_path = trait_path.trim().trim_start_matches('!');
+        let trait_path = trait_path.split('<').next().unwrap_or(trait_path);
+        let name = trait_path.rsplit("::").next().unwrap_or(trait_path).trim();
+        (!name.is_empty()).then_some(name)
+    }
+}
+
+// Impl blocks have no visibility of their own; they are as visible as the implemented type
+static IMPL_VISIBILITY: Visibility = Visibility::Public;
+
+impl CodeUnit for FunctionUnit {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        UnitKind::Function
+    }
+}
+
+impl CodeUnit for StructUnit {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        // Enums are parsed into StructUnit, tell them apart by their head
+        if self.head.split_whitespace().any(|word| word == "enum") {
+            UnitKind::Enum
+        } else {
+            UnitKind::Struct
+        }
+    }
+}
+
+impl CodeUnit for TraitUnit {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        UnitKind::Trait
+    }
+}
+
+impl CodeUnit for ImplUnit {
+    fn name(&self) -> &str {
+        &self.head
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &IMPL_VISIBILITY
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        UnitKind::Impl
+    }
+}
+
+impl CodeUnit for ModuleUnit {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        UnitKind::Module
+    }
+}
+
+impl CodeUnit for MacroUnit {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        UnitKind::Macro
+    }
+}
+
+impl CodeUnit for ConstUnit {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        UnitKind::Const
+    }
+}
+
+impl CodeUnit for TypeAliasUnit {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    fn kind(&self) -> UnitKind {
+        UnitKind::TypeAlias
+    }
+}
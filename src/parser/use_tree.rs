@@ -0,0 +1,161 @@
+use super::attributes::split_args;
+
+/// A single path imported by a `use` declaration, see [`parse_use_tree`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsePath {
+    /// The segments of the imported path, e.g. `std`, `io`, `Read`. Globs end with `*`
+    pub path: Vec<String>,
+    /// The name the path is imported as, e.g. `Map` for `HashMap as Map` or `_` for an
+    /// unnamed import
+    pub alias: Option<String>,
+    /// Whether the path is a module imported by `self` in a list, e.g. `std::io` for
+    /// `std::io::{self, Read}`, which imports the module but not an item of the same name
+    pub is_self: bool,
+}
+
+/// Decompose a `use` declaration, or the use tree it imports, into its imported paths,
+/// in source order.
+///
+/// Nested lists are expanded with the prefix of each enclosing list. The visibility, the
+/// `use` keyword and the final `;` are optional. Empty lists import nothing.
+///
+/// # Examples
+///
+/// ```
+/// use codebank::parse_use_tree;
+///
+/// let paths = parse_use_tree("pub use a::{b::{self, c, d as _}, e::*};");
+/// let names: Vec<_> = paths.iter().map(|use_path| use_path.path.join("::")).collect();
+/// assert_eq!(names, ["a::b", "a::b::c", "a::b::d", "a::e::*"]);
+/// assert!(paths[0].is_self);
+/// assert_eq!(paths[2].alias.as_deref(), Some("_"));
+/// ```
+pub fn parse_use_tree(source: &str) -> Vec<UsePath> {
+    // Line breaks and indentation of long lists don't matter, e.g. around `as`
+    let source = source.split_whitespace().collect::<Vec<_>>().join(" ");
+    let tree = strip_use_keyword(source.trim_end_matches(';').trim_end());
+    let mut paths = Vec::new();
+    expand(Vec::new(), tree, &mut paths);
+    paths
+}
+
+// The use tree of a declaration, without its visibility and `use` keyword
+fn strip_use_keyword(source: &str) -> &str {
+    let mut rest = source;
+    if let Some(after) = rest.strip_prefix("pub ") {
+        rest = after;
+    } else if let Some(restricted) = rest.strip_prefix("pub(") {
+        // `pub(crate)`, `pub(in crate::api)`
+        rest = restricted
+            .split_once(')')
+            .map_or("", |(_, rest)| rest.trim_start());
+    }
+    match rest.strip_prefix("use ") {
+        Some(tree) => tree,
+        // Not a declaration, the source is the tree itself
+        None if rest.len() == source.len() => source,
+        None => "",
+    }
+}
+
+fn expand(prefix: Vec<String>, tree: &str, paths: &mut Vec<UsePath>) {
+    let tree = tree.trim();
+    let segments = |path: &str| {
+        path.split("::")
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    if let Some(open) = tree.find('{') {
+        let Some(list) = tree[open + 1..].strip_suffix('}') else {
+            return;
+        };
+        let prefix = [prefix, segments(&tree[..open])].concat();
+        for item in split_args(list) {
+            expand(prefix.clone(), &item, paths);
+        }
+        return;
+    }
+
+    let (path, alias) = match tree.split_once(" as ") {
+        Some((path, alias)) => (path, Some(alias.trim().to_string())),
+        None => (tree, None),
+    };
+    let mut path = [prefix, segments(path)].concat();
+    // `self` stands for the module of the list it is in
+    let is_self = path.len() > 1 && path.last().is_some_and(|last| last == "self");
+    if is_self {
+        path.pop();
+    }
+    if !path.is_empty() {
+        paths.push(UsePath {
+            path,
+            alias,
+            is_self,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(source: &str) -> Vec<String> {
+        parse_use_tree(source)
+            .iter()
+            .map(|use_path| {
+                let path = use_path.path.join("::");
+                match (&use_path.alias, use_path.is_self) {
+                    (Some(alias), _) => format!("{} as {}", path, alias),
+                    (None, true) => format!("{} (self)", path),
+                    (None, false) => path,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_use_tree() {
+        assert_eq!(paths("use std::io::Read;"), ["std::io::Read"]);
+        assert_eq!(
+            paths("use a::{b::{c, d as _}, e::*};"),
+            ["a::b::c", "a::b::d as _", "a::e::*"]
+        );
+        assert_eq!(
+            paths("pub(crate) use std::{fmt::{self, Display}, io::{self as stdio}};"),
+            ["std::fmt (self)", "std::fmt::Display", "std::io as stdio"]
+        );
+        assert_eq!(
+            paths("use ::serde::{de::{self, Deserialize as De}, Serialize};"),
+            [
+                "serde::de (self)",
+                "serde::de::Deserialize as De",
+                "serde::Serialize"
+            ]
+        );
+        assert_eq!(
+            paths("pub(in crate::api) use {alloc::vec::Vec, core::{*, mem::swap}};"),
+            ["alloc::vec::Vec", "core::*", "core::mem::swap"]
+        );
+        assert_eq!(
+            paths(
+                "use crate::{\n    api::{\n        Client,\n        Request as\n            Req,\n    },\n};"
+            ),
+            ["crate::api::Client", "crate::api::Request as Req"]
+        );
+        assert_eq!(paths("use super::*;"), ["super::*"]);
+    }
+
+    #[test]
+    fn test_parse_use_tree_forms() {
+        // A bare tree, without the declaration
+        assert_eq!(paths("a::{b, c as d}"), ["a::b", "a::c as d"]);
+        // Paths starting like a visibility or the `use` keyword
+        assert_eq!(paths("users::Admin"), ["users::Admin"]);
+        assert_eq!(paths("public::{self}"), ["public (self)"]);
+        assert!(paths("use a::{};").is_empty());
+        assert!(paths("use a::{b, c;").is_empty());
+        assert!(paths("pub(crate)").is_empty());
+    }
+}
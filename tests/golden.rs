@@ -0,0 +1,52 @@
+//! End-to-end golden tests: generate the code bank of `fixtures/sample_crate` with each
+//! strategy and compare the whole output with the snapshots in `tests/snapshots`.
+//!
+//! After an intended change of the output, regenerate the goldens and review them with
+//! [cargo-insta](https://insta.rs):
+//!
+//! ```sh
+//! cargo insta test --review --test golden
+//! ```
+//!
+//! or, without cargo-insta, accept every change with
+//! `INSTA_UPDATE=always cargo test --test golden` and review the diff of the `.snap` files.
+
+use codebank::{Bank, BankConfig, BankStrategy, CodeBank};
+use std::path::{Path, PathBuf};
+
+fn sample_crate() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join("sample_crate")
+}
+
+fn generate(strategy: BankStrategy) -> String {
+    let code_bank = CodeBank::try_new().unwrap();
+    let mut config = BankConfig::new(sample_crate(), strategy, vec![]);
+    // The provenance comment holds the absolute root directory and the crate version
+    config.include_provenance = false;
+    code_bank.generate(&config).unwrap()
+}
+
+#[test]
+fn golden_default() {
+    insta::assert_snapshot!(generate(BankStrategy::Default));
+}
+
+#[test]
+fn golden_no_tests() {
+    insta::assert_snapshot!(generate(BankStrategy::NoTests));
+}
+
+#[test]
+fn golden_summary() {
+    insta::assert_snapshot!(generate(BankStrategy::Summary));
+}
+
+#[test]
+fn golden_output_is_deterministic() {
+    assert_eq!(
+        generate(BankStrategy::Summary),
+        generate(BankStrategy::Summary)
+    );
+}